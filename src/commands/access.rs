@@ -0,0 +1,119 @@
+// src/commands/access.rs
+
+//! Availability rules for commands: admin restrictions, operator switches,
+//! chat scope and per-user cooldowns.
+
+use crate::commands::registry::{CommandSpec, Scope};
+use crate::config::BotConfig;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use teloxide::types::{Chat, UserId};
+
+/// Why a command may not be used right now.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Denial {
+    Disabled,
+    AdminOnly,
+    GroupsOnly,
+    CoolingDown(Duration),
+}
+
+impl fmt::Display for Denial {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Disabled => write!(f, "🚫 This command is disabled."),
+            Self::AdminOnly => write!(f, "🔒 This command is only available to bot admins."),
+            Self::GroupsOnly => write!(f, "👥 This command only works in group chats."),
+            Self::CoolingDown(left) => {
+                write!(f, "⏳ Slow down! Try again in {}s.", left.as_secs().max(1))
+            }
+        }
+    }
+}
+
+/// Checks whether `user` may see and use `spec` in `chat`.
+///
+/// Cooldowns are not considered here so help pages stay stable.
+pub fn check(
+    spec: &CommandSpec,
+    config: &BotConfig,
+    user: Option<UserId>,
+    chat: &Chat,
+) -> Result<(), Denial> {
+    if config.is_disabled(spec.name) {
+        return Err(Denial::Disabled);
+    }
+    if spec.admin_only && !user.is_some_and(|id| config.is_admin(id)) {
+        return Err(Denial::AdminOnly);
+    }
+    if spec.scope == Scope::GroupsOnly && chat.is_private() {
+        return Err(Denial::GroupsOnly);
+    }
+    Ok(())
+}
+
+/// When each user's cooldown on a rate-limited command ends.
+///
+/// Entries are dropped once they expire, so the map only ever holds users
+/// who are currently cooling down.
+#[derive(Debug, Default)]
+pub struct Cooldowns {
+    until: Mutex<HashMap<(UserId, &'static str), Instant>>,
+}
+
+impl Cooldowns {
+    /// Records a use of `spec` by `user`, or returns the time left to wait.
+    pub fn try_acquire(&self, spec: &'static CommandSpec, user: UserId) -> Result<(), Denial> {
+        self.try_acquire_at(spec, user, Instant::now())
+    }
+
+    fn try_acquire_at(
+        &self,
+        spec: &'static CommandSpec,
+        user: UserId,
+        now: Instant,
+    ) -> Result<(), Denial> {
+        let Some(cooldown) = spec.cooldown else {
+            return Ok(());
+        };
+
+        let mut until = self.until.lock().expect("cooldown lock poisoned");
+        until.retain(|_, end| *end > now);
+        if let Some(end) = until.get(&(user, spec.name)) {
+            return Err(Denial::CoolingDown(end.duration_since(now)));
+        }
+        until.insert((user, spec.name), now + cooldown);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::registry;
+
+    #[test]
+    fn test_cooldown_blocks_until_elapsed() {
+        let cooldowns = Cooldowns::default();
+        let spec = registry::find("currency").unwrap();
+        let cooldown = spec.cooldown.unwrap();
+        let user = UserId(1);
+        let start = Instant::now();
+
+        assert!(cooldowns.try_acquire_at(spec, user, start).is_ok());
+        assert!(matches!(
+            cooldowns.try_acquire_at(spec, user, start + cooldown / 2),
+            Err(Denial::CoolingDown(_))
+        ));
+        assert!(cooldowns.try_acquire_at(spec, UserId(2), start).is_ok());
+        assert!(
+            cooldowns
+                .try_acquire_at(spec, user, start + cooldown)
+                .is_ok()
+        );
+        // User 2's cooldown has run out and was evicted.
+        assert_eq!(cooldowns.until.lock().unwrap().len(), 1);
+    }
+}
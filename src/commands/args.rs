@@ -0,0 +1,640 @@
+// src/commands/args.rs
+
+//! Declarative, typed parsing of command arguments.
+//!
+//! Handlers declare a [`Signature`] describing their positional arguments.
+//! Parsing yields typed values or an [`ArgError`] that points at the
+//! offending token, and the same signature renders the usage line shown on
+//! help pages, so the two can't drift apart.
+
+use chrono::NaiveTime;
+use rust_decimal::Decimal;
+use std::fmt;
+use std::time::Duration;
+
+/// The type of value an argument accepts.
+#[derive(Debug, Clone, Copy)]
+pub enum ArgKind {
+    /// A positive number, e.g. `10` or `2,5`, read by the given function so
+    /// each command decides which decimal separators it accepts.
+    Amount(fn(&str) -> Option<Decimal>),
+    /// A whole number within `min..=max`, e.g. a count of days.
+    Number { min: u32, max: u32 },
+    /// A currency code, e.g. `USD`, checked and normalized by the given
+    /// function so this module needn't know which currencies exist.
+    Currency(fn(&str) -> Option<String>),
+    /// A place name. It takes the rest of the input (`New York`), or when
+    /// followed by other arguments, the words up to the first one that is
+    /// theirs or can't be part of a name.
+    City,
+    /// A span such as `90s`, `15m`, `2h`, `30d` or `1w`.
+    Duration,
+    /// A user given as `@username` or numeric ID.
+    User,
+    /// A time of day such as `09:00`.
+    Time,
+    /// One of a fixed set of keywords.
+    Choice(&'static [&'static str]),
+    /// A token checked and normalized by `parse`, for shapes specific to
+    /// one command such as `wind>50`.
+    Custom {
+        expected: &'static str,
+        parse: fn(&str) -> Option<String>,
+    },
+    /// Free text. When last, it takes the rest of the input.
+    Text,
+}
+
+impl ArgKind {
+    /// Kinds that swallow all remaining tokens when they come last.
+    fn is_greedy(self) -> bool {
+        matches!(self, Self::City | Self::Text)
+    }
+
+    /// Whether `token` is meant for this kind, even if it's out of range,
+    /// so a bad value is reported rather than handed to another argument.
+    fn claims(self, token: &str) -> bool {
+        match self {
+            Self::Number { .. } => token.chars().all(|c| c.is_ascii_digit()),
+            Self::City => token
+                .chars()
+                .all(|c| c.is_alphanumeric() || "-'.,()".contains(c)),
+            kind => parse_value(kind, token).is_some(),
+        }
+    }
+
+    fn expected(self) -> String {
+        match self {
+            Self::Amount(_) => "a positive number".to_string(),
+            Self::Number { min, max } => format!("a whole number from {} to {}", min, max),
+            Self::Currency(_) => "a currency code like USD or BTC".to_string(),
+            Self::City => "a city name".to_string(),
+            Self::Duration => "a duration like 30d, 12h or 15m".to_string(),
+            Self::User => "a @username".to_string(),
+            Self::Time => "a time like 09:00".to_string(),
+            Self::Choice(options) => format!("one of {}", options.join(", ")),
+            Self::Custom { expected, .. } => expected.to_string(),
+            Self::Text => "some text".to_string(),
+        }
+    }
+}
+
+/// How many tokens an argument takes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Arity {
+    Required,
+    Optional,
+    /// One or more values; must be the last argument.
+    Variadic,
+}
+
+/// A single declared argument.
+#[derive(Debug, Clone, Copy)]
+pub struct Arg {
+    pub name: &'static str,
+    pub kind: ArgKind,
+    pub arity: Arity,
+}
+
+impl Arg {
+    pub const fn required(name: &'static str, kind: ArgKind) -> Self {
+        Self {
+            name,
+            kind,
+            arity: Arity::Required,
+        }
+    }
+
+    pub const fn optional(name: &'static str, kind: ArgKind) -> Self {
+        Self {
+            name,
+            kind,
+            arity: Arity::Optional,
+        }
+    }
+
+    pub const fn variadic(name: &'static str, kind: ArgKind) -> Self {
+        Self {
+            name,
+            kind,
+            arity: Arity::Variadic,
+        }
+    }
+
+    fn usage(&self) -> String {
+        let label = match self.kind {
+            ArgKind::Choice(options) => options.join("|"),
+            _ => self.name.to_string(),
+        };
+        match self.arity {
+            Arity::Required => format!("<{}>", label),
+            Arity::Optional => format!("[{}]", label),
+            Arity::Variadic => format!("<{}>...", label),
+        }
+    }
+}
+
+/// The ordered argument list of a command.
+#[derive(Debug)]
+pub struct Signature {
+    pub args: &'static [Arg],
+}
+
+impl Signature {
+    pub const fn new(args: &'static [Arg]) -> Self {
+        Self { args }
+    }
+
+    /// Renders the argument synopsis, e.g. `<amount> <from> <to>`.
+    pub fn usage(&self) -> String {
+        self.args
+            .iter()
+            .map(Arg::usage)
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Parses whitespace-separated `input` against this signature.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`ArgError`] naming the first token that doesn't fit, a
+    /// missing required argument, or a surplus token.
+    pub fn parse(&self, input: &str) -> Result<Args, ArgError> {
+        let tokens: Vec<&str> = input.split_whitespace().collect();
+        let mut values = Vec::new();
+        let mut pos = 0;
+
+        for (i, arg) in self.args.iter().enumerate() {
+            let is_last = i + 1 == self.args.len();
+
+            if pos >= tokens.len() {
+                if arg.arity == Arity::Optional {
+                    continue;
+                }
+                return Err(ArgError::new(ArgErrorKind::Missing, Some(*arg), pos, None));
+            }
+
+            if is_last && arg.kind.is_greedy() && arg.arity != Arity::Variadic {
+                let rest = tokens[pos..].join(" ");
+                values.push((arg.name, Value::Text(rest)));
+                pos = tokens.len();
+                continue;
+            }
+
+            if arg.kind.is_greedy() && arg.arity != Arity::Variadic {
+                // Stop at the first word a later argument would take.
+                let later = &self.args[i + 1..];
+                let end = tokens[pos..]
+                    .iter()
+                    .position(|token| !arg.kind.claims(token) || claimed_by_later(later, token))
+                    .map_or(tokens.len(), |n| pos + n);
+                if end == pos {
+                    if arg.arity == Arity::Optional {
+                        continue;
+                    }
+                    return Err(ArgError::new(ArgErrorKind::Missing, Some(*arg), pos, None));
+                }
+                values.push((arg.name, Value::Text(tokens[pos..end].join(" "))));
+                pos = end;
+                continue;
+            }
+
+            if arg.arity == Arity::Variadic {
+                for token in &tokens[pos..] {
+                    let value = parse_value(arg.kind, token).ok_or_else(|| {
+                        ArgError::new(
+                            ArgErrorKind::Invalid,
+                            Some(*arg),
+                            pos,
+                            Some(token.to_string()),
+                        )
+                    })?;
+                    values.push((arg.name, value));
+                    pos += 1;
+                }
+                continue;
+            }
+
+            let token = tokens[pos];
+            match parse_value(arg.kind, token) {
+                Some(value) => {
+                    values.push((arg.name, value));
+                    pos += 1;
+                }
+                // A bad optional argument may belong to the next one instead.
+                None if arg.arity == Arity::Optional && !is_last && !arg.kind.claims(token) => {}
+                None => {
+                    return Err(ArgError::new(
+                        ArgErrorKind::Invalid,
+                        Some(*arg),
+                        pos,
+                        Some(token.to_string()),
+                    ));
+                }
+            }
+        }
+
+        if let Some(extra) = tokens.get(pos) {
+            return Err(ArgError::new(
+                ArgErrorKind::Unexpected,
+                None,
+                pos,
+                Some(extra.to_string()),
+            ));
+        }
+
+        Ok(Args { values })
+    }
+}
+
+/// Whether any of `later` could take `token`, looking no further than the
+/// first argument that must be given.
+fn claimed_by_later(later: &[Arg], token: &str) -> bool {
+    for arg in later {
+        if arg.kind.claims(token) {
+            return true;
+        }
+        if arg.arity != Arity::Optional {
+            break;
+        }
+    }
+    false
+}
+
+fn parse_value(kind: ArgKind, token: &str) -> Option<Value> {
+    match kind {
+        ArgKind::Amount(parse) => parse(token)
+            .filter(|amount| *amount > Decimal::ZERO)
+            .map(Value::Amount),
+        ArgKind::Number { min, max } => token
+            .parse()
+            .ok()
+            .filter(|n| (min..=max).contains(n))
+            .map(Value::Number),
+        ArgKind::Currency(validate) => validate(token).map(Value::Currency),
+        ArgKind::City | ArgKind::Text => {
+            let text = token.trim();
+            (!text.is_empty()).then(|| Value::Text(text.to_string()))
+        }
+        ArgKind::Duration => parse_duration(token).map(Value::Duration),
+        ArgKind::User => parse_user(token).map(Value::User),
+        ArgKind::Time => NaiveTime::parse_from_str(token, "%H:%M")
+            .ok()
+            .map(Value::Time),
+        ArgKind::Custom { parse, .. } => parse(token).map(Value::Text),
+        ArgKind::Choice(options) => options
+            .iter()
+            .find(|option| option.eq_ignore_ascii_case(token))
+            .map(|option| Value::Choice(option)),
+    }
+}
+
+/// Parses `<n><unit>` where unit is one of `s`, `m`, `h`, `d`, `w`.
+pub fn parse_duration(token: &str) -> Option<Duration> {
+    let token = token.to_ascii_lowercase();
+    let unit_at = token.find(|c: char| !c.is_ascii_digit())?;
+    let (number, unit) = token.split_at(unit_at);
+    let number: u64 = number.parse().ok().filter(|n| *n > 0)?;
+    let seconds = match unit {
+        "s" => 1,
+        "m" | "min" => 60,
+        "h" => 3_600,
+        "d" => 86_400,
+        "w" => 604_800,
+        _ => return None,
+    };
+    Some(Duration::from_secs(number.checked_mul(seconds)?))
+}
+
+fn parse_user(token: &str) -> Option<Mention> {
+    if let Some(name) = token.strip_prefix('@') {
+        let valid = name.len() >= 2 && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        return valid.then(|| Mention::Username(name.to_string()));
+    }
+    token.parse().ok().map(Mention::Id)
+}
+
+/// A user reference typed as an argument.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Mention {
+    Username(String),
+    Id(u64),
+}
+
+/// A parsed argument value.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Amount(Decimal),
+    Number(u32),
+    Currency(String),
+    Text(String),
+    Duration(Duration),
+    User(Mention),
+    Time(NaiveTime),
+    Choice(&'static str),
+}
+
+/// Parsed arguments, looked up by their declared name.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Args {
+    values: Vec<(&'static str, Value)>,
+}
+
+impl Args {
+    /// Every value bound to `name` (more than one for variadic arguments).
+    pub fn all<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Value> + 'a {
+        self.values
+            .iter()
+            .filter(move |(n, _)| *n == name)
+            .map(|(_, v)| v)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Value> {
+        self.values.iter().find(|(n, _)| *n == name).map(|(_, v)| v)
+    }
+
+    pub fn amount(&self, name: &str) -> Option<Decimal> {
+        match self.get(name)? {
+            Value::Amount(amount) => Some(*amount),
+            _ => None,
+        }
+    }
+
+    pub fn number(&self, name: &str) -> Option<u32> {
+        match self.get(name)? {
+            Value::Number(number) => Some(*number),
+            _ => None,
+        }
+    }
+
+    pub fn currency(&self, name: &str) -> Option<&str> {
+        match self.get(name)? {
+            Value::Currency(code) => Some(code),
+            _ => None,
+        }
+    }
+
+    /// The value of a `City`, `Custom` or `Text` argument.
+    pub fn text(&self, name: &str) -> Option<&str> {
+        match self.get(name)? {
+            Value::Text(text) => Some(text),
+            _ => None,
+        }
+    }
+
+    pub fn duration(&self, name: &str) -> Option<Duration> {
+        match self.get(name)? {
+            Value::Duration(duration) => Some(*duration),
+            _ => None,
+        }
+    }
+
+    pub fn user(&self, name: &str) -> Option<&Mention> {
+        match self.get(name)? {
+            Value::User(mention) => Some(mention),
+            _ => None,
+        }
+    }
+
+    pub fn time(&self, name: &str) -> Option<NaiveTime> {
+        match self.get(name)? {
+            Value::Time(time) => Some(*time),
+            _ => None,
+        }
+    }
+
+    pub fn choice(&self, name: &str) -> Option<&'static str> {
+        match self.get(name)? {
+            Value::Choice(choice) => Some(choice),
+            _ => None,
+        }
+    }
+}
+
+/// What went wrong with an argument.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArgErrorKind {
+    /// A required argument was not given.
+    Missing,
+    /// The token doesn't match the argument's type.
+    Invalid,
+    /// More tokens were given than the signature accepts.
+    Unexpected,
+}
+
+/// A parse failure pointing at a specific token.
+#[derive(Debug, Clone)]
+pub struct ArgError {
+    pub kind: ArgErrorKind,
+    /// The argument being parsed; `None` for surplus tokens.
+    pub arg: Option<Arg>,
+    /// Zero-based index of the offending token (or where it was expected).
+    pub position: usize,
+    pub token: Option<String>,
+}
+
+impl ArgError {
+    fn new(kind: ArgErrorKind, arg: Option<Arg>, position: usize, token: Option<String>) -> Self {
+        Self {
+            kind,
+            arg,
+            position,
+            token,
+        }
+    }
+
+    /// A token the signature accepts but the command can't use with the
+    /// other arguments given.
+    pub fn unexpected(position: usize, token: &str) -> Self {
+        Self::new(
+            ArgErrorKind::Unexpected,
+            None,
+            position,
+            Some(token.to_string()),
+        )
+    }
+
+    /// Renders the error, the input with the bad token marked, and the usage line.
+    pub fn render(&self, command: &str, input: &str, signature: &Signature) -> String {
+        let mut tokens: Vec<String> = input.split_whitespace().map(String::from).collect();
+        match (self.kind, self.arg) {
+            (ArgErrorKind::Missing, Some(arg)) => tokens.push(format!("»{}«", arg.usage())),
+            _ => {
+                if let Some(token) = tokens.get_mut(self.position) {
+                    *token = format!("»{}«", token);
+                }
+            }
+        }
+
+        format!(
+            "⚠️ {}\n/{} {}\nUsage: /{} {}",
+            self,
+            command,
+            tokens.join(" "),
+            command,
+            signature.usage()
+        )
+    }
+}
+
+impl fmt::Display for ArgError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let token = self.token.as_deref().unwrap_or_default();
+        match (self.kind, self.arg) {
+            (ArgErrorKind::Missing, Some(arg)) => write!(
+                f,
+                "Missing {}: expected {}.",
+                arg.usage(),
+                arg.kind.expected()
+            ),
+            (ArgErrorKind::Invalid, Some(arg)) => write!(
+                f,
+                "Argument {} ({}): '{}' is not {}.",
+                self.position + 1,
+                arg.usage(),
+                token,
+                arg.kind.expected()
+            ),
+            _ => write!(f, "Unexpected extra argument '{}'.", token),
+        }
+    }
+}
+
+impl std::error::Error for ArgError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn code(token: &str) -> Option<String> {
+        let code = token.to_ascii_uppercase();
+        ["EUR", "GBP", "USD"]
+            .contains(&code.as_str())
+            .then_some(code)
+    }
+
+    const SIG: Signature = Signature::new(&[
+        Arg::required("amount", ArgKind::Amount(|token| token.parse().ok())),
+        Arg::required("from", ArgKind::Currency(code)),
+        Arg::optional("unit", ArgKind::Choice(&["metric", "imperial"])),
+        Arg::required("to", ArgKind::Currency(code)),
+    ]);
+
+    #[test]
+    fn test_usage() {
+        assert_eq!(SIG.usage(), "<amount> <from> [metric|imperial] <to>");
+    }
+
+    #[test]
+    fn test_parse_typed_values() {
+        let args = SIG.parse("2.5 usd eur").unwrap();
+        assert_eq!(args.amount("amount"), Some(Decimal::new(25, 1)));
+        assert_eq!(args.currency("from"), Some("USD"));
+        assert_eq!(args.choice("unit"), None);
+        assert_eq!(args.currency("to"), Some("EUR"));
+
+        let args = SIG.parse("1 USD Imperial EUR").unwrap();
+        assert_eq!(args.choice("unit"), Some("imperial"));
+        assert!(SIG.parse("-5 usd eur").is_err());
+        assert!(SIG.parse("0 usd eur").is_err());
+    }
+
+    #[test]
+    fn test_errors_point_at_token() {
+        let err = SIG.parse("abc USD EUR").unwrap_err();
+        assert_eq!((err.kind, err.position), (ArgErrorKind::Invalid, 0));
+        assert_eq!(err.token.as_deref(), Some("abc"));
+
+        let err = SIG.parse("10 USD").unwrap_err();
+        assert_eq!(err.kind, ArgErrorKind::Missing);
+        assert_eq!(err.arg.map(|a| a.name), Some("to"));
+
+        let err = SIG.parse("10 USD EURO").unwrap_err();
+        assert_eq!((err.kind, err.position), (ArgErrorKind::Invalid, 2));
+
+        let err = SIG.parse("10 USD EUR GBP").unwrap_err();
+        assert_eq!((err.kind, err.position), (ArgErrorKind::Unexpected, 3));
+    }
+
+    #[test]
+    fn test_greedy_last_argument() {
+        const CITY: Signature = Signature::new(&[Arg::required("city", ArgKind::City)]);
+        let args = CITY.parse("  New   York ").unwrap();
+        assert_eq!(args.text("city"), Some("New York"));
+        assert_eq!(CITY.parse("").unwrap_err().kind, ArgErrorKind::Missing);
+    }
+
+    #[test]
+    fn test_variadic_last_argument() {
+        const RATES: Signature = Signature::new(&[
+            Arg::required("from", ArgKind::Currency(code)),
+            Arg::variadic("to", ArgKind::Currency(code)),
+        ]);
+        assert_eq!(RATES.usage(), "<from> <to>...");
+
+        let args = RATES.parse("usd eur gbp").unwrap();
+        let targets: Vec<_> = args.all("to").cloned().collect();
+        assert_eq!(
+            targets,
+            [Value::Currency("EUR".into()), Value::Currency("GBP".into())]
+        );
+
+        let err = RATES.parse("USD").unwrap_err();
+        assert_eq!(err.kind, ArgErrorKind::Missing);
+        let err = RATES.parse("USD EUR XYZ GBP").unwrap_err();
+        assert_eq!((err.kind, err.position), (ArgErrorKind::Invalid, 2));
+        assert_eq!(err.arg.map(|a| a.name), Some("to"));
+    }
+
+    #[test]
+    fn test_city_stops_at_later_arguments() {
+        const FORECAST: Signature = Signature::new(&[
+            Arg::optional("city", ArgKind::City),
+            Arg::optional("days", ArgKind::Number { min: 1, max: 3 }),
+            Arg::optional("view", ArgKind::Choice(&["today"])),
+        ]);
+        assert_eq!(FORECAST.usage(), "[city] [days] [today]");
+
+        let args = FORECAST.parse("Buenos Aires 2").unwrap();
+        assert_eq!(args.text("city"), Some("Buenos Aires"));
+        assert_eq!(args.number("days"), Some(2));
+        let args = FORECAST.parse("today").unwrap();
+        assert_eq!(
+            (args.text("city"), args.choice("view")),
+            (None, Some("today"))
+        );
+
+        // Out of range, but clearly meant as the day count.
+        let err = FORECAST.parse("Paris 7").unwrap_err();
+        assert_eq!((err.kind, err.position), (ArgErrorKind::Invalid, 1));
+        assert_eq!(err.arg.map(|a| a.name), Some("days"));
+
+        const ALERT: Signature = Signature::new(&[
+            Arg::required("city", ArgKind::City),
+            Arg::variadic("at", ArgKind::Time),
+        ]);
+        let args = ALERT.parse("New York 07:30 18:00").unwrap();
+        assert_eq!(args.text("city"), Some("New York"));
+        assert_eq!(args.all("at").count(), 2);
+        let err = ALERT.parse("07:30").unwrap_err();
+        assert_eq!(err.kind, ArgErrorKind::Missing);
+        assert_eq!(err.arg.map(|a| a.name), Some("city"));
+    }
+
+    #[test]
+    fn test_scalar_parsers() {
+        assert_eq!(
+            parse_duration("30d"),
+            Some(Duration::from_secs(30 * 86_400))
+        );
+        assert_eq!(parse_duration("15m"), Some(Duration::from_secs(900)));
+        assert_eq!(parse_duration("d"), None);
+        assert_eq!(parse_duration("0h"), None);
+        assert_eq!(
+            parse_user("@alice_1"),
+            Some(Mention::Username("alice_1".into()))
+        );
+        assert_eq!(parse_user("42"), Some(Mention::Id(42)));
+    }
+}
@@ -1,8 +1,163 @@
+use crate::commands::{Command, registry};
 use teloxide::prelude::*;
 use teloxide::requests::Requester;
+use teloxide::types::{InlineKeyboardButton, InlineKeyboardMarkup, Me};
+use teloxide::utils::command::BotCommands;
 
-pub async fn unrecognized(bot: Bot, msg: Message) -> ResponseResult<()> {
-    let response = "🤖 I didn't recognize that command.\nType /help to see what I can do.";
-    bot.send_message(msg.chat.id, response).await?;
+/// Callback data prefix for "Did you mean…?" buttons. A button explains its
+/// command rather than running it, since most commands need arguments.
+pub const SUGGESTION_PREFIX: &str = "suggest:";
+
+/// How many alternatives to offer at most.
+const MAX_SUGGESTIONS: usize = 3;
+
+/// Replies to unknown commands, suggesting the closest known ones.
+///
+/// Commands addressed to another bot (`/cmd@otherbot`) are ignored so the bot
+/// stays quiet in chats it shares with other bots.
+pub async fn unrecognized(bot: Bot, msg: Message, me: Me) -> ResponseResult<()> {
+    let Some(text) = msg.text() else {
+        return Ok(());
+    };
+    let token = text.split_whitespace().next().unwrap_or_default();
+    let token = token.trim_start_matches('/');
+
+    let typed = match token.split_once('@') {
+        Some((_, target)) if !target.eq_ignore_ascii_case(me.username()) => return Ok(()),
+        Some((command, _)) => command,
+        None => token,
+    };
+
+    let suggestions = suggest(typed);
+    if suggestions.is_empty() {
+        let response = "🤖 I didn't recognize that command.\nType /help to see what I can do.";
+        bot.send_message(msg.chat.id, response).await?;
+        return Ok(());
+    }
+
+    let options: Vec<String> = suggestions.iter().map(|s| format!("/{}", s)).collect();
+    let buttons = suggestions.iter().map(|name| {
+        vec![InlineKeyboardButton::callback(
+            format!("ℹ️ What is /{}?", name),
+            format!("{}{}", SUGGESTION_PREFIX, name),
+        )]
+    });
+
+    bot.send_message(
+        msg.chat.id,
+        format!("🤔 Did you mean {}?", options.join(" or ")),
+    )
+    .reply_markup(InlineKeyboardMarkup::new(buttons))
+    .await?;
+    Ok(())
+}
+
+/// Handles a tap on a suggestion button by showing what the command does.
+pub async fn handle_suggestion_callback(
+    bot: Bot,
+    q: CallbackQuery,
+    name: &str,
+) -> ResponseResult<()> {
+    bot.answer_callback_query(q.id.clone()).await?;
+
+    let Some(spec) = registry::find(name) else {
+        return Ok(());
+    };
+    let description = Command::bot_commands()
+        .into_iter()
+        .find(|c| c.command.trim_start_matches('/') == spec.name)
+        .map(|c| c.description)
+        .unwrap_or_default();
+
+    if let Some(message) = q.message {
+        bot.edit_message_text(
+            message.chat().id,
+            message.id(),
+            format!(
+                "👉 /{} — {}\nTap the command to run it.",
+                spec.name, description
+            ),
+        )
+        .await?;
+    }
     Ok(())
 }
+
+/// Ranks known commands and aliases by similarity to `typed`.
+///
+/// Prefix matches come first, then names within a small edit distance.
+/// Aliases are collapsed into their canonical command.
+pub fn suggest(typed: &str) -> Vec<&'static str> {
+    let typed = typed.to_lowercase();
+    if typed.is_empty() {
+        return Vec::new();
+    }
+    let max_distance = (typed.chars().count() / 3).max(1);
+
+    let mut scored: Vec<(usize, &'static str)> = registry::COMMANDS
+        .iter()
+        .filter_map(|spec| {
+            std::iter::once(&spec.name)
+                .chain(spec.aliases)
+                .filter_map(|candidate| {
+                    let is_prefix = typed.len() >= 2 && candidate.starts_with(&typed);
+                    let overshoots = candidate.len() >= 3 && typed.starts_with(*candidate);
+                    if is_prefix || overshoots {
+                        Some(0)
+                    } else {
+                        let distance = edit_distance(&typed, candidate);
+                        (distance <= max_distance).then_some(distance)
+                    }
+                })
+                .min()
+                .map(|score| (score, spec.name))
+        })
+        .collect();
+
+    scored.sort();
+    scored
+        .into_iter()
+        .take(MAX_SUGGESTIONS)
+        .map(|(_, name)| name)
+        .collect()
+}
+
+/// Levenshtein distance over Unicode scalar values.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(ca != *cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(row[j + 1] + 1);
+        }
+    }
+    row[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("weather", "weather"), 0);
+        assert_eq!(edit_distance("wether", "weather"), 1);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("", "abc"), 3);
+    }
+
+    #[test]
+    fn test_suggest() {
+        assert_eq!(suggest("wether"), vec!["weather"]);
+        assert_eq!(suggest("curency"), vec!["currency"]);
+        assert_eq!(suggest("leader"), vec!["leaderboard"]);
+        // Aliases resolve to the canonical command.
+        assert_eq!(suggest("convrt"), vec!["currency"]);
+        assert!(suggest("xyzzyplugh").is_empty());
+    }
+}
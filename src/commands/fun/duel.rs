@@ -0,0 +1,312 @@
+use crate::commands::args::{Arg, ArgKind, Mention, Signature};
+use crate::commands::fun::roll::DICE_ANIMATION;
+use crate::storage::{Player, Storage};
+use log::error;
+use std::sync::Arc;
+use teloxide::types::{
+    DiceEmoji, InlineKeyboardButton, InlineKeyboardMarkup, MessageEntityKind, User,
+};
+use teloxide::{prelude::*, types::Message};
+
+/// Callback data prefix of the accept and decline buttons under a challenge.
+pub const DUEL_PREFIX: &str = "duel:";
+
+/// Arguments accepted by `/duel`; the opponent may come from a reply instead.
+pub const ARGS: Signature = Signature::new(&[Arg::optional("opponent", ArgKind::User)]);
+
+/// Whether `/duel` parses `args`; a reply or text mention stands in for
+/// them.
+pub fn parses(msg: &Message, args: &str) -> bool {
+    find_opponent(msg).is_some() || ARGS.parse(args).is_ok()
+}
+
+/// Handles `/duel @user` (or `/duel` as a reply) by posting a challenge the
+/// opponent must accept. Once they do, both players roll a 🎲 and the higher
+/// value wins. Results feed the chat's `/leaderboard`.
+pub async fn handle_duel(
+    bot: Bot,
+    msg: Message,
+    args: String,
+    storage: Arc<Storage>,
+) -> ResponseResult<()> {
+    let Some(from) = msg.from.as_ref() else {
+        return Ok(());
+    };
+    let challenger = Player::from_user(from);
+
+    // Replies and text mentions carry the user directly; otherwise parse `@username`.
+    let opponent = match find_opponent(&msg) {
+        Some(user) => Some((Player::from_user(user), Target::Id(user.id.0))),
+        None => match ARGS.parse(&args) {
+            Ok(args) => args.user("opponent").and_then(|mention| match mention {
+                Mention::Username(username) => Some((
+                    Player::from_username(username),
+                    Target::Username(username.to_lowercase()),
+                )),
+                // Bare IDs can't be resolved to a display name.
+                Mention::Id(_) => None,
+            }),
+            Err(err) => {
+                bot.send_message(msg.chat.id, err.render("duel", &args, &ARGS))
+                    .await?;
+                return Ok(());
+            }
+        },
+    };
+
+    let (opponent, target) = match opponent {
+        Some((opponent, target)) if !opponent.is_same(&challenger) => (opponent, target),
+        Some(_) => {
+            bot.send_message(msg.chat.id, "🤺 You can't duel yourself!")
+                .await?;
+            return Ok(());
+        }
+        None => {
+            bot.send_message(
+                msg.chat.id,
+                "Usage: /duel @username\nOr reply to someone's message with /duel.",
+            )
+            .await?;
+            return Ok(());
+        }
+    };
+
+    let data = |action: &str| {
+        format!(
+            "{}{}:{}:{}",
+            DUEL_PREFIX,
+            action,
+            from.id.0,
+            target.encode()
+        )
+    };
+    let keyboard = InlineKeyboardMarkup::new([[
+        InlineKeyboardButton::callback("⚔️ Accept", data("accept")),
+        InlineKeyboardButton::callback("🏳️ Decline", data("decline")),
+    ]]);
+    let challenge = bot
+        .send_message(
+            msg.chat.id,
+            format!(
+                "⚔️ {} challenges {}! {}, do you accept?",
+                challenger.name, opponent.name, opponent.name
+            ),
+        )
+        .reply_markup(keyboard)
+        .await?;
+    storage
+        .pending_duels
+        .lock()
+        .await
+        .insert((msg.chat.id.0, challenge.id.0));
+    Ok(())
+}
+
+/// Who a challenge is addressed to, as carried in the button data.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Target {
+    Id(u64),
+    /// Lowercase username, for opponents named only by `@username`.
+    Username(String),
+}
+
+impl Target {
+    fn encode(&self) -> String {
+        match self {
+            Self::Id(id) => id.to_string(),
+            Self::Username(username) => format!("@{}", username),
+        }
+    }
+
+    fn decode(text: &str) -> Option<Self> {
+        match text.strip_prefix('@') {
+            Some(username) => Some(Self::Username(username.to_string())),
+            None => text.parse().ok().map(Self::Id),
+        }
+    }
+
+    fn is(&self, user: &User) -> bool {
+        match self {
+            Self::Id(id) => user.id.0 == *id,
+            Self::Username(username) => user
+                .username
+                .as_ref()
+                .is_some_and(|u| u.eq_ignore_ascii_case(username)),
+        }
+    }
+}
+
+/// Splits `accept:<challenger id>:<target>` into its parts.
+fn parse_callback(data: &str) -> Option<(bool, u64, Target)> {
+    let mut parts = data.splitn(3, ':');
+    let accept = match parts.next()? {
+        "accept" => true,
+        "decline" => false,
+        _ => return None,
+    };
+    let challenger = parts.next()?.parse().ok()?;
+    let target = Target::decode(parts.next()?)?;
+    Some((accept, challenger, target))
+}
+
+/// Handles the buttons under a challenge. Only the challenged player may
+/// accept; either player may call it off. Accepting rolls the dice.
+pub async fn handle_duel_callback(
+    bot: Bot,
+    q: CallbackQuery,
+    data: &str,
+    storage: Arc<Storage>,
+) -> ResponseResult<()> {
+    let (Some((accept, challenger_id, target)), Some(message)) =
+        (parse_callback(data), q.message.as_ref())
+    else {
+        bot.answer_callback_query(q.id.clone()).await?;
+        return Ok(());
+    };
+    let chat_id = message.chat().id;
+    let is_challenger = q.from.id.0 == challenger_id;
+    let is_opponent = target.is(&q.from) && !is_challenger;
+
+    let allowed = is_opponent || (is_challenger && !accept);
+    if !allowed {
+        bot.answer_callback_query(q.id.clone())
+            .text("This challenge isn't for you.")
+            .await?;
+        return Ok(());
+    }
+
+    let challenger = match bot.get_chat_member(chat_id, UserId(challenger_id)).await {
+        Ok(member) => Player::from_user(&member.user),
+        Err(err) => {
+            error!(
+                "Failed to look up duel challenger {}: {}",
+                challenger_id, err
+            );
+            bot.answer_callback_query(q.id.clone())
+                .text("The challenger can't be found anymore.")
+                .await?;
+            return Ok(());
+        }
+    };
+    // Only the first answer to reach this point settles the challenge.
+    let claimed = storage
+        .pending_duels
+        .lock()
+        .await
+        .remove(&(chat_id.0, message.id().0));
+    if !claimed {
+        bot.answer_callback_query(q.id.clone())
+            .text("This challenge is no longer open.")
+            .await?;
+        return Ok(());
+    }
+    let player = Player::from_user(&q.from);
+    bot.answer_callback_query(q.id.clone()).await?;
+
+    let text = match (accept, is_challenger) {
+        (true, _) => format!(
+            "⚔️ {} accepts {}'s challenge!",
+            player.name, challenger.name
+        ),
+        (false, true) => format!("🏳️ {} called off the duel.", challenger.name),
+        (false, false) => format!(
+            "🏳️ {} declined {}'s challenge.",
+            player.name, challenger.name
+        ),
+    };
+    // Editing the challenge removes its buttons.
+    if let Err(err) = bot.edit_message_text(chat_id, message.id(), text).await {
+        log::warn!("Failed to update duel challenge: {}", err);
+    }
+    if !accept {
+        return Ok(());
+    }
+
+    let first = bot.send_dice(chat_id).emoji(DiceEmoji::Dice).await?;
+    let second = bot.send_dice(chat_id).emoji(DiceEmoji::Dice).await?;
+    let (Some(a), Some(b)) = (first.dice(), second.dice()) else {
+        return Ok(());
+    };
+    let (a, b) = (a.value, b.value);
+
+    // Announce the winner once the dice settle without holding up this chat.
+    tokio::spawn(async move {
+        tokio::time::sleep(DICE_ANIMATION).await;
+        let reply = settle(&storage, chat_id.0, &challenger, &player, a, b).await;
+        if let Err(err) = bot.send_message(chat_id, reply).await {
+            error!("Failed to send duel result: {}", err);
+        }
+    });
+    Ok(())
+}
+
+/// Records the duel on the leaderboard and describes the outcome.
+async fn settle(
+    storage: &Storage,
+    chat_id: i64,
+    challenger: &Player,
+    opponent: &Player,
+    a: u8,
+    b: u8,
+) -> String {
+    match a.cmp(&b) {
+        std::cmp::Ordering::Equal => format!("🤝 Both rolled {} — it's a draw!", a),
+        ordering => {
+            let (winner, loser) = if ordering.is_gt() {
+                (challenger, opponent)
+            } else {
+                (opponent, challenger)
+            };
+            if let Err(err) = storage
+                .leaderboards
+                .update(|boards| boards.record_duel(chat_id, winner, loser))
+                .await
+            {
+                error!("Failed to save duel result: {}", err);
+            }
+            format!(
+                "🏆 {} wins {} to {} against {}!",
+                winner.name,
+                a.max(b),
+                a.min(b),
+                loser.name
+            )
+        }
+    }
+}
+
+/// The user the replied-to message or a text mention points at.
+fn find_opponent(msg: &Message) -> Option<&User> {
+    if let Some(user) = msg.reply_to_message().and_then(|m| m.from.as_ref()) {
+        if !user.is_bot {
+            return Some(user);
+        }
+    }
+
+    msg.entities()
+        .into_iter()
+        .flatten()
+        .find_map(|e| match &e.kind {
+            MessageEntityKind::TextMention { user } if !user.is_bot => Some(user),
+            _ => None,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_callback() {
+        assert_eq!(
+            parse_callback("accept:42:7"),
+            Some((true, 42, Target::Id(7)))
+        );
+        assert_eq!(
+            parse_callback("decline:42:@bob_1"),
+            Some((false, 42, Target::Username("bob_1".to_string())))
+        );
+        assert_eq!(parse_callback("roll:42:7"), None);
+        assert_eq!(parse_callback("accept:x:7"), None);
+    }
+}
@@ -0,0 +1,405 @@
+// src/commands/fun/joke/community.rs
+
+//! The local joke collection: `/addjoke` submissions, the admin review
+//! queue, 👍/👎 voting and `/joke top`.
+
+use crate::commands::args::{Arg, ArgKind, Signature};
+use crate::config::BotConfig;
+use crate::storage::{Storage, StoredJoke, Submitter};
+use log::error;
+use rand::seq::IndexedRandom;
+use std::sync::Arc;
+use teloxide::prelude::*;
+use teloxide::types::{InlineKeyboardButton, InlineKeyboardMarkup, Message};
+
+/// Callback data prefix for the 👍/👎 buttons.
+pub const VOTE_PREFIX: &str = "jokevote:";
+
+/// Callback data prefix for the approve/reject buttons sent to admins.
+pub const REVIEW_PREFIX: &str = "jokereview:";
+
+/// Arguments accepted by `/addjoke`.
+pub const ADDJOKE_ARGS: Signature = Signature::new(&[Arg::required("joke", ArgKind::Text)]);
+
+/// Longest joke accepted, in characters.
+const MAX_LENGTH: usize = 500;
+
+/// Submissions one user may have waiting for review.
+const MAX_PENDING_PER_USER: usize = 3;
+
+/// Jokes listed by `/joke top`.
+const TOP_LIMIT: usize = 10;
+
+/// Telegram's limit on a message's length, in UTF-16 code units.
+const MAX_MESSAGE_LENGTH: usize = 4096;
+
+/// Pending jokes shown at once by `/jokequeue`.
+const QUEUE_PAGE: usize = 5;
+
+/// Sends a random approved joke from the local collection with voting
+/// buttons. Returns `false` if the collection is empty.
+pub async fn send_random(bot: &Bot, chat_id: ChatId, storage: &Storage) -> ResponseResult<bool> {
+    let joke = storage
+        .jokes
+        .read(|j| j.approved().choose(&mut rand::rng()).map(|j| (*j).clone()))
+        .await;
+    let Some(joke) = joke else {
+        return Ok(false);
+    };
+    bot.send_message(chat_id, joke.text.as_str())
+        .reply_markup(vote_keyboard(&joke))
+        .await?;
+    Ok(true)
+}
+
+/// Lists the best-voted local jokes.
+pub async fn handle_top(bot: Bot, msg: Message, storage: Arc<Storage>) -> ResponseResult<()> {
+    let top = storage.jokes.read(|j| j.top(TOP_LIMIT)).await;
+    bot.send_message(msg.chat.id, format_top(&top)).await?;
+    Ok(())
+}
+
+/// Queues a joke for review and shows it to the bot admins.
+pub async fn handle_addjoke(
+    bot: Bot,
+    msg: Message,
+    text: String,
+    storage: Arc<Storage>,
+    config: Arc<BotConfig>,
+) -> ResponseResult<()> {
+    let Some(user) = msg.from.as_ref() else {
+        return Ok(());
+    };
+    let joke = match ADDJOKE_ARGS.parse(&text) {
+        Ok(args) => args.text("joke").unwrap_or_default().trim().to_string(),
+        Err(err) => {
+            bot.send_message(msg.chat.id, err.render("addjoke", &text, &ADDJOKE_ARGS))
+                .await?;
+            return Ok(());
+        }
+    };
+    if joke.chars().count() > MAX_LENGTH {
+        bot.send_message(
+            msg.chat.id,
+            format!("⚠️ Jokes can be at most {} characters long.", MAX_LENGTH),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    let submitter = Submitter {
+        user_id: user.id.0,
+        name: user.full_name(),
+    };
+    let submitted = storage
+        .jokes
+        .update(|j| {
+            if j.pending_from(submitter.user_id) >= MAX_PENDING_PER_USER {
+                return None;
+            }
+            let id = j.submit(joke, submitter);
+            j.get(id).cloned()
+        })
+        .await;
+    let joke = match submitted {
+        Ok(Some(joke)) => joke,
+        Ok(None) => {
+            bot.send_message(
+                msg.chat.id,
+                format!(
+                    "⏳ You already have {} jokes waiting for review.",
+                    MAX_PENDING_PER_USER
+                ),
+            )
+            .await?;
+            return Ok(());
+        }
+        Err(err) => {
+            error!("Failed to save joke submission: {}", err);
+            bot.send_message(msg.chat.id, "❌ Failed to save the joke.")
+                .await?;
+            return Ok(());
+        }
+    };
+
+    bot.send_message(
+        msg.chat.id,
+        format!(
+            "📬 Thanks! Joke #{} is waiting for an admin's review.",
+            joke.id
+        ),
+    )
+    .await?;
+
+    if config.admins.is_empty() {
+        log::warn!("No BOT_ADMINS configured to review joke #{}", joke.id);
+    }
+    for admin in &config.admins {
+        if let Err(err) = send_for_review(&bot, ChatId::from(*admin), &joke).await {
+            log::warn!(
+                "Failed to send joke #{} to admin {}: {}",
+                joke.id,
+                admin,
+                err
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Shows the oldest pending jokes with review buttons (admins only).
+pub async fn handle_jokequeue(bot: Bot, msg: Message, storage: Arc<Storage>) -> ResponseResult<()> {
+    let pending = storage.jokes.read(|j| j.pending()).await;
+    if pending.is_empty() {
+        bot.send_message(msg.chat.id, "🎉 No jokes waiting for review.")
+            .await?;
+        return Ok(());
+    }
+
+    for joke in pending.iter().take(QUEUE_PAGE) {
+        send_for_review(&bot, msg.chat.id, joke).await?;
+    }
+    if pending.len() > QUEUE_PAGE {
+        bot.send_message(
+            msg.chat.id,
+            format!("…and {} more after these.", pending.len() - QUEUE_PAGE),
+        )
+        .await?;
+    }
+    Ok(())
+}
+
+async fn send_for_review(bot: &Bot, chat_id: ChatId, joke: &StoredJoke) -> ResponseResult<()> {
+    let keyboard = InlineKeyboardMarkup::new([[
+        InlineKeyboardButton::callback(
+            "✅ Approve",
+            format!("{}{}:approve", REVIEW_PREFIX, joke.id),
+        ),
+        InlineKeyboardButton::callback("🗑 Reject", format!("{}{}:reject", REVIEW_PREFIX, joke.id)),
+    ]]);
+    bot.send_message(chat_id, format_review(joke))
+        .reply_markup(keyboard)
+        .await?;
+    Ok(())
+}
+
+/// Approves or rejects a submission and tells its author. Only bot admins
+/// may review.
+pub async fn handle_review_callback(
+    bot: Bot,
+    q: CallbackQuery,
+    data: &str,
+    storage: Arc<Storage>,
+    config: Arc<BotConfig>,
+) -> ResponseResult<()> {
+    let parsed = data
+        .split_once(':')
+        .and_then(|(id, verdict)| Some((id.parse::<u64>().ok()?, verdict == "approve")));
+    let (Some((id, approve)), Some(message)) = (parsed, q.message.as_ref()) else {
+        bot.answer_callback_query(q.id.clone()).await?;
+        return Ok(());
+    };
+    if !config.is_admin(q.from.id) {
+        bot.answer_callback_query(q.id.clone())
+            .text("Only bot admins can review jokes.")
+            .await?;
+        return Ok(());
+    }
+
+    let reviewed = match storage.jokes.update(|j| j.moderate(id, approve)).await {
+        Ok(reviewed) => reviewed,
+        Err(err) => {
+            error!("Failed to save joke review: {}", err);
+            bot.answer_callback_query(q.id.clone())
+                .text("Failed to save the review.")
+                .await?;
+            return Ok(());
+        }
+    };
+    let Some(joke) = reviewed else {
+        bot.answer_callback_query(q.id.clone())
+            .text("Already reviewed.")
+            .await?;
+        bot.edit_message_reply_markup(message.chat().id, message.id())
+            .await?;
+        return Ok(());
+    };
+
+    let verdict = if approve {
+        "✅ Approved"
+    } else {
+        "🗑 Rejected"
+    };
+    bot.answer_callback_query(q.id.clone())
+        .text(format!("{} joke #{}.", verdict, joke.id))
+        .await?;
+    bot.edit_message_text(
+        message.chat().id,
+        message.id(),
+        format!(
+            "{}\n\n{} by {}",
+            format_review(&joke),
+            verdict,
+            q.from.full_name()
+        ),
+    )
+    .await?;
+
+    if let Some(submitter) = &joke.submitter {
+        let notice = if approve {
+            format!(
+                "🎉 Your joke #{} was approved! Find it with /joke community.",
+                joke.id
+            )
+        } else {
+            format!("🙅 Your joke #{} wasn't approved this time.", joke.id)
+        };
+        let author = UserId(submitter.user_id);
+        if let Err(err) = bot.send_message(author, notice).await {
+            log::warn!("Failed to tell {} about joke #{}: {}", author, joke.id, err);
+        }
+    }
+    Ok(())
+}
+
+/// Records a 👍 or 👎 and refreshes the counts on the buttons.
+pub async fn handle_vote_callback(
+    bot: Bot,
+    q: CallbackQuery,
+    data: &str,
+    storage: Arc<Storage>,
+) -> ResponseResult<()> {
+    let parsed = data
+        .split_once(':')
+        .and_then(|(id, vote)| Some((id.parse::<u64>().ok()?, vote == "up")));
+    let (Some((id, up)), Some(message)) = (parsed, q.message.as_ref()) else {
+        bot.answer_callback_query(q.id.clone()).await?;
+        return Ok(());
+    };
+
+    let joke = match storage.jokes.update(|j| j.vote(id, q.from.id.0, up)).await {
+        Ok(Some(joke)) => joke,
+        Ok(None) => {
+            bot.answer_callback_query(q.id.clone())
+                .text("This joke can't be voted on anymore.")
+                .await?;
+            return Ok(());
+        }
+        Err(err) => {
+            error!("Failed to save joke vote: {}", err);
+            bot.answer_callback_query(q.id.clone())
+                .text("Failed to save your vote.")
+                .await?;
+            return Ok(());
+        }
+    };
+
+    let notice = match joke.votes.get(&q.from.id.0) {
+        Some(_) if up => "👍 Thanks!",
+        Some(_) => "👎 Noted.",
+        None => "Vote withdrawn.",
+    };
+    bot.answer_callback_query(q.id.clone()).text(notice).await?;
+    bot.edit_message_reply_markup(message.chat().id, message.id())
+        .reply_markup(vote_keyboard(&joke))
+        .await?;
+    Ok(())
+}
+
+fn vote_keyboard(joke: &StoredJoke) -> InlineKeyboardMarkup {
+    InlineKeyboardMarkup::new([[
+        InlineKeyboardButton::callback(
+            format!("👍 {}", joke.upvotes()),
+            format!("{}{}:up", VOTE_PREFIX, joke.id),
+        ),
+        InlineKeyboardButton::callback(
+            format!("👎 {}", joke.downvotes()),
+            format!("{}{}:down", VOTE_PREFIX, joke.id),
+        ),
+    ]])
+}
+
+fn format_review(joke: &StoredJoke) -> String {
+    let author = joke
+        .submitter
+        .as_ref()
+        .map_or("the bundled collection", |s| s.name.as_str());
+    format!("📝 Joke #{} from {}:\n\n{}", joke.id, author, joke.text)
+}
+
+/// The ranking, cut short once the next joke would overflow one message.
+fn format_top(jokes: &[StoredJoke]) -> String {
+    if jokes.is_empty() {
+        return "🏆 No votes yet. Vote with 👍/👎 on /joke community jokes.".to_string();
+    }
+    let length = |text: &str| text.encode_utf16().count();
+    // Room for the "…and N more." line.
+    let budget = MAX_MESSAGE_LENGTH - 20;
+    let mut text = "🏆 Top jokes".to_string();
+    for (rank, joke) in jokes.iter().enumerate() {
+        let entry = format!(
+            "\n\n{}. {}\n👍 {}  👎 {}",
+            rank + 1,
+            joke.text,
+            joke.upvotes(),
+            joke.downvotes()
+        );
+        if length(&text) + length(&entry) > budget {
+            text.push_str(&format!("\n\n…and {} more.", jokes.len() - rank));
+            break;
+        }
+        text.push_str(&entry);
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::JokeStore;
+
+    #[test]
+    fn test_format_top() {
+        assert!(format_top(&[]).starts_with("🏆 No votes yet"));
+
+        let mut store = JokeStore::default();
+        let submitter = Submitter {
+            user_id: 1,
+            name: "Alice".to_string(),
+        };
+        let id = store.submit(
+            "Why did the chicken cross the road?\nTo get to the other side.".to_string(),
+            submitter,
+        );
+        store.moderate(id, true);
+        store.vote(id, 1, true);
+        store.vote(id, 2, true);
+        store.vote(id, 3, false);
+        assert_eq!(
+            format_top(&store.top(TOP_LIMIT)),
+            "🏆 Top jokes\n\n\
+             1. Why did the chicken cross the road?\nTo get to the other side.\n👍 2  👎 1"
+        );
+    }
+
+    #[test]
+    fn test_format_top_fits_one_message() {
+        let mut store = JokeStore::default();
+        for user_id in 0..TOP_LIMIT as u64 {
+            let submitter = Submitter {
+                user_id,
+                name: "Alice".to_string(),
+            };
+            let id = store.submit("ha".repeat(MAX_LENGTH / 2), submitter);
+            store.moderate(id, true);
+            store.vote(id, user_id, true);
+        }
+
+        let top = store.top(TOP_LIMIT);
+        assert_eq!(top.len(), TOP_LIMIT);
+        let text = format_top(&top);
+        assert!(text.encode_utf16().count() <= MAX_MESSAGE_LENGTH);
+        assert!(text.contains("7. "));
+        assert!(text.ends_with("…and 3 more."));
+    }
+}
@@ -0,0 +1,207 @@
+// src/commands/fun/joke/daily.rs
+
+//! `/jotd`: one joke a day, the same in every chat. Subscribed chats get it
+//! at a set time from `jobs::joke_of_the_day`.
+
+use crate::commands::args::{Arg, ArgError, ArgKind, Signature};
+use crate::services::ApiService;
+use crate::services::joke::JokeRequest;
+use crate::storage::{JotdSubscription, REPEAT_WINDOW_DAYS, Storage};
+use chrono::{Days, NaiveDate, NaiveTime, Utc};
+use log::error;
+use rand::seq::IndexedRandom;
+use std::sync::Arc;
+use teloxide::{prelude::*, types::Message};
+
+/// Arguments accepted by `/jotd`; a time only goes with `subscribe`.
+pub const ARGS: Signature = Signature::new(&[
+    Arg::optional("action", ArgKind::Choice(&["subscribe", "unsubscribe"])),
+    Arg::optional("utc_time", ArgKind::Time),
+]);
+
+/// Delivery time when `/jotd subscribe` gives none.
+const DEFAULT_TIME: NaiveTime = NaiveTime::from_hms_opt(9, 0, 0).unwrap();
+
+/// JokeAPI draws before giving up on a joke that wasn't told recently.
+const MAX_ATTEMPTS: usize = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Action {
+    Show,
+    Subscribe(NaiveTime),
+    Unsubscribe,
+}
+
+pub async fn handle_jotd(
+    bot: Bot,
+    msg: Message,
+    text: String,
+    api: Arc<ApiService>,
+    storage: Arc<Storage>,
+) -> ResponseResult<()> {
+    let action = match parse(&text) {
+        Ok(action) => action,
+        Err(reply) => {
+            bot.send_message(msg.chat.id, reply).await?;
+            return Ok(());
+        }
+    };
+
+    let chat_id = msg.chat.id.0;
+    let now = Utc::now();
+    let reply = match action {
+        Action::Show => {
+            let subscription = storage.jotd.read(|j| j.get(chat_id).cloned()).await;
+            let footer = match subscription {
+                Some(s) => format!("Delivered here daily at {} UTC.", s.time.format("%H:%M")),
+                None => "Get it every day with /jotd subscribe 09:00.".to_string(),
+            };
+            match todays_joke(&api, &storage, now.date_naive()).await {
+                Some(joke) => format!("{}\n\n{}", format_jotd(&joke), footer),
+                None => "😓 Couldn't find today's joke. Try again later.".to_string(),
+            }
+        }
+        Action::Subscribe(time) => {
+            // Starting after today's time has passed waits for tomorrow, and
+            // before it doesn't catch up on yesterday's.
+            let today = now.date_naive();
+            let subscription = JotdSubscription {
+                chat_id,
+                time,
+                last_sent: Some(if now.time() >= time {
+                    today
+                } else {
+                    today - Days::new(1)
+                }),
+            };
+            match storage.jotd.update(|j| j.subscribe(subscription)).await {
+                Ok(replaced) => format!(
+                    "🃏 {} Every day at {} UTC this chat gets the joke of the day, \
+                     with no repeats within {} days.",
+                    if replaced { "Updated!" } else { "Subscribed!" },
+                    time.format("%H:%M"),
+                    REPEAT_WINDOW_DAYS
+                ),
+                Err(err) => {
+                    error!("Failed to save joke subscription: {}", err);
+                    "❌ Failed to save the subscription.".to_string()
+                }
+            }
+        }
+        Action::Unsubscribe => match storage.jotd.update(|j| j.unsubscribe(chat_id)).await {
+            Ok(true) => "🔕 Unsubscribed from the joke of the day.".to_string(),
+            Ok(false) => "This chat isn't subscribed to the joke of the day.".to_string(),
+            Err(err) => {
+                error!("Failed to remove joke subscription: {}", err);
+                "❌ Failed to unsubscribe.".to_string()
+            }
+        },
+    };
+    bot.send_message(msg.chat.id, reply).await?;
+    Ok(())
+}
+
+/// Whether `text` parses as `/jotd` arguments.
+pub fn parses(_msg: &Message, text: &str) -> bool {
+    parse(text).is_ok()
+}
+
+/// The action asked for, or the rendered usage error.
+fn parse(text: &str) -> Result<Action, String> {
+    let render = |err: ArgError| err.render("jotd", text, &ARGS);
+    let args = ARGS.parse(text).map_err(render)?;
+    match (args.choice("action"), args.time("utc_time")) {
+        (None, None) => Ok(Action::Show),
+        (Some("subscribe"), time) => Ok(Action::Subscribe(time.unwrap_or(DEFAULT_TIME))),
+        (Some(_), None) => Ok(Action::Unsubscribe),
+        // The time is the last word given.
+        (_, Some(_)) => {
+            let words: Vec<&str> = text.split_whitespace().collect();
+            let last = words.len() - 1;
+            Err(render(ArgError::unexpected(last, words[last])))
+        }
+    }
+}
+
+pub fn format_jotd(joke: &str) -> String {
+    format!("🃏 Joke of the day\n\n{}", joke)
+}
+
+/// The joke for `date`, picked on first use and then shared by everyone.
+///
+/// It is fetched once through JokeAPI, skipping jokes told in the last
+/// [`REPEAT_WINDOW_DAYS`] days; if JokeAPI fails or keeps repeating itself,
+/// the local collection provides one. `None` if neither has a fresh joke.
+pub async fn todays_joke(api: &ApiService, storage: &Storage, date: NaiveDate) -> Option<String> {
+    let cached = || storage.jotd.read(|j| j.joke_on(date).map(str::to_string));
+    if let Some(joke) = cached().await {
+        return Some(joke);
+    }
+    let _picking = storage.jotd_picking.lock().await;
+    if let Some(joke) = cached().await {
+        return Some(joke);
+    }
+
+    let mut picked = None;
+    for _ in 0..MAX_ATTEMPTS {
+        match api.get_joke(&JokeRequest::default()).await {
+            Ok(joke) if storage.jotd.read(|j| j.is_recent(&joke, date)).await => continue,
+            Ok(joke) => {
+                picked = Some(joke);
+                break;
+            }
+            Err(err) => {
+                log::warn!("Joke of the day from JokeAPI failed: {}", err);
+                break;
+            }
+        }
+    }
+    if picked.is_none() {
+        let local: Vec<String> = storage
+            .jokes
+            .read(|j| j.approved().iter().map(|j| j.text.clone()).collect())
+            .await;
+        let fresh: Vec<String> = storage
+            .jotd
+            .read(|j| {
+                local
+                    .into_iter()
+                    .filter(|t| !j.is_recent(t, date))
+                    .collect()
+            })
+            .await;
+        picked = fresh.choose(&mut rand::rng()).cloned();
+    }
+
+    let joke = picked?;
+    if let Err(err) = storage.jotd.update(|j| j.record(date, joke.clone())).await {
+        error!("Failed to save the joke of the day: {}", err);
+    }
+    Some(joke)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(parse(""), Ok(Action::Show));
+        assert_eq!(parse("Subscribe"), Ok(Action::Subscribe(DEFAULT_TIME)));
+        assert_eq!(
+            parse("subscribe 7:45"),
+            Ok(Action::Subscribe(
+                NaiveTime::from_hms_opt(7, 45, 0).unwrap()
+            ))
+        );
+        assert!(parse("subscribe 25:00").is_err());
+        assert_eq!(parse("unsubscribe"), Ok(Action::Unsubscribe));
+        assert!(parse("unsubscribe now").is_err());
+        assert!(
+            parse("unsubscribe 09:00")
+                .unwrap_err()
+                .contains("/jotd unsubscribe »09:00«")
+        );
+        assert!(parse("09:00").unwrap_err().contains("/jotd »09:00«"));
+    }
+}
@@ -0,0 +1,176 @@
+// src/commands/fun/joke/mod.rs
+
+//! `/joke [category]`: a JokeAPI joke filtered by the chat's settings, with
+//! buttons for another one from any category. The local collection answers
+//! `/joke community` and stands in when JokeAPI is down.
+
+use crate::commands::args::{Arg, ArgKind, Signature};
+use crate::services::joke::{JokeCategory, JokeRequest};
+use crate::services::{ApiService, ServiceError};
+use crate::storage::{ChatJokePrefs, Storage};
+use std::sync::Arc;
+use teloxide::prelude::*;
+use teloxide::types::{InlineKeyboardButton, InlineKeyboardMarkup, Message};
+
+pub mod community;
+pub mod daily;
+pub mod settings;
+
+/// Callback data prefix for the category buttons.
+pub const JOKE_PREFIX: &str = "joke:";
+
+/// Picks a joke from the local collection instead of JokeAPI.
+const COMMUNITY: &str = "community";
+
+/// Lists the local collection's best-voted jokes.
+const TOP: &str = "top";
+
+/// Everything `/joke` accepts: the JokeAPI categories, then [`COMMUNITY`]
+/// and [`TOP`].
+const CHOICES: [&str; JokeCategory::KEYS.len() + 2] = {
+    let mut choices = [COMMUNITY; JokeCategory::KEYS.len() + 2];
+    let mut i = 0;
+    while i < JokeCategory::KEYS.len() {
+        choices[i] = JokeCategory::KEYS[i];
+        i += 1;
+    }
+    choices[i + 1] = TOP;
+    choices
+};
+
+/// Arguments accepted by `/joke`.
+pub const ARGS: Signature = Signature::new(&[Arg::optional("category", ArgKind::Choice(&CHOICES))]);
+
+/// Buttons per keyboard row.
+const BUTTONS_PER_ROW: usize = 3;
+
+pub async fn handle_joke(
+    bot: Bot,
+    msg: Message,
+    text: String,
+    api: Arc<ApiService>,
+    storage: Arc<Storage>,
+) -> ResponseResult<()> {
+    let args = match ARGS.parse(&text) {
+        Ok(args) => args,
+        Err(err) => {
+            bot.send_message(msg.chat.id, err.render("joke", &text, &ARGS))
+                .await?;
+            return Ok(());
+        }
+    };
+    let choice = args.choice("category").unwrap_or("any");
+    match choice {
+        TOP => community::handle_top(bot, msg, storage).await,
+        COMMUNITY => send_community(&bot, msg.chat.id, &storage).await,
+        _ => {
+            let category = JokeCategory::parse(choice).unwrap_or(JokeCategory::Any);
+            send_joke(&bot, msg.chat.id, category, &api, &storage).await
+        }
+    }
+}
+
+/// Tells another joke from the category on the pressed button.
+pub async fn handle_joke_callback(
+    bot: Bot,
+    q: CallbackQuery,
+    data: &str,
+    api: Arc<ApiService>,
+    storage: Arc<Storage>,
+) -> ResponseResult<()> {
+    bot.answer_callback_query(q.id.clone()).await?;
+    let Some(message) = q.message.as_ref() else {
+        return Ok(());
+    };
+    let chat_id = message.chat().id;
+    match JokeCategory::parse(data) {
+        Some(category) => send_joke(&bot, chat_id, category, &api, &storage).await,
+        None if data == COMMUNITY => send_community(&bot, chat_id, &storage).await,
+        None => Ok(()),
+    }
+}
+
+async fn send_community(bot: &Bot, chat_id: ChatId, storage: &Storage) -> ResponseResult<()> {
+    if !community::send_random(bot, chat_id, storage).await? {
+        bot.send_message(chat_id, "📭 No jokes yet. Send one in with /addjoke.")
+            .await?;
+    }
+    Ok(())
+}
+
+async fn send_joke(
+    bot: &Bot,
+    chat_id: ChatId,
+    category: JokeCategory,
+    api: &ApiService,
+    storage: &Storage,
+) -> ResponseResult<()> {
+    let prefs = storage.joke_prefs.read(|p| p.get(chat_id.0)).await;
+    if category.is_unsafe() && prefs.safe_mode {
+        bot.send_message(
+            chat_id,
+            "🙈 Dark jokes are off while safe mode is on (see /jokesettings).",
+        )
+        .await?;
+        return Ok(());
+    }
+
+    let joke = match api.get_joke(&JokeRequest::new(category, &prefs)).await {
+        Ok(joke) => joke,
+        Err(err @ ServiceError::NotFound { .. }) => {
+            log::info!("No JokeAPI joke for chat {}: {}", chat_id, err);
+            bot.send_message(
+                chat_id,
+                "🤷 No joke fits this category and the chat's /jokesettings. Try another category.",
+            )
+            .await?;
+            return Ok(());
+        }
+        Err(err) => {
+            log::warn!("JokeAPI failed, using the local collection: {}", err);
+            if !community::send_random(bot, chat_id, storage).await? {
+                bot.send_message(chat_id, "😓 Couldn't fetch a joke. Try again later.")
+                    .await?;
+            }
+            return Ok(());
+        }
+    };
+    bot.send_message(chat_id, joke)
+        .reply_markup(category_keyboard(&prefs))
+        .await?;
+    Ok(())
+}
+
+/// One button per category the chat's settings allow, plus the local
+/// collection.
+fn category_keyboard(prefs: &ChatJokePrefs) -> InlineKeyboardMarkup {
+    let mut buttons: Vec<_> = JokeCategory::ALL
+        .into_iter()
+        .filter(|c| !(c.is_unsafe() && prefs.safe_mode))
+        .map(|c| {
+            InlineKeyboardButton::callback(
+                format!("{} {}", c.emoji(), c.label()),
+                format!("{}{}", JOKE_PREFIX, c.key()),
+            )
+        })
+        .collect();
+    buttons.push(InlineKeyboardButton::callback(
+        "👥 Community",
+        format!("{}{}", JOKE_PREFIX, COMMUNITY),
+    ));
+    InlineKeyboardMarkup::new(buttons.chunks(BUTTONS_PER_ROW).map(<[_]>::to_vec))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_args_cover_every_category() {
+        let ArgKind::Choice(choices) = ARGS.args[0].kind else {
+            panic!("category should be a choice");
+        };
+        assert_eq!(&choices[..JokeCategory::KEYS.len()], JokeCategory::KEYS);
+        assert_eq!(&choices[JokeCategory::KEYS.len()..], [COMMUNITY, TOP]);
+    }
+}
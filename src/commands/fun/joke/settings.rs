@@ -0,0 +1,233 @@
+// src/commands/fun/joke/settings.rs
+
+//! `/jokesettings`: a chat's joke language, excluded content and safe mode.
+//! They filter JokeAPI jokes only; the local collection is English and
+//! screened by the bot admins who approve each joke.
+
+use crate::commands::args::{Arg, ArgError, ArgKind, Signature, Value};
+use crate::config::BotConfig;
+use crate::services::joke::LANGUAGES;
+use crate::storage::{ChatJokePrefs, JokeFlag, Storage};
+use log::error;
+use std::sync::Arc;
+use teloxide::prelude::*;
+use teloxide::types::{Message, UserId};
+
+/// Arguments accepted by `/jokesettings` to change a setting; without any it
+/// shows them. The value is checked against the setting's own signature.
+pub const ARGS: Signature = Signature::new(&[
+    Arg::required(
+        "setting",
+        ArgKind::Choice(&["lang", "language", "block", "allow", "safe"]),
+    ),
+    Arg::optional("value", ArgKind::Text),
+]);
+
+const LANG_ARGS: Signature = Signature::new(&[Arg::required("code", ArgKind::Choice(LANGUAGES))]);
+
+/// Flag names, plus `all` for every flag.
+const FLAG_NAMES: &[&str] = &[
+    "all",
+    "nsfw",
+    "religious",
+    "political",
+    "racist",
+    "sexist",
+    "explicit",
+];
+
+const FLAG_ARGS: Signature = Signature::new(&[Arg::variadic("flag", ArgKind::Choice(FLAG_NAMES))]);
+
+const SAFE_ARGS: Signature =
+    Signature::new(&[Arg::required("mode", ArgKind::Choice(&["on", "off"]))]);
+
+/// A requested change to the chat's settings.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Change {
+    Language(String),
+    Block(Vec<JokeFlag>),
+    Allow(Vec<JokeFlag>),
+    SafeMode(bool),
+}
+
+/// Shows or changes the chat's joke settings. In groups, only chat admins
+/// and bot admins may turn safe mode off.
+pub async fn handle_jokesettings(
+    bot: Bot,
+    msg: Message,
+    text: String,
+    storage: Arc<Storage>,
+    config: Arc<BotConfig>,
+) -> ResponseResult<()> {
+    let chat_id = msg.chat.id;
+    let change = match parse(&text) {
+        Ok(Some(change)) => change,
+        Ok(None) => {
+            let prefs = storage.joke_prefs.read(|p| p.get(chat_id.0)).await;
+            bot.send_message(chat_id, describe(&prefs)).await?;
+            return Ok(());
+        }
+        Err(reply) => {
+            bot.send_message(chat_id, reply).await?;
+            return Ok(());
+        }
+    };
+
+    if change == Change::SafeMode(false) && !msg.chat.is_private() {
+        let user = msg.from.as_ref().map(|u| u.id);
+        if !may_disable_safe_mode(&bot, chat_id, user, &config).await {
+            bot.send_message(chat_id, "🔒 Only group admins can turn safe mode off.")
+                .await?;
+            return Ok(());
+        }
+    }
+
+    let result = storage
+        .joke_prefs
+        .update(|p| {
+            match &change {
+                Change::Language(code) => p.set_language(chat_id.0, code),
+                Change::Block(flags) => p.set_blocked(chat_id.0, flags, true),
+                Change::Allow(flags) => p.set_blocked(chat_id.0, flags, false),
+                Change::SafeMode(enabled) => p.set_safe_mode(chat_id.0, *enabled),
+            }
+            p.get(chat_id.0)
+        })
+        .await;
+    let reply = match result {
+        Ok(prefs) => format!("✅ Saved.\n\n{}", describe(&prefs)),
+        Err(err) => {
+            error!("Failed to save joke settings: {}", err);
+            "❌ Failed to save the joke settings.".to_string()
+        }
+    };
+    bot.send_message(chat_id, reply).await?;
+    Ok(())
+}
+
+/// Bot admins anywhere, and the group's own administrators.
+async fn may_disable_safe_mode(
+    bot: &Bot,
+    chat_id: ChatId,
+    user: Option<UserId>,
+    config: &BotConfig,
+) -> bool {
+    let Some(user) = user else {
+        return false;
+    };
+    if config.is_admin(user) {
+        return true;
+    }
+    match bot.get_chat_member(chat_id, user).await {
+        Ok(member) => member.is_privileged(),
+        Err(err) => {
+            error!("Failed to look up chat member {}: {}", user, err);
+            false
+        }
+    }
+}
+
+/// Whether `text` parses as `/jokesettings` arguments.
+pub fn parses(_msg: &Message, text: &str) -> bool {
+    parse(text).is_ok()
+}
+
+/// The change asked for, `None` to show the settings, or the rendered
+/// usage error.
+fn parse(text: &str) -> Result<Option<Change>, String> {
+    if text.trim().is_empty() {
+        return Ok(None);
+    }
+    let args = ARGS
+        .parse(text)
+        .map_err(|err| err.render("jokesettings", text, &ARGS))?;
+    let setting = args.choice("setting").unwrap_or_default();
+    let value = args.text("value").unwrap_or_default();
+    // Errors point into the value, under the setting's own usage line.
+    let render = |err: ArgError, sub: &Signature| {
+        err.render(&format!("jokesettings {}", setting), value, sub)
+    };
+
+    match setting {
+        "lang" | "language" => {
+            let args = LANG_ARGS.parse(value).map_err(|e| render(e, &LANG_ARGS))?;
+            let code = args.choice("code").unwrap_or_default();
+            Ok(Some(Change::Language(code.to_string())))
+        }
+        "safe" => {
+            let args = SAFE_ARGS.parse(value).map_err(|e| render(e, &SAFE_ARGS))?;
+            Ok(Some(Change::SafeMode(args.choice("mode") == Some("on"))))
+        }
+        action => {
+            let args = FLAG_ARGS.parse(value).map_err(|e| render(e, &FLAG_ARGS))?;
+            let flags = flags(args.all("flag").filter_map(|v| match v {
+                Value::Choice(name) => Some(*name),
+                _ => None,
+            }));
+            Ok(Some(if action == "block" {
+                Change::Block(flags)
+            } else {
+                Change::Allow(flags)
+            }))
+        }
+    }
+}
+
+/// The flags named; `all` stands for every flag.
+fn flags<'a>(names: impl Iterator<Item = &'a str>) -> Vec<JokeFlag> {
+    let names: Vec<_> = names.collect();
+    if names.contains(&"all") {
+        return JokeFlag::ALL.to_vec();
+    }
+    names.into_iter().filter_map(JokeFlag::parse).collect()
+}
+
+fn describe(prefs: &ChatJokePrefs) -> String {
+    let blocked = if prefs.blacklist.is_empty() {
+        "nothing".to_string()
+    } else {
+        let names: Vec<_> = prefs.blacklist.iter().map(|f| f.name()).collect();
+        names.join(", ")
+    };
+    format!(
+        "🃏 Joke settings for this chat\n\
+         Language: {}\n\
+         Blocked: {}\n\
+         Safe mode: {}\n\
+         These filter JokeAPI jokes; /joke community jokes are screened by the bot admins instead.",
+        prefs.language,
+        blocked,
+        if prefs.safe_mode { "on" } else { "off" }
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(parse("  "), Ok(None));
+        assert_eq!(
+            parse("lang DE"),
+            Ok(Some(Change::Language("de".to_string())))
+        );
+        assert!(parse("lang xx").is_err());
+        assert_eq!(
+            parse("block Political religious"),
+            Ok(Some(Change::Block(vec![
+                JokeFlag::Political,
+                JokeFlag::Religious
+            ])))
+        );
+        assert_eq!(
+            parse("allow all"),
+            Ok(Some(Change::Allow(JokeFlag::ALL.to_vec())))
+        );
+        assert!(parse("block gory").unwrap_err().contains("»gory«"));
+        assert!(parse("block").is_err());
+        assert_eq!(parse("safe off"), Ok(Some(Change::SafeMode(false))));
+        assert!(parse("safe maybe").is_err());
+        assert!(parse("colour blue").is_err());
+    }
+}
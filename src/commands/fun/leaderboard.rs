@@ -0,0 +1,51 @@
+use crate::storage::Storage;
+use std::sync::Arc;
+use teloxide::{prelude::*, types::Message};
+
+/// How many players `/leaderboard` lists.
+const TOP_PLAYERS: usize = 10;
+
+/// Shows the chat's dice-game rankings: duel record, streaks and jackpots.
+pub async fn handle_leaderboard(
+    bot: Bot,
+    msg: Message,
+    storage: Arc<Storage>,
+) -> ResponseResult<()> {
+    let ranking = storage
+        .leaderboards
+        .read(|boards| boards.ranking(msg.chat.id.0))
+        .await;
+
+    if ranking.is_empty() {
+        bot.send_message(
+            msg.chat.id,
+            "🏆 No games played here yet. Start one with /duel or /roll 🎰!",
+        )
+        .await?;
+        return Ok(());
+    }
+
+    let mut text = String::from("🏆 Leaderboard\n");
+    for (rank, player) in ranking.iter().take(TOP_PLAYERS).enumerate() {
+        let medal = match rank {
+            0 => "🥇".to_string(),
+            1 => "🥈".to_string(),
+            2 => "🥉".to_string(),
+            n => format!("{}.", n + 1),
+        };
+        text.push_str(&format!(
+            "\n{} {} — {}W/{}L, streak {} (best {}), 🎰 {}/{}",
+            medal,
+            player.name,
+            player.duels_won,
+            player.duels_lost,
+            player.current_streak,
+            player.best_streak,
+            player.jackpots,
+            player.spins,
+        ));
+    }
+
+    bot.send_message(msg.chat.id, text).await?;
+    Ok(())
+}
@@ -1,4 +1,6 @@
 // src/commands/fun/mod.rs
 
+pub mod duel;
 pub mod joke;
+pub mod leaderboard;
 pub mod roll;
\ No newline at end of file
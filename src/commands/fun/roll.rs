@@ -1,7 +1,540 @@
+use crate::storage::{Player, Storage};
+use log::error;
+use rand::Rng;
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+use teloxide::sugar::request::RequestReplyExt;
+use teloxide::types::DiceEmoji;
 use teloxide::{prelude::*, types::Message};
 
-// Sends Telegram's animated dice message 🥳
-pub async fn handle_roll(bot: Bot, msg: Message) -> ResponseResult<()> {
-    bot.send_dice(msg.chat.id).await?;
+/// Upper bounds that keep a single roll cheap to compute and short enough to reply with.
+const MAX_DICE: u32 = 100;
+const MAX_SIDES: u32 = 1000;
+const MAX_MODIFIER: i64 = 10_000;
+
+/// How long Telegram's dice animation plays before the result is visible.
+pub const DICE_ANIMATION: Duration = Duration::from_millis(3500);
+
+/// Handles `/roll [notation | emoji]`.
+///
+/// Without arguments (or with a dice emoji) it sends Telegram's animated dice
+/// and interprets the value once the animation has settled. Otherwise it
+/// evaluates dice notation such as `2d20+5`, `4d6kh3`, `d100` or `adv`.
+/// 🎰 spins are recorded on the chat's leaderboard.
+pub async fn handle_roll(
+    bot: Bot,
+    msg: Message,
+    args: String,
+    storage: Arc<Storage>,
+) -> ResponseResult<()> {
+    match parse_roll(&args) {
+        Ok(RollRequest::Animated(emoji)) => {
+            let sent = bot.send_dice(msg.chat.id).emoji(emoji).await?;
+            if let Some(dice) = sent.dice() {
+                if let (DiceEmoji::SlotMachine, Some(user)) = (emoji, msg.from.as_ref()) {
+                    let player = Player::from_user(user);
+                    let jackpot = is_jackpot(dice.value);
+                    if let Err(err) = storage
+                        .leaderboards
+                        .update(|boards| boards.record_spin(msg.chat.id.0, &player, jackpot))
+                        .await
+                    {
+                        error!("Failed to save slot spin: {}", err);
+                    }
+                }
+                // Reply once the animation settles without holding up this update.
+                let text = describe_dice(emoji, dice.value);
+                let (chat_id, sent_id) = (msg.chat.id, sent.id);
+                tokio::spawn(async move {
+                    tokio::time::sleep(DICE_ANIMATION).await;
+                    if let Err(err) = bot.send_message(chat_id, text).reply_to(sent_id).await {
+                        error!("Failed to send dice result: {}", err);
+                    }
+                });
+            }
+        }
+        Ok(RollRequest::Notation(roll)) => {
+            let outcome = roll.roll(&mut rand::rng());
+            bot.send_message(msg.chat.id, outcome.to_string())
+                .reply_to(msg.id)
+                .await?;
+        }
+        Err(err) => {
+            bot.send_message(
+                msg.chat.id,
+                format!(
+                    "⚠️ {}\nUsage: /roll [NdS[kh|kl N][+M]] [adv|dis]\n\
+                     Examples: /roll 2d20+5, /roll 4d6kh3, /roll d100, /roll adv, /roll 🎯",
+                    err
+                ),
+            )
+            .await?;
+        }
+    }
     Ok(())
 }
+
+/// What the user asked `/roll` to do.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RollRequest {
+    /// Send one of Telegram's animated dice.
+    Animated(DiceEmoji),
+    /// Evaluate dice notation locally.
+    Notation(Roll),
+}
+
+/// Keep only the highest or lowest dice of a pool.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Keep {
+    Highest(u32),
+    Lowest(u32),
+}
+
+/// Roll the whole expression twice and keep the better or worse total.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Advantage {
+    Advantage,
+    Disadvantage,
+}
+
+/// A parsed dice expression, e.g. `4d6kh3+2`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Roll {
+    pub count: u32,
+    pub sides: u32,
+    pub keep: Option<Keep>,
+    pub modifier: i64,
+    pub advantage: Option<Advantage>,
+}
+
+/// Reasons dice notation is rejected.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RollError {
+    /// The token is not valid dice notation.
+    Syntax(String),
+    /// The notation is valid but outside the supported bounds.
+    OutOfRange(String),
+}
+
+impl fmt::Display for RollError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Syntax(token) => write!(f, "I can't read '{}' as dice notation.", token),
+            Self::OutOfRange(reason) => write!(f, "{}", reason),
+        }
+    }
+}
+
+/// A single evaluation of a [`Roll`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Attempt {
+    /// Every die rolled, in roll order.
+    pub dice: Vec<u32>,
+    /// Indices into `dice` discarded by a keep rule.
+    pub dropped: Vec<usize>,
+    pub total: i64,
+}
+
+/// Result of rolling a [`Roll`], including the discarded attempt under (dis)advantage.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RollOutcome {
+    pub roll: Roll,
+    pub kept: Attempt,
+    pub discarded: Option<Attempt>,
+}
+
+impl RollOutcome {
+    pub fn total(&self) -> i64 {
+        self.kept.total
+    }
+}
+
+/// Parses `/roll` arguments into a request.
+///
+/// An empty argument keeps the classic animated 🎲.
+pub fn parse_roll(input: &str) -> Result<RollRequest, RollError> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Ok(RollRequest::Animated(DiceEmoji::Dice));
+    }
+    if let Some(emoji) = parse_dice_emoji(input) {
+        return Ok(RollRequest::Animated(emoji));
+    }
+
+    let mut expression: Option<Roll> = None;
+    let mut advantage = None;
+
+    for token in input.split_whitespace() {
+        let lower = token.to_lowercase();
+        match lower.as_str() {
+            "adv" | "advantage" => advantage = Some(Advantage::Advantage),
+            "dis" | "disadvantage" => advantage = Some(Advantage::Disadvantage),
+            _ if expression.is_none() => expression = Some(parse_expression(&lower)?),
+            _ => return Err(RollError::Syntax(token.to_string())),
+        }
+    }
+
+    let mut roll = match expression {
+        Some(roll) => roll,
+        // `adv` on its own is the classic d20 roll with advantage.
+        None => Roll {
+            count: 1,
+            sides: 20,
+            keep: None,
+            modifier: 0,
+            advantage: None,
+        },
+    };
+    roll.advantage = advantage;
+    Ok(RollRequest::Notation(roll))
+}
+
+/// Maps the Telegram dice emojis to their `DiceEmoji` variant.
+pub fn parse_dice_emoji(input: &str) -> Option<DiceEmoji> {
+    // Telegram clients sometimes append a variation selector to emoji.
+    match input.trim().trim_end_matches('\u{fe0f}') {
+        "🎲" => Some(DiceEmoji::Dice),
+        "🎯" => Some(DiceEmoji::Darts),
+        "🏀" => Some(DiceEmoji::Basketball),
+        "⚽" => Some(DiceEmoji::Football),
+        "🎳" => Some(DiceEmoji::Bowling),
+        "🎰" => Some(DiceEmoji::SlotMachine),
+        _ => None,
+    }
+}
+
+/// Parses `[N]dS[kh|kl K][+|-M]`.
+fn parse_expression(token: &str) -> Result<Roll, RollError> {
+    let syntax = || RollError::Syntax(token.to_string());
+
+    let (count, rest) = token.split_once('d').ok_or_else(syntax)?;
+    let count = if count.is_empty() {
+        1
+    } else {
+        count.parse::<u32>().map_err(|_| syntax())?
+    };
+
+    let modifier_at = rest.find(['+', '-']).unwrap_or(rest.len());
+    let (pool, modifier) = rest.split_at(modifier_at);
+    let modifier = if modifier.is_empty() {
+        0
+    } else {
+        modifier.parse::<i64>().map_err(|_| syntax())?
+    };
+
+    let (sides, keep) = match pool.find('k') {
+        Some(at) => {
+            let (sides, rule) = pool.split_at(at);
+            let keep = match rule.get(..2) {
+                Some("kh") => Keep::Highest(parse_keep_count(&rule[2..]).ok_or_else(syntax)?),
+                Some("kl") => Keep::Lowest(parse_keep_count(&rule[2..]).ok_or_else(syntax)?),
+                _ => return Err(syntax()),
+            };
+            (sides, Some(keep))
+        }
+        None => (pool, None),
+    };
+    let sides = sides.parse::<u32>().map_err(|_| syntax())?;
+
+    if count == 0 || count > MAX_DICE {
+        return Err(RollError::OutOfRange(format!(
+            "You can roll between 1 and {} dice at once.",
+            MAX_DICE
+        )));
+    }
+    if !(2..=MAX_SIDES).contains(&sides) {
+        return Err(RollError::OutOfRange(format!(
+            "Dice need between 2 and {} sides.",
+            MAX_SIDES
+        )));
+    }
+    if modifier.unsigned_abs() > MAX_MODIFIER.unsigned_abs() {
+        return Err(RollError::OutOfRange(format!(
+            "Modifiers are limited to ±{}.",
+            MAX_MODIFIER
+        )));
+    }
+    if let Some(Keep::Highest(n) | Keep::Lowest(n)) = keep {
+        if n == 0 || n > count {
+            return Err(RollError::OutOfRange(format!(
+                "You can keep between 1 and {} dice.",
+                count
+            )));
+        }
+    }
+
+    Ok(Roll {
+        count,
+        sides,
+        keep,
+        modifier,
+        advantage: None,
+    })
+}
+
+/// A bare `kh`/`kl` keeps a single die.
+fn parse_keep_count(digits: &str) -> Option<u32> {
+    if digits.is_empty() {
+        Some(1)
+    } else {
+        digits.parse().ok()
+    }
+}
+
+impl Roll {
+    /// Rolls the expression with the given RNG.
+    ///
+    /// Taking the RNG as a parameter keeps results reproducible in tests.
+    pub fn roll<R: Rng + ?Sized>(&self, rng: &mut R) -> RollOutcome {
+        let first = self.attempt(rng);
+        let Some(advantage) = self.advantage else {
+            return RollOutcome {
+                roll: self.clone(),
+                kept: first,
+                discarded: None,
+            };
+        };
+
+        let second = self.attempt(rng);
+        let first_wins = match advantage {
+            Advantage::Advantage => first.total >= second.total,
+            Advantage::Disadvantage => first.total <= second.total,
+        };
+        let (kept, discarded) = if first_wins {
+            (first, second)
+        } else {
+            (second, first)
+        };
+
+        RollOutcome {
+            roll: self.clone(),
+            kept,
+            discarded: Some(discarded),
+        }
+    }
+
+    fn attempt<R: Rng + ?Sized>(&self, rng: &mut R) -> Attempt {
+        let dice: Vec<u32> = (0..self.count)
+            .map(|_| rng.random_range(1..=self.sides))
+            .collect();
+
+        let mut order: Vec<usize> = (0..dice.len()).collect();
+        let dropped = match self.keep {
+            Some(Keep::Highest(n)) => {
+                order.sort_by_key(|&i| std::cmp::Reverse(dice[i]));
+                order.split_off(n as usize)
+            }
+            Some(Keep::Lowest(n)) => {
+                order.sort_by_key(|&i| dice[i]);
+                order.split_off(n as usize)
+            }
+            None => Vec::new(),
+        };
+
+        let kept_sum: i64 = dice
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| !dropped.contains(i))
+            .map(|(_, &v)| i64::from(v))
+            .sum();
+
+        Attempt {
+            dice,
+            dropped,
+            total: kept_sum + self.modifier,
+        }
+    }
+}
+
+impl fmt::Display for Roll {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}d{}", self.count, self.sides)?;
+        match self.keep {
+            Some(Keep::Highest(n)) => write!(f, "kh{}", n)?,
+            Some(Keep::Lowest(n)) => write!(f, "kl{}", n)?,
+            None => {}
+        }
+        if self.modifier != 0 {
+            write!(f, "{:+}", self.modifier)?;
+        }
+        match self.advantage {
+            Some(Advantage::Advantage) => write!(f, " with advantage"),
+            Some(Advantage::Disadvantage) => write!(f, " with disadvantage"),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Attempt {
+    /// Renders the dice as `[6, 5, (2), 4]`, bracketing dropped dice.
+    fn breakdown(&self) -> String {
+        let dice: Vec<String> = self
+            .dice
+            .iter()
+            .enumerate()
+            .map(|(i, v)| {
+                if self.dropped.contains(&i) {
+                    format!("({})", v)
+                } else {
+                    v.to_string()
+                }
+            })
+            .collect();
+        format!("[{}]", dice.join(", "))
+    }
+}
+
+impl fmt::Display for RollOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "🎲 {}\n{}", self.roll, self.kept.breakdown())?;
+        if self.roll.modifier != 0 {
+            write!(f, " {:+}", self.roll.modifier)?;
+        }
+        write!(f, " = {}", self.total())?;
+        if let Some(discarded) = &self.discarded {
+            write!(
+                f,
+                "\nDiscarded: {} = {}",
+                discarded.breakdown(),
+                discarded.total
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Symbols on the 🎰 reels, in the order Telegram encodes them.
+const SLOT_SYMBOLS: [&str; 4] = ["BAR", "🍇", "🍋", "7️⃣"];
+
+/// Decodes a 🎰 value (1..=64) into its three reels.
+///
+/// Telegram packs the reels as base-4 digits of `value - 1`, left reel first.
+pub fn slot_reels(value: u8) -> [&'static str; 3] {
+    let v = usize::from(value.saturating_sub(1));
+    [
+        SLOT_SYMBOLS[v & 3],
+        SLOT_SYMBOLS[(v >> 2) & 3],
+        SLOT_SYMBOLS[(v >> 4) & 3],
+    ]
+}
+
+/// `7️⃣ 7️⃣ 7️⃣` is the top prize.
+pub fn is_jackpot(value: u8) -> bool {
+    value == 64
+}
+
+/// Explains what an animated dice value means for each emoji.
+pub fn describe_dice(emoji: DiceEmoji, value: u8) -> String {
+    match emoji {
+        DiceEmoji::Dice => format!("🎲 You rolled a {}!", value),
+        DiceEmoji::Darts => match value {
+            6 => "🎯 Bullseye!".to_string(),
+            1 => "🎯 Missed the board entirely.".to_string(),
+            v => format!("🎯 Scored {} out of 6 — close!", v),
+        },
+        DiceEmoji::Basketball => match value {
+            4 | 5 => "🏀 Swish! It's in.".to_string(),
+            _ => "🏀 Missed the hoop.".to_string(),
+        },
+        DiceEmoji::Football => match value {
+            3..=5 => "⚽ Goal!".to_string(),
+            _ => "⚽ Wide of the post.".to_string(),
+        },
+        DiceEmoji::Bowling => match value {
+            6 => "🎳 Strike!".to_string(),
+            1 => "🎳 Gutter ball.".to_string(),
+            v => format!("🎳 Knocked down some pins ({}/6).", v),
+        },
+        DiceEmoji::SlotMachine => {
+            let reels = slot_reels(value).join(" ");
+            if is_jackpot(value) {
+                format!("🎰 {} — JACKPOT! 🎉", reels)
+            } else if value == 1 || value == 22 || value == 43 {
+                format!("🎰 {} — three of a kind!", reels)
+            } else {
+                format!("🎰 {} — better luck next spin.", reels)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    fn notation(input: &str) -> Roll {
+        match parse_roll(input) {
+            Ok(RollRequest::Notation(roll)) => roll,
+            other => panic!("expected notation for {input:?}, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_parse_roll_requests() {
+        assert_eq!(parse_roll(""), Ok(RollRequest::Animated(DiceEmoji::Dice)));
+        assert_eq!(
+            parse_roll("🎰"),
+            Ok(RollRequest::Animated(DiceEmoji::SlotMachine))
+        );
+
+        let roll = notation("2d20+5");
+        assert_eq!((roll.count, roll.sides, roll.modifier), (2, 20, 5));
+
+        let roll = notation("4d6kh3");
+        assert_eq!(roll.keep, Some(Keep::Highest(3)));
+
+        let roll = notation("d100");
+        assert_eq!((roll.count, roll.sides), (1, 100));
+
+        let roll = notation("adv");
+        assert_eq!((roll.count, roll.sides), (1, 20));
+        assert_eq!(roll.advantage, Some(Advantage::Advantage));
+
+        let roll = notation("d20-1 dis");
+        assert_eq!(roll.modifier, -1);
+        assert_eq!(roll.advantage, Some(Advantage::Disadvantage));
+
+        assert!(parse_roll("banana").is_err());
+        assert!(parse_roll("0d6").is_err());
+        assert!(parse_roll("2d1").is_err());
+        assert!(parse_roll("2d6kh3").is_err());
+        assert!(parse_roll("1d6 2d6").is_err());
+        assert!(matches!(
+            parse_roll("d6-9223372036854775808"),
+            Err(RollError::OutOfRange(_))
+        ));
+    }
+
+    #[test]
+    fn test_roll_is_reproducible_and_keeps_highest() {
+        let roll = notation("4d6kh3");
+        let a = roll.roll(&mut StdRng::seed_from_u64(7));
+        let b = roll.roll(&mut StdRng::seed_from_u64(7));
+        assert_eq!(a, b);
+
+        assert_eq!(a.kept.dice.len(), 4);
+        assert_eq!(a.kept.dropped.len(), 1);
+        let lowest = *a.kept.dice.iter().min().unwrap();
+        assert_eq!(a.kept.dice[a.kept.dropped[0]], lowest);
+        let expected: u32 = a.kept.dice.iter().sum::<u32>() - lowest;
+        assert_eq!(a.total(), i64::from(expected));
+    }
+
+    #[test]
+    fn test_advantage_keeps_better_attempt() {
+        let outcome = notation("adv").roll(&mut StdRng::seed_from_u64(42));
+        let discarded = outcome.discarded.as_ref().unwrap();
+        assert!(outcome.total() >= discarded.total);
+    }
+
+    #[test]
+    fn test_slot_reels() {
+        assert_eq!(slot_reels(1), ["BAR", "BAR", "BAR"]);
+        assert_eq!(slot_reels(64), ["7️⃣", "7️⃣", "7️⃣"]);
+        assert!(is_jackpot(64));
+        assert!(!is_jackpot(43));
+    }
+}
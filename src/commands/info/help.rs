@@ -1,10 +1,278 @@
-use crate::commands::Command;
-use teloxide::utils::command::BotCommands;
+use crate::commands::access;
+use crate::commands::args::{Arg, ArgKind, Signature};
+use crate::commands::fallback::unknown_command::suggest;
+use crate::commands::registry::{self, Category, CommandSpec};
+use crate::config::BotConfig;
+use std::sync::Arc;
+use teloxide::types::{Chat, InlineKeyboardButton, InlineKeyboardMarkup, UserId};
 use teloxide::{prelude::*, types::Message};
 
-/// Sends the list of available commands to the user.
-pub async fn handle_help(bot: Bot, msg: Message) -> ResponseResult<()> {
-    let help_text = Command::descriptions().to_string();
-    bot.send_message(msg.chat.id, help_text).await?;
+/// Callback data prefix for help navigation buttons.
+pub const HELP_PREFIX: &str = "help:";
+
+/// Commands listed per category page.
+const PAGE_SIZE: usize = 5;
+
+/// Arguments accepted by `/help`.
+pub const ARGS: Signature = Signature::new(&[Arg::optional("command", ArgKind::Text)]);
+
+/// Sends the help overview, or the detail page for `/help <command>`.
+///
+/// Commands the caller can't use in this chat (admin-only, disabled or
+/// restricted to other chat types) are left out.
+pub async fn handle_help(
+    bot: Bot,
+    msg: Message,
+    args: String,
+    config: Arc<BotConfig>,
+) -> ResponseResult<()> {
+    let user = msg.from.as_ref().map(|u| u.id);
+    let visible = visibility(&config, user, &msg.chat);
+    let args = match ARGS.parse(&args) {
+        Ok(args) => args,
+        Err(err) => {
+            bot.send_message(msg.chat.id, err.render("help", &args, &ARGS))
+                .await?;
+            return Ok(());
+        }
+    };
+    let query = args.text("command").unwrap_or_default();
+
+    let page = if query.is_empty() {
+        HelpPage::Home
+    } else {
+        match registry::find(query).filter(|spec| visible(spec)) {
+            Some(spec) => HelpPage::Command(spec),
+            None => {
+                let hints: Vec<String> = suggest(query.trim_start_matches('/'))
+                    .into_iter()
+                    .filter_map(registry::find)
+                    .filter(|spec| visible(spec))
+                    .map(|spec| format!("/help {}", spec.name))
+                    .collect();
+                let mut text = format!("❓ There's no command called '{}'.", query);
+                if !hints.is_empty() {
+                    text.push_str(&format!("\nDid you mean {}?", hints.join(" or ")));
+                }
+                bot.send_message(msg.chat.id, text).await?;
+                return Ok(());
+            }
+        }
+    };
+
+    // Anonymous senders have no permissions beyond everyone's.
+    let owner = user.unwrap_or(UserId(0));
+    let (text, keyboard) = render(&page, owner, &visible);
+    bot.send_message(msg.chat.id, text)
+        .reply_markup(keyboard)
+        .await?;
+    Ok(())
+}
+
+/// Handles help navigation buttons by editing the help message in place.
+/// The message is shared, so it keeps showing what its requester may use,
+/// whoever taps.
+pub async fn handle_help_callback(
+    bot: Bot,
+    q: CallbackQuery,
+    data: &str,
+    config: Arc<BotConfig>,
+) -> ResponseResult<()> {
+    bot.answer_callback_query(q.id.clone()).await?;
+
+    let (Some((owner, page)), Some(message)) = (HelpPage::parse(data), q.message.as_ref()) else {
+        return Ok(());
+    };
+    let visible = visibility(&config, Some(owner), message.chat());
+    if let HelpPage::Command(spec) = page {
+        if !visible(spec) {
+            return Ok(());
+        }
+    }
+
+    let (text, keyboard) = render(&page, owner, &visible);
+    bot.edit_message_text(message.chat().id, message.id(), text)
+        .reply_markup(keyboard)
+        .await?;
     Ok(())
 }
+
+/// A screen of the interactive help.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum HelpPage {
+    Home,
+    Category(Category, usize),
+    Command(&'static CommandSpec),
+}
+
+impl HelpPage {
+    /// Parses callback data (without the prefix): the requester's user ID,
+    /// then `home`, `cat:<slug>:<page>` or `cmd:<name>`.
+    fn parse(data: &str) -> Option<(UserId, Self)> {
+        let (owner, data) = data.split_once(':')?;
+        let owner = UserId(owner.parse().ok()?);
+        let mut parts = data.split(':');
+        let page = match (parts.next()?, parts.next(), parts.next()) {
+            ("home", None, None) => Some(Self::Home),
+            ("cat", Some(slug), Some(page)) => Some(Self::Category(
+                Category::from_slug(slug)?,
+                page.parse().ok()?,
+            )),
+            ("cmd", Some(name), None) => registry::find(name).map(Self::Command),
+            _ => None,
+        }?;
+        Some((owner, page))
+    }
+
+    fn callback_data(&self, owner: UserId) -> String {
+        let page = match self {
+            Self::Home => "home".to_string(),
+            Self::Category(category, page) => format!("cat:{}:{}", category.slug(), page),
+            Self::Command(spec) => format!("cmd:{}", spec.name),
+        };
+        format!("{}{}:{}", HELP_PREFIX, owner, page)
+    }
+
+    fn button(&self, owner: UserId, label: impl Into<String>) -> InlineKeyboardButton {
+        InlineKeyboardButton::callback(label, self.callback_data(owner))
+    }
+}
+
+/// Builds the filter deciding which commands the caller may see.
+fn visibility<'a>(
+    config: &'a BotConfig,
+    user: Option<UserId>,
+    chat: &'a Chat,
+) -> impl Fn(&CommandSpec) -> bool + 'a {
+    move |spec| access::check(spec, config, user, chat).is_ok()
+}
+
+fn render(
+    page: &HelpPage,
+    owner: UserId,
+    visible: &dyn Fn(&CommandSpec) -> bool,
+) -> (String, InlineKeyboardMarkup) {
+    match *page {
+        HelpPage::Home => render_home(owner, visible),
+        HelpPage::Category(category, page) => render_category(category, page, owner, visible),
+        HelpPage::Command(spec) => render_command(spec, owner),
+    }
+}
+
+fn render_home(
+    owner: UserId,
+    visible: &dyn Fn(&CommandSpec) -> bool,
+) -> (String, InlineKeyboardMarkup) {
+    let mut text = String::from("📖 Available commands\n");
+    let mut buttons = Vec::new();
+
+    for category in Category::ALL {
+        let count = registry::in_category(category)
+            .filter(|s| visible(s))
+            .count();
+        if count == 0 {
+            continue;
+        }
+        text.push_str(&format!("\n{} — {} command(s)", category.title(), count));
+        buttons.push(HelpPage::Category(category, 0).button(owner, category.title()));
+    }
+    text.push_str("\n\nPick a category, or send /help <command> for details.");
+
+    let rows = buttons.chunks(2).map(<[_]>::to_vec);
+    (text, InlineKeyboardMarkup::new(rows))
+}
+
+fn render_category(
+    category: Category,
+    page: usize,
+    owner: UserId,
+    visible: &dyn Fn(&CommandSpec) -> bool,
+) -> (String, InlineKeyboardMarkup) {
+    let specs: Vec<&CommandSpec> = registry::in_category(category)
+        .filter(|s| visible(s))
+        .collect();
+    let pages = specs.len().div_ceil(PAGE_SIZE).max(1);
+    let page = page.min(pages - 1);
+
+    let mut text = format!("{} ({}/{})\n", category.title(), page + 1, pages);
+    let mut rows = Vec::new();
+    for spec in specs.iter().skip(page * PAGE_SIZE).take(PAGE_SIZE) {
+        text.push_str(&format!("\n{} — {}", spec.synopsis(), spec.description()));
+        rows.push(vec![
+            HelpPage::Command(spec).button(owner, format!("/{}", spec.name)),
+        ]);
+    }
+
+    let mut nav = Vec::new();
+    if page > 0 {
+        nav.push(HelpPage::Category(category, page - 1).button(owner, "◀️"));
+    }
+    nav.push(HelpPage::Home.button(owner, "🏠"));
+    if page + 1 < pages {
+        nav.push(HelpPage::Category(category, page + 1).button(owner, "▶️"));
+    }
+    rows.push(nav);
+
+    (text, InlineKeyboardMarkup::new(rows))
+}
+
+fn render_command(spec: &'static CommandSpec, owner: UserId) -> (String, InlineKeyboardMarkup) {
+    let mut text = format!("{}\n{}\n", spec.synopsis(), spec.description());
+
+    if !spec.examples.is_empty() {
+        text.push_str("\nExamples:");
+        for example in spec.examples {
+            text.push_str(&format!("\n  {}", example));
+        }
+        text.push('\n');
+    }
+    if !spec.aliases.is_empty() {
+        let aliases: Vec<String> = spec.aliases.iter().map(|a| format!("/{}", a)).collect();
+        text.push_str(&format!("\nAliases: {}", aliases.join(", ")));
+    }
+    if let Some(cooldown) = spec.cooldown {
+        text.push_str(&format!("\nCooldown: {}s per user", cooldown.as_secs()));
+    }
+    if spec.admin_only {
+        text.push_str("\nAdmins only");
+    }
+    if spec.scope == registry::Scope::GroupsOnly {
+        text.push_str("\nGroup chats only");
+    }
+
+    let back =
+        HelpPage::Category(spec.category, 0).button(owner, format!("« {}", spec.category.title()));
+    (
+        text.trim_end().to_string(),
+        InlineKeyboardMarkup::new([[back]]),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_help_page_callback_roundtrip() {
+        let pages = [
+            HelpPage::Home,
+            HelpPage::Category(Category::Utilities, 1),
+            HelpPage::Command(registry::find("weather").unwrap()),
+        ];
+        for page in pages {
+            let data = page.callback_data(UserId(42));
+            let parsed = HelpPage::parse(data.strip_prefix(HELP_PREFIX).unwrap());
+            assert_eq!(parsed, Some((UserId(42), page)));
+        }
+        assert_eq!(HelpPage::parse("42:cat:nope:0"), None);
+        assert_eq!(HelpPage::parse("home"), None);
+    }
+
+    #[test]
+    fn test_hidden_commands_are_not_listed() {
+        let hide_duel = |spec: &CommandSpec| spec.name != "duel";
+        let (text, _) = render_category(Category::Fun, 0, UserId(1), &hide_duel);
+        assert!(text.contains("/roll"));
+        assert!(!text.contains("/duel"));
+    }
+}
@@ -1,18 +1,258 @@
-use teloxide::{prelude::*, types::Message};
-
-// This function sends a message to the user with their user ID and chat ID.
-pub async fn handle_id(bot: Bot, msg: Message) -> ResponseResult<()> {
-    let user_id_text = match msg.from.as_ref() {
-        Some(user) => format!(
-            "👤 Your user ID: {}\n💬 Chat ID: {}",
-            user.id.0, msg.chat.id
+use crate::commands::args::{Arg, ArgKind, Signature};
+use crate::config::BotConfig;
+use std::sync::Arc;
+use teloxide::prelude::*;
+use teloxide::types::{Chat, InputFile, Message, MessageOrigin, ParseMode, User};
+use teloxide::utils::html;
+
+/// Arguments accepted by `/id`.
+pub const ARGS: Signature = Signature::new(&[Arg::optional("mode", ArgKind::Choice(&["json"]))]);
+
+/// Longest text Telegram accepts in one message.
+const MAX_MESSAGE_LENGTH: usize = 4096;
+
+/// Shows the IDs around the command: sender, chat, topic and message. As a
+/// reply it also shows who wrote the replied-to message and, if that was
+/// forwarded, where it came from. `/id json` dumps the raw message instead;
+/// it exposes other users' details, so only bot admins may use it.
+pub async fn handle_id(
+    bot: Bot,
+    msg: Message,
+    text: String,
+    config: Arc<BotConfig>,
+) -> ResponseResult<()> {
+    let args = match ARGS.parse(&text) {
+        Ok(args) => args,
+        Err(err) => {
+            bot.send_message(msg.chat.id, err.render("id", &text, &ARGS))
+                .await?;
+            return Ok(());
+        }
+    };
+
+    if args.choice("mode").is_some() {
+        if !msg.from.as_ref().is_some_and(|u| config.is_admin(u.id)) {
+            bot.send_message(msg.chat.id, "🔒 Only bot admins can use /id json.")
+                .await?;
+            return Ok(());
+        }
+        return send_json(&bot, &msg).await;
+    }
+    bot.send_message(msg.chat.id, describe(&msg)).await?;
+    Ok(())
+}
+
+/// Sends the message as pretty-printed JSON, as a file if it's too long to
+/// fit in a message.
+async fn send_json(bot: &Bot, msg: &Message) -> ResponseResult<()> {
+    let json = match serde_json::to_string_pretty(msg) {
+        Ok(json) => json,
+        Err(err) => {
+            log::error!("Failed to serialize message {}: {}", msg.id, err);
+            bot.send_message(msg.chat.id, "❌ Failed to serialize the message.")
+                .await?;
+            return Ok(());
+        }
+    };
+    let text = format!("<pre>{}</pre>", html::escape(&json));
+
+    if text.chars().count() <= MAX_MESSAGE_LENGTH {
+        bot.send_message(msg.chat.id, text)
+            .parse_mode(ParseMode::Html)
+            .await?;
+    } else {
+        let file =
+            InputFile::memory(json.into_bytes()).file_name(format!("message-{}.json", msg.id));
+        bot.send_document(msg.chat.id, file).await?;
+    }
+    Ok(())
+}
+
+fn describe(msg: &Message) -> String {
+    let mut lines = vec![
+        match msg.from.as_ref() {
+            Some(user) => format!("👤 Your user ID: {}", user.id),
+            None => "❓ Could not determine your user ID.".to_string(),
+        },
+        format!("💬 Chat ID: {}", msg.chat.id),
+        format!("🏷 Chat: {}", describe_chat(&msg.chat)),
+    ];
+    if let Some(thread) = msg.thread_id {
+        let label = if msg.is_topic_message {
+            "Topic"
+        } else {
+            "Thread"
+        };
+        lines.push(format!("🧵 {} ID: {}", label, thread));
+    }
+    lines.push(format!("✉️ Message ID: {}", msg.id));
+
+    // In forum topics, every message without an explicit reply points at the
+    // topic's creation notice; that isn't a reply worth describing.
+    let reply = msg
+        .reply_to_message()
+        .filter(|reply| reply.forum_topic_created().is_none());
+    if let Some(reply) = reply {
+        lines.push(String::new());
+        let author = match (&reply.from, &reply.sender_chat) {
+            (_, Some(chat)) => format!("{}: {}", describe_chat(chat), chat.id),
+            (Some(user), None) => format!("{}: {}", describe_user(user), user.id),
+            (None, None) => "unknown".to_string(),
+        };
+        lines.push(format!(
+            "↩️ Replied-to message {} from {}",
+            reply.id, author
+        ));
+        if let Some(origin) = reply.forward_origin() {
+            lines.push(format!("📨 Forwarded from {}", describe_origin(origin)));
+        }
+    }
+    lines.join("\n")
+}
+
+fn describe_origin(origin: &MessageOrigin) -> String {
+    match origin {
+        MessageOrigin::User { sender_user, .. } => {
+            format!("{}: {}", describe_user(sender_user), sender_user.id)
+        }
+        MessageOrigin::HiddenUser {
+            sender_user_name, ..
+        } => format!("{} (ID hidden by their privacy settings)", sender_user_name),
+        MessageOrigin::Chat {
+            sender_chat,
+            author_signature,
+            ..
+        } => format!(
+            "{}: {}{}",
+            describe_chat(sender_chat),
+            sender_chat.id,
+            signature(author_signature)
         ),
-        None => format!(
-            "❓ Could not determine your user ID.\n💬 Chat ID: {}",
-            msg.chat.id
+        MessageOrigin::Channel {
+            chat,
+            message_id,
+            author_signature,
+            ..
+        } => format!(
+            "{}: {}, post {}{}",
+            describe_chat(chat),
+            chat.id,
+            message_id,
+            signature(author_signature)
         ),
+    }
+}
+
+fn signature(author: &Option<String>) -> String {
+    author
+        .as_ref()
+        .map(|a| format!(", signed {}", a))
+        .unwrap_or_default()
+}
+
+/// `Alice (@alice)`, marked when it's a bot.
+fn describe_user(user: &User) -> String {
+    let mut text = user.full_name();
+    if let Some(username) = &user.username {
+        text.push_str(&format!(" (@{})", username));
+    }
+    if user.is_bot {
+        text.push_str(" [bot]");
+    }
+    text
+}
+
+/// The chat's type followed by its title or username, e.g.
+/// `supergroup "Rustaceans"`.
+fn describe_chat(chat: &Chat) -> String {
+    let kind = if chat.is_private() {
+        "private chat"
+    } else if chat.is_group() {
+        "group"
+    } else if chat.is_supergroup() {
+        "supergroup"
+    } else {
+        "channel"
     };
+    match (chat.title(), chat.username()) {
+        (Some(title), _) => format!("{} \"{}\"", kind, title),
+        (None, Some(username)) => format!("{} @{}", kind, username),
+        (None, None) => kind.to_string(),
+    }
+}
 
-    bot.send_message(msg.chat.id, user_id_text).await?;
-    Ok(())
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_describe_reply_to_channel_forward() {
+        let msg: Message = serde_json::from_value(json!({
+            "message_id": 42,
+            "message_thread_id": 7,
+            "is_topic_message": true,
+            "date": 1_700_000_000,
+            "chat": {"id": -1001, "type": "supergroup", "title": "Rustaceans", "is_forum": true},
+            "from": {"id": 1, "is_bot": false, "first_name": "Alice", "username": "alice"},
+            "text": "/id",
+            "reply_to_message": {
+                "message_id": 40,
+                "date": 1_700_000_000,
+                "chat": {"id": -1001, "type": "supergroup", "title": "Rustaceans"},
+                "from": {"id": 2, "is_bot": false, "first_name": "Bob"},
+                "forward_origin": {
+                    "type": "channel",
+                    "date": 1_600_000_000,
+                    "chat": {"id": -1002, "type": "channel", "title": "News"},
+                    "message_id": 99
+                },
+                "text": "Big news"
+            }
+        }))
+        .unwrap();
+
+        assert_eq!(
+            describe(&msg),
+            "👤 Your user ID: 1\n\
+             💬 Chat ID: -1001\n\
+             🏷 Chat: supergroup \"Rustaceans\"\n\
+             🧵 Topic ID: 7\n\
+             ✉️ Message ID: 42\n\
+             \n\
+             ↩️ Replied-to message 40 from Bob: 2\n\
+             📨 Forwarded from channel \"News\": -1002, post 99"
+        );
+    }
+
+    #[test]
+    fn test_describe_skips_topic_created_notice() {
+        let msg: Message = serde_json::from_value(json!({
+            "message_id": 42,
+            "message_thread_id": 7,
+            "is_topic_message": true,
+            "date": 1_700_000_000,
+            "chat": {"id": -1001, "type": "supergroup", "title": "Rustaceans", "is_forum": true},
+            "from": {"id": 1, "is_bot": false, "first_name": "Alice", "username": "alice"},
+            "text": "/id",
+            "reply_to_message": {
+                "message_id": 7,
+                "message_thread_id": 7,
+                "date": 1_700_000_000,
+                "chat": {"id": -1001, "type": "supergroup", "title": "Rustaceans", "is_forum": true},
+                "from": {"id": 2, "is_bot": false, "first_name": "Bob"},
+                "forum_topic_created": {"name": "Help", "icon_color": 7322096}
+            }
+        }))
+        .unwrap();
+
+        assert_eq!(
+            describe(&msg),
+            "👤 Your user ID: 1\n\
+             💬 Chat ID: -1001\n\
+             🏷 Chat: supergroup \"Rustaceans\"\n\
+             🧵 Topic ID: 7\n\
+             ✉️ Message ID: 42"
+        );
+    }
 }
@@ -9,14 +9,19 @@ use teloxide::prelude::*;
 use teloxide::types::Message;
 use teloxide::utils::command::BotCommands;
 
+use crate::config::BotConfig;
 use crate::services::ApiService;
+use crate::storage::Storage;
 use std::sync::Arc;
 use std::time::Instant;
 
 /// Command handler submodules organized by domain.
+pub mod access;
+pub mod args;
 pub mod fallback;
 pub mod fun;
 pub mod info;
+pub mod registry;
 pub mod system;
 pub mod utils;
 
@@ -32,24 +37,70 @@ pub enum Command {
     Start,
     #[command(description = "Check if the bot is alive.")]
     Ping,
-    #[command(description = "Display this help message.")]
-    Help,
+    #[command(description = "Display this help message.", aliases = ["commands"])]
+    Help(String),
     #[command(description = "Show bot information.")]
     About,
-    #[command(description = "Show your user ID and chat ID.")]
-    Id,
+    #[command(
+        description = "Show IDs for you, this chat and the replied-to message ('json' for raw)."
+    )]
+    Id(String),
     #[command(description = "Show the current time.")]
     Time,
     #[command(description = "Echo a message.")]
     Echo(String),
-    #[command(description = "Check weather in a city.")]
+    #[command(description = "Check weather in a city, or in your saved one.", aliases = ["w"])]
     Weather(String),
-    #[command(description = "Convert currency (e.g., '10 USD to EUR').")]
+    #[command(description = "Forecast for up to 3 days, or 'today' hour by hour.")]
+    Forecast(String),
+    #[command(description = "Save your default city for weather commands.")]
+    Setcity(String),
+    #[command(description = "Show weather in metric or imperial units.")]
+    Units(String),
+    #[command(
+        description = "Warn this chat about strong wind or rain (e.g., 'Lima wind>50 rain>80%')."
+    )]
+    Weatheralert(String),
+    #[command(description = "List and cancel this chat's weather alerts.")]
+    Weatheralerts,
+    #[command(
+        description = "Convert currency (e.g., '10 USD to EUR' or '$10 in €,£').",
+        aliases = ["fx", "convert"]
+    )]
     Currency(String),
-    #[command(description = "Roll a random number.")]
-    Roll,
-    #[command(description = "Tell a random joke.")]
-    Joke,
+    #[command(description = "List supported currencies (e.g., 'peso' or 'japan').")]
+    Currencies(String),
+    #[command(description = "Chart an exchange rate (e.g., 'USD EUR 30d').")]
+    Fxchart(String),
+    #[command(description = "Alert when a rate crosses a threshold (e.g., 'USD PEN > 3.9').")]
+    Fxalert(String),
+    #[command(description = "List and cancel this chat's FX alerts.")]
+    Fxalerts,
+    #[command(description = "Show crypto prices with 24h change (e.g., 'BTC ETH in EUR').")]
+    Crypto(String),
+    #[command(
+        description = "Roll dice (e.g., '2d20+5', '4d6kh3', 'adv', '🎯').",
+        aliases = ["dice"]
+    )]
+    Roll(String),
+    #[command(description = "Challenge someone to a dice duel (e.g., '@user').")]
+    Duel(String),
+    #[command(description = "Show this chat's dice-game rankings.", aliases = ["top"])]
+    Leaderboard,
+    #[command(description = "Tell a joke (e.g., 'programming', 'pun', 'community' or 'top').")]
+    Joke(String),
+    #[command(description = "Send a joke in for the community collection.")]
+    Addjoke(String),
+    #[command(description = "Review jokes waiting for approval (admins).")]
+    Jokequeue,
+    #[command(
+        description = "Show the joke of the day, or get it here daily (e.g., 'subscribe 09:00')."
+    )]
+    Jotd(String),
+    #[command(description = "Set this chat's joke language, blocked topics and safe mode.")]
+    Jokesettings(String),
+    #[command(description = "Show API usage against provider quotas (admins).")]
+    Quota,
 }
 
 /// Compact execution metadata for structured logging.
@@ -113,7 +164,8 @@ impl ExecutionMetrics {
 
 /// Primary command dispatch handler.
 ///
-/// Routes incoming commands to their respective handlers while measuring
+/// Enforces the availability rules from the command registry, then routes
+/// incoming commands to their respective handlers while measuring
 /// execution time and logging outcomes for observability.
 ///
 /// # Arguments
@@ -122,6 +174,9 @@ impl ExecutionMetrics {
 /// * `msg` - Original message that triggered the command.
 /// * `cmd` - Parsed command variant.
 /// * `api` - Shared API service for external data fetching.
+/// * `storage` - Persistent stores for state that outlives a single command.
+/// * `config` - Operator settings (admins, disabled commands).
+/// * `cooldowns` - Per-user rate limiting state.
 ///
 /// # Errors
 ///
@@ -131,17 +186,73 @@ pub async fn dispatch_command(
     msg: Message,
     cmd: Command,
     api: Arc<ApiService>,
+    storage: Arc<Storage>,
+    config: Arc<BotConfig>,
+    cooldowns: Arc<access::Cooldowns>,
 ) -> ResponseResult<()> {
+    // The command is looked up by the name the user typed, so aliases
+    // share their canonical command's rules and cooldown.
+    let text = msg.text().unwrap_or_default().trim_start();
+    let (name, args) = text.split_once(char::is_whitespace).unwrap_or((text, ""));
+    let name = name.split('@').next().unwrap_or(name);
+    if let Err(denial) = check_access(name, args.trim(), &msg, &config, &cooldowns) {
+        bot.send_message(msg.chat.id, denial.to_string()).await?;
+        return Ok(());
+    }
+
     let metrics = ExecutionMetrics::from_message(&msg);
     let start = Instant::now();
 
-    let result = route_command(bot, msg, &cmd, api).await;
+    let result = route_command(bot, msg, &cmd, api, storage, config).await;
 
     metrics.log(&cmd, &result, start.elapsed().as_millis());
 
     result
 }
 
+/// Answers a location or venue shared in a private chat with the weather
+/// there, under the same rules and cooldown as `/weather`.
+///
+/// # Errors
+///
+/// Propagates handler-specific errors as `RequestError` variants.
+pub async fn dispatch_location(
+    bot: Bot,
+    msg: Message,
+    api: Arc<ApiService>,
+    storage: Arc<Storage>,
+    config: Arc<BotConfig>,
+    cooldowns: Arc<access::Cooldowns>,
+) -> ResponseResult<()> {
+    if let Err(denial) = check_access("weather", "", &msg, &config, &cooldowns) {
+        bot.send_message(msg.chat.id, denial.to_string()).await?;
+        return Ok(());
+    }
+    utils::weather::handle_location(bot, msg, api, storage).await
+}
+
+/// Applies the registry's access rules and cooldown for command `name`.
+/// Arguments the handler will reject as a usage error don't start the
+/// cooldown, so a typo doesn't lock the user out of the retry.
+fn check_access(
+    name: &str,
+    args: &str,
+    msg: &Message,
+    config: &BotConfig,
+    cooldowns: &access::Cooldowns,
+) -> Result<(), access::Denial> {
+    let Some(spec) = registry::find(name) else {
+        return Ok(());
+    };
+
+    let user = msg.from.as_ref().map(|u| u.id);
+    access::check(spec, config, user, &msg.chat)?;
+    match user {
+        Some(user) if spec.accepts(msg, args) => cooldowns.try_acquire(spec, user),
+        _ => Ok(()),
+    }
+}
+
 /// Internal routing logic separated for testability.
 ///
 /// This pure function allows unit testing of command dispatch without
@@ -151,26 +262,116 @@ async fn route_command(
     msg: Message,
     cmd: &Command,
     api: Arc<ApiService>,
+    storage: Arc<Storage>,
+    config: Arc<BotConfig>,
 ) -> ResponseResult<()> {
     match cmd {
         Command::Start => system::start::handle_start(bot, msg).await,
         Command::Ping => system::ping::handle_ping(bot, msg).await,
-        Command::Help => info::help::handle_help(bot, msg).await,
+        Command::Help(args) => info::help::handle_help(bot, msg, args.clone(), config).await,
         Command::About => info::about::handle_about(bot, msg).await,
-        Command::Id => info::id::handle_id(bot, msg).await,
+        Command::Id(args) => info::id::handle_id(bot, msg, args.clone(), config).await,
         Command::Time => info::time::handle_time(bot, msg).await,
         Command::Echo(text) => utils::echo::handle_echo(bot, msg, text.clone()).await,
         Command::Weather(city) => {
-            utils::weather::handle_weather(bot, msg, city.clone(), api).await
+            utils::weather::handle_weather(bot, msg, city.clone(), api, storage, config).await
+        }
+        Command::Forecast(args) => {
+            utils::weather::forecast::handle_forecast(bot, msg, args.clone(), api, storage).await
+        }
+        Command::Setcity(city) => {
+            utils::weather::prefs::handle_setcity(bot, msg, city.clone(), api, storage).await
+        }
+        Command::Units(system) => {
+            utils::weather::prefs::handle_units(bot, msg, system.clone(), storage).await
+        }
+        Command::Weatheralert(args) => {
+            utils::weather::alerts::handle_weatheralert(bot, msg, args.clone(), api, storage).await
+        }
+        Command::Weatheralerts => {
+            utils::weather::alerts::handle_weatheralerts(bot, msg, storage).await
         }
         Command::Currency(input) => {
             utils::currency::handle_currency(bot, msg, input.clone(), api).await
         }
-        Command::Roll => fun::roll::handle_roll(bot, msg).await,
-        Command::Joke => fun::joke::handle_joke(bot, msg, api).await,
+        Command::Currencies(search) => {
+            utils::currency::handle_currencies(bot, msg, search.clone()).await
+        }
+        Command::Fxchart(args) => {
+            utils::currency::handle_fxchart(bot, msg, args.clone(), api).await
+        }
+        Command::Fxalert(args) => {
+            utils::currency::alerts::handle_fxalert(bot, msg, args.clone(), api, storage).await
+        }
+        Command::Fxalerts => utils::currency::alerts::handle_fxalerts(bot, msg, storage).await,
+        Command::Crypto(args) => {
+            utils::currency::crypto::handle_crypto(bot, msg, args.clone(), api).await
+        }
+        Command::Roll(args) => fun::roll::handle_roll(bot, msg, args.clone(), storage).await,
+        Command::Duel(args) => fun::duel::handle_duel(bot, msg, args.clone(), storage).await,
+        Command::Leaderboard => fun::leaderboard::handle_leaderboard(bot, msg, storage).await,
+        Command::Joke(args) => fun::joke::handle_joke(bot, msg, args.clone(), api, storage).await,
+        Command::Addjoke(text) => {
+            fun::joke::community::handle_addjoke(bot, msg, text.clone(), storage, config).await
+        }
+        Command::Jokequeue => fun::joke::community::handle_jokequeue(bot, msg, storage).await,
+        Command::Jotd(args) => {
+            fun::joke::daily::handle_jotd(bot, msg, args.clone(), api, storage).await
+        }
+        Command::Jokesettings(args) => {
+            fun::joke::settings::handle_jokesettings(bot, msg, args.clone(), storage, config).await
+        }
+        Command::Quota => system::quota::handle_quota(bot, msg, api).await,
     }
 }
 
+/// Routes inline keyboard button presses by their callback data prefix.
+///
+/// # Errors
+///
+/// Propagates handler-specific errors as `RequestError` variants.
+pub async fn dispatch_callback(
+    bot: Bot,
+    q: CallbackQuery,
+    api: Arc<ApiService>,
+    storage: Arc<Storage>,
+    config: Arc<BotConfig>,
+) -> ResponseResult<()> {
+    let data = q.data.clone().unwrap_or_default();
+
+    if let Some(page) = data.strip_prefix(info::help::HELP_PREFIX) {
+        return info::help::handle_help_callback(bot, q, page, config).await;
+    }
+    if let Some(name) = data.strip_prefix(fallback::unknown_command::SUGGESTION_PREFIX) {
+        return fallback::unknown_command::handle_suggestion_callback(bot, q, name).await;
+    }
+    if let Some(id) = data.strip_prefix(utils::currency::alerts::FXALERT_PREFIX) {
+        return utils::currency::alerts::handle_fxalert_callback(bot, q, id, storage, config).await;
+    }
+    if let Some(id) = data.strip_prefix(utils::weather::alerts::WXALERT_PREFIX) {
+        return utils::weather::alerts::handle_weatheralert_callback(bot, q, id, storage, config)
+            .await;
+    }
+
+    if let Some(duel) = data.strip_prefix(fun::duel::DUEL_PREFIX) {
+        return fun::duel::handle_duel_callback(bot, q, duel, storage).await;
+    }
+    if let Some(category) = data.strip_prefix(fun::joke::JOKE_PREFIX) {
+        return fun::joke::handle_joke_callback(bot, q, category, api, storage).await;
+    }
+    if let Some(vote) = data.strip_prefix(fun::joke::community::VOTE_PREFIX) {
+        return fun::joke::community::handle_vote_callback(bot, q, vote, storage).await;
+    }
+    if let Some(review) = data.strip_prefix(fun::joke::community::REVIEW_PREFIX) {
+        return fun::joke::community::handle_review_callback(bot, q, review, storage, config)
+            .await;
+    }
+
+    log::warn!("Unhandled callback data: {:?}", data);
+    bot.answer_callback_query(q.id).await?;
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -183,4 +384,29 @@ mod tests {
             Command::Echo("hello".to_string())
         );
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_usage_errors_skip_cooldown() {
+        let msg: Message = serde_json::from_value(serde_json::json!({
+            "message_id": 1,
+            "date": 1_700_000_000,
+            "chat": {"id": 1, "type": "private", "first_name": "Alice"},
+            "from": {"id": 1, "is_bot": false, "first_name": "Alice"},
+            "text": "/currency"
+        }))
+        .unwrap();
+        let config = BotConfig::default();
+        let cooldowns = access::Cooldowns::default();
+        let check = |args| check_access("/fx", args, &msg, &config, &cooldowns);
+
+        assert!(check("100 USD").is_ok());
+        assert!(check("100 USD XYZ").is_ok());
+        assert!(check("100 USD EUR").is_ok());
+        assert!(matches!(
+            check("100 USD EUR"),
+            Err(access::Denial::CoolingDown(_))
+        ));
+        // A usage error still gets its reply while the cooldown runs.
+        assert!(check("100 USD").is_ok());
+    }
+}
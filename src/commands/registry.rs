@@ -0,0 +1,391 @@
+// src/commands/registry.rs
+
+//! Static metadata about every command.
+//!
+//! `BotCommands` only exposes names and descriptions, so anything else other
+//! modules need to know about a command (category, usage, aliases, limits)
+//! lives here. The tests keep this table in sync with the `Command` enum.
+
+use crate::commands::args::Signature;
+use crate::commands::{Command, fun, info, utils};
+use std::time::Duration;
+use teloxide::types::Message;
+use teloxide::utils::command::BotCommands;
+
+/// Help-page grouping, mirroring the handler submodules.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Category {
+    Fun,
+    Info,
+    System,
+    Utilities,
+}
+
+impl Category {
+    pub const ALL: [Category; 4] = [
+        Category::Fun,
+        Category::Info,
+        Category::System,
+        Category::Utilities,
+    ];
+
+    pub fn title(self) -> &'static str {
+        match self {
+            Self::Fun => "🎉 Fun",
+            Self::Info => "ℹ️ Info",
+            Self::System => "⚙️ System",
+            Self::Utilities => "🛠️ Utilities",
+        }
+    }
+
+    /// Stable identifier used in callback data.
+    pub fn slug(self) -> &'static str {
+        match self {
+            Self::Fun => "fun",
+            Self::Info => "info",
+            Self::System => "system",
+            Self::Utilities => "utils",
+        }
+    }
+
+    pub fn from_slug(slug: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|c| c.slug() == slug)
+    }
+}
+
+/// Kinds of chat a command may be used in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scope {
+    Anywhere,
+    GroupsOnly,
+}
+
+/// Metadata for a single command.
+#[derive(Debug)]
+pub struct CommandSpec {
+    /// Canonical name without the leading slash.
+    pub name: &'static str,
+    /// Alternative names accepted by the parser.
+    pub aliases: &'static [&'static str],
+    pub category: Category,
+    /// Typed arguments; their usage line takes precedence over `usage`.
+    pub args: Option<&'static Signature>,
+    /// Free-form synopsis for commands that parse their own arguments.
+    pub usage: &'static str,
+    /// Checks the arguments the way the handler parses them, where `args`
+    /// alone can't tell.
+    pub parses: Option<fn(&Message, &str) -> bool>,
+    /// Complete example invocations.
+    pub examples: &'static [&'static str],
+    /// Minimum time between two uses by the same user.
+    pub cooldown: Option<Duration>,
+    /// Restricted to the operators listed in `BOT_ADMINS`.
+    pub admin_only: bool,
+    pub scope: Scope,
+}
+
+/// Names are unique, so they identify a command.
+impl PartialEq for CommandSpec {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name
+    }
+}
+
+impl CommandSpec {
+    const fn new(name: &'static str, category: Category) -> Self {
+        Self {
+            name,
+            aliases: &[],
+            category,
+            args: None,
+            usage: "",
+            parses: None,
+            examples: &[],
+            cooldown: None,
+            admin_only: false,
+            scope: Scope::Anywhere,
+        }
+    }
+
+    const fn aliases(mut self, aliases: &'static [&'static str]) -> Self {
+        self.aliases = aliases;
+        self
+    }
+
+    const fn usage(mut self, usage: &'static str, examples: &'static [&'static str]) -> Self {
+        self.usage = usage;
+        self.examples = examples;
+        self
+    }
+
+    const fn args(mut self, args: &'static Signature, examples: &'static [&'static str]) -> Self {
+        self.args = Some(args);
+        self.examples = examples;
+        self
+    }
+
+    const fn parses(mut self, parses: fn(&Message, &str) -> bool) -> Self {
+        self.parses = Some(parses);
+        self
+    }
+
+    const fn cooldown(mut self, secs: u64) -> Self {
+        self.cooldown = Some(Duration::from_secs(secs));
+        self
+    }
+
+    const fn admin_only(mut self) -> Self {
+        self.admin_only = true;
+        self
+    }
+
+    const fn groups_only(mut self) -> Self {
+        self.scope = Scope::GroupsOnly;
+        self
+    }
+
+    /// The one-line description from the `Command` enum.
+    pub fn description(&self) -> String {
+        Command::bot_commands()
+            .into_iter()
+            .find(|c| c.command.trim_start_matches('/') == self.name)
+            .map(|c| c.description)
+            .unwrap_or_default()
+    }
+
+    /// Whether the handler will parse `text` as this command's arguments,
+    /// rather than reply with a usage error.
+    pub fn accepts(&self, msg: &Message, text: &str) -> bool {
+        match (self.parses, self.args) {
+            (Some(parses), _) => parses(msg, text),
+            (None, Some(args)) => args.parse(text).is_ok(),
+            (None, None) => true,
+        }
+    }
+
+    /// `/name <args>` as shown on help pages.
+    pub fn synopsis(&self) -> String {
+        let usage = match self.args {
+            Some(args) => args.usage(),
+            None => self.usage.to_string(),
+        };
+        if usage.is_empty() {
+            format!("/{}", self.name)
+        } else {
+            format!("/{} {}", self.name, usage)
+        }
+    }
+}
+
+/// Every command the bot understands, in the order of the `Command` enum.
+pub const COMMANDS: &[CommandSpec] = &[
+    CommandSpec::new("start", Category::System),
+    CommandSpec::new("ping", Category::System),
+    CommandSpec::new("help", Category::Info)
+        .aliases(&["commands"])
+        .args(&info::help::ARGS, &["/help", "/help weather"]),
+    CommandSpec::new("about", Category::Info),
+    CommandSpec::new("id", Category::Info).args(&info::id::ARGS, &["/id", "/id json"]),
+    CommandSpec::new("time", Category::Info),
+    CommandSpec::new("echo", Category::Utilities).args(&utils::echo::ARGS, &["/echo hello"]),
+    CommandSpec::new("weather", Category::Utilities)
+        .aliases(&["w"])
+        .args(
+            &utils::weather::ARGS,
+            &[
+                "/weather Lima",
+                "/weather",
+                "/weather Lima, Madrid, Berlin",
+                "/weather subscribe Lima 07:30",
+                "/weather subscriptions",
+            ],
+        )
+        .parses(utils::weather::parses)
+        .cooldown(5),
+    CommandSpec::new("forecast", Category::Utilities)
+        .args(
+            &utils::weather::forecast::ARGS,
+            &[
+                "/forecast Lima",
+                "/forecast Paris 2",
+                "/forecast Tokyo today",
+            ],
+        )
+        .cooldown(5),
+    CommandSpec::new("setcity", Category::Utilities)
+        .args(
+            &utils::weather::prefs::SETCITY_ARGS,
+            &["/setcity Lima", "/setcity clear"],
+        )
+        .cooldown(5),
+    CommandSpec::new("units", Category::Utilities).args(
+        &utils::weather::prefs::UNITS_ARGS,
+        &["/units imperial", "/units metric"],
+    ),
+    CommandSpec::new("weatheralert", Category::Utilities)
+        .args(
+            &utils::weather::alerts::ARGS,
+            &[
+                "/weatheralert Lima wind>50 rain>80%",
+                "/weatheralert Oslo gust>70",
+            ],
+        )
+        .parses(utils::weather::alerts::parses)
+        .cooldown(10),
+    CommandSpec::new("weatheralerts", Category::Utilities),
+    CommandSpec::new("currency", Category::Utilities)
+        .aliases(&["fx", "convert"])
+        .usage(
+            utils::currency::USAGE,
+            &[
+                "/currency 100 USD EUR",
+                "/currency $10 in €",
+                "/currency 1.5k JPY to USD,EUR,GBP",
+                "/currency 10,50 EUR USD",
+                "/currency 100 USD EUR 2025-01-31",
+                "/currency 0.5 BTC EUR",
+            ],
+        )
+        .parses(utils::currency::parses)
+        .cooldown(10),
+    CommandSpec::new("currencies", Category::Utilities).args(
+        &utils::currency::CURRENCIES_ARGS,
+        &["/currencies", "/currencies peso", "/currencies japan"],
+    ),
+    CommandSpec::new("fxchart", Category::Utilities)
+        .args(
+            &utils::currency::FXCHART_ARGS,
+            &[
+                "/fxchart USD EUR",
+                "/fxchart USD JPY 90d",
+                "/fxchart EUR GBP 1w",
+            ],
+        )
+        .cooldown(15),
+    CommandSpec::new("fxalert", Category::Utilities)
+        .args(
+            &utils::currency::alerts::FXALERT_ARGS,
+            &["/fxalert USD PEN > 3.9", "/fxalert EUR USD below 1.05"],
+        )
+        .parses(utils::currency::alerts::parses)
+        .cooldown(10),
+    CommandSpec::new("fxalerts", Category::Utilities),
+    CommandSpec::new("crypto", Category::Utilities)
+        .usage(
+            utils::currency::crypto::USAGE,
+            &["/crypto", "/crypto BTC ETH", "/crypto SOL DOGE in EUR"],
+        )
+        .parses(utils::currency::crypto::parses)
+        .cooldown(10),
+    CommandSpec::new("roll", Category::Fun)
+        .aliases(&["dice"])
+        .usage(
+            "[NdS[kh|kl N][+M]] [adv|dis] | 🎲 🎯 🏀 ⚽ 🎳 🎰",
+            &[
+                "/roll",
+                "/roll 2d20+5",
+                "/roll 4d6kh3",
+                "/roll adv",
+                "/roll 🎰",
+            ],
+        ),
+    CommandSpec::new("duel", Category::Fun)
+        .args(&fun::duel::ARGS, &["/duel @alice"])
+        .parses(fun::duel::parses)
+        .cooldown(10)
+        .groups_only(),
+    CommandSpec::new("leaderboard", Category::Fun)
+        .aliases(&["top"])
+        .groups_only(),
+    CommandSpec::new("joke", Category::Fun)
+        .args(
+            &fun::joke::ARGS,
+            &["/joke", "/joke programming", "/joke pun", "/joke top"],
+        )
+        .cooldown(3),
+    CommandSpec::new("addjoke", Category::Fun)
+        .args(
+            &fun::joke::community::ADDJOKE_ARGS,
+            &["/addjoke I told a chemistry joke. There was no reaction."],
+        )
+        .cooldown(30),
+    CommandSpec::new("jokequeue", Category::Fun).admin_only(),
+    CommandSpec::new("jotd", Category::Fun)
+        .args(
+            &fun::joke::daily::ARGS,
+            &[
+                "/jotd",
+                "/jotd subscribe",
+                "/jotd subscribe 07:30",
+                "/jotd unsubscribe",
+            ],
+        )
+        .parses(fun::joke::daily::parses)
+        .cooldown(5),
+    CommandSpec::new("jokesettings", Category::Fun)
+        .args(
+            &fun::joke::settings::ARGS,
+            &[
+                "/jokesettings",
+                "/jokesettings lang de",
+                "/jokesettings block political religious",
+                "/jokesettings safe off",
+            ],
+        )
+        .parses(fun::joke::settings::parses)
+        .cooldown(5),
+    CommandSpec::new("quota", Category::System).admin_only(),
+];
+
+/// Looks up a command by its name or one of its aliases.
+pub fn find(name: &str) -> Option<&'static CommandSpec> {
+    let name = name.trim_start_matches('/').to_lowercase();
+    COMMANDS
+        .iter()
+        .find(|spec| spec.name == name || spec.aliases.contains(&name.as_str()))
+}
+
+/// Commands belonging to `category`, in registry order.
+pub fn in_category(category: Category) -> impl Iterator<Item = &'static CommandSpec> {
+    COMMANDS
+        .iter()
+        .filter(move |spec| spec.category == category)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registry_matches_command_enum() {
+        for spec in COMMANDS {
+            for name in std::iter::once(&spec.name).chain(spec.aliases) {
+                assert!(
+                    Command::parse(&format!("/{}", name), "bot").is_ok(),
+                    "/{} is in the registry but does not parse",
+                    name
+                );
+            }
+            assert!(
+                !spec.description().is_empty(),
+                "/{} has no description",
+                spec.name
+            );
+        }
+
+        for command in Command::bot_commands() {
+            assert!(
+                find(&command.command).is_some(),
+                "{} is missing from the registry",
+                command.command
+            );
+        }
+    }
+
+    #[test]
+    fn test_find_by_alias() {
+        assert_eq!(find("/fx").map(|s| s.name), Some("currency"));
+        assert_eq!(find("Weather").map(|s| s.name), Some("weather"));
+        assert!(find("nope").is_none());
+    }
+}
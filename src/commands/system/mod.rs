@@ -1,4 +1,5 @@
 // src/commands/system/mod.rs
 
 pub mod ping;
+pub mod quota;
 pub mod start;
\ No newline at end of file
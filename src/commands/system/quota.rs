@@ -0,0 +1,116 @@
+use crate::services::currency::Health;
+use crate::services::quota::Budget;
+use crate::services::{ApiService, ProviderStatus};
+use std::sync::Arc;
+use std::time::Instant;
+use teloxide::{prelude::*, types::Message};
+
+/// Shows each currency provider's usage this billing period and its health.
+///
+/// Admin-only; access is enforced by the command registry.
+pub async fn handle_quota(bot: Bot, msg: Message, api: Arc<ApiService>) -> ResponseResult<()> {
+    let statuses = api.provider_status().await;
+    bot.send_message(msg.chat.id, format_status(&statuses, Instant::now()))
+        .await?;
+    Ok(())
+}
+
+fn format_status(statuses: &[ProviderStatus], now: Instant) -> String {
+    if statuses.is_empty() {
+        return "📊 No currency providers are configured.".to_string();
+    }
+
+    let mut lines = vec!["📊 Currency provider usage:".to_string()];
+    for status in statuses {
+        let usage = match &status.usage {
+            Some(usage) => {
+                let hard = usage
+                    .limits
+                    .hard
+                    .map(|h| format!(" / {}", h))
+                    .unwrap_or_default();
+                let soft = usage
+                    .limits
+                    .soft
+                    .map(|s| format!(" (soft {})", s))
+                    .unwrap_or_default();
+                let budget = match usage.limits.budget(usage.calls) {
+                    Budget::Available => "",
+                    Budget::Conserve => " · 🟡 preferring cache",
+                    Budget::Exhausted => " · ⛔ calls refused",
+                };
+                format!(
+                    "{}{} calls in {}{}{}",
+                    usage.calls, hard, usage.period, soft, budget
+                )
+            }
+            None => "local, unmetered".to_string(),
+        };
+        lines.push(format!(
+            "• {} — {}\n   {}",
+            status.name,
+            usage,
+            format_health(status.health, now)
+        ));
+    }
+    lines.join("\n")
+}
+
+fn format_health(health: Health, now: Instant) -> String {
+    let minutes = |at: Instant| at.saturating_duration_since(now).as_secs().div_ceil(60);
+    match health {
+        Health::Healthy => "✅ healthy".to_string(),
+        Health::Failing { failures, retry_at } => format!(
+            "⚠️ {} failure(s), retry in {} min",
+            failures,
+            minutes(retry_at)
+        ),
+        Health::Exhausted { retry_at } => {
+            format!(
+                "⛔ provider reported no quota, retry in {} min",
+                minutes(retry_at)
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::quota::{Limits, Usage};
+    use std::time::Duration;
+
+    #[test]
+    fn test_format_status() {
+        let now = Instant::now();
+        let statuses = [
+            ProviderStatus {
+                name: "exchangerate.host",
+                health: Health::Healthy,
+                usage: Some(Usage {
+                    period: "2025-01".to_string(),
+                    calls: 85,
+                    limits: Limits {
+                        soft: Some(80),
+                        hard: Some(100),
+                    },
+                }),
+            },
+            ProviderStatus {
+                name: "static rates file",
+                health: Health::Failing {
+                    failures: 2,
+                    retry_at: now + Duration::from_secs(90),
+                },
+                usage: None,
+            },
+        ];
+
+        assert_eq!(
+            format_status(&statuses, now),
+            "📊 Currency provider usage:\n\
+             • exchangerate.host — 85 / 100 calls in 2025-01 (soft 80) · 🟡 preferring cache\n   ✅ healthy\n\
+             • static rates file — local, unmetered\n   ⚠️ 2 failure(s), retry in 2 min"
+        );
+    }
+}
@@ -0,0 +1,95 @@
+// src/commands/utils/alerts.rs
+
+//! The alert list and its cancel buttons, shared by `/fxalerts` and
+//! `/weatheralerts`. Each kind of alert supplies its own wording.
+
+use crate::config::BotConfig;
+use crate::storage::{Alert, AlertStore, JsonStore};
+use log::error;
+use serde::{Serialize, de::DeserializeOwned};
+use teloxide::prelude::*;
+use teloxide::types::{InlineKeyboardButton, InlineKeyboardMarkup};
+
+/// How one kind of alert is listed.
+pub struct ListStyle {
+    /// Heading above the alerts, e.g. `🔔 FX alerts`.
+    pub title: &'static str,
+    /// Shown instead when the chat has no alerts.
+    pub empty: &'static str,
+    /// Callback data prefix that routes the cancel buttons.
+    pub prefix: &'static str,
+}
+
+/// The chat's alerts, one line and one cancel button each; `label`
+/// describes an alert, e.g. `#7 USD → PEN > 3.90`.
+pub fn render_list<A: Alert>(
+    alerts: &[A],
+    style: &ListStyle,
+    label: impl Fn(&A) -> String,
+) -> (String, InlineKeyboardMarkup) {
+    if alerts.is_empty() {
+        return (style.empty.to_string(), InlineKeyboardMarkup::default());
+    }
+
+    let mut lines = vec![format!("{} ({}):", style.title, alerts.len())];
+    let mut rows = Vec::with_capacity(alerts.len());
+    for alert in alerts {
+        let label = label(alert);
+        rows.push([InlineKeyboardButton::callback(
+            format!("❌ {}", label),
+            format!("{}{}", style.prefix, alert.owner().id),
+        )]);
+        lines.push(format!("• {}", label));
+    }
+    lines.push("Tap an alert to cancel it.".to_string());
+    (lines.join("\n"), InlineKeyboardMarkup::new(rows))
+}
+
+/// Cancels an alert from its button and refreshes the list in place with
+/// `render`.
+///
+/// Only the alert's creator or a bot admin may cancel it.
+pub async fn handle_cancel_callback<A>(
+    bot: Bot,
+    q: CallbackQuery,
+    data: &str,
+    store: &JsonStore<AlertStore<A>>,
+    config: &BotConfig,
+    render: impl Fn(&[A]) -> (String, InlineKeyboardMarkup),
+) -> ResponseResult<()>
+where
+    A: Alert,
+    AlertStore<A>: Serialize + DeserializeOwned,
+{
+    let (Ok(id), Some(message)) = (data.parse::<u64>(), q.message.as_ref()) else {
+        bot.answer_callback_query(q.id.clone()).await?;
+        return Ok(());
+    };
+    let chat_id = message.chat().id;
+    let user = q.from.id;
+
+    let cancelled = store
+        .update(|alerts| {
+            alerts.cancel(id, chat_id.0, |owner| {
+                owner.user_id == Some(user.0) || config.is_admin(user)
+            })
+        })
+        .await;
+    let notice = match cancelled {
+        Ok(Some(true)) => format!("Alert #{} cancelled.", id),
+        Ok(Some(false)) => "Only the alert's creator or an admin can cancel it.".to_string(),
+        Ok(None) => format!("Alert #{} no longer exists.", id),
+        Err(err) => {
+            error!("Failed to cancel alert #{}: {}", id, err);
+            "Failed to cancel the alert.".to_string()
+        }
+    };
+    bot.answer_callback_query(q.id.clone()).text(notice).await?;
+
+    let alerts = store.read(|a| a.in_chat(chat_id.0)).await;
+    let (text, keyboard) = render(&alerts);
+    bot.edit_message_text(chat_id, message.id(), text)
+        .reply_markup(keyboard)
+        .await?;
+    Ok(())
+}
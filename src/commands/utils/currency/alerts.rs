@@ -0,0 +1,229 @@
+// src/commands/utils/currency/alerts.rs
+
+//! `/fxalert` and `/fxalerts`: one-shot notifications when a rate crosses a
+//! threshold. The checks themselves run in `jobs::fx_alerts`.
+
+use super::catalogue;
+use super::format::{Locale, format_rate};
+use super::query::parse_number;
+use crate::commands::args::{Arg, ArgKind, Signature};
+use crate::commands::utils::alerts::{self, ListStyle, handle_cancel_callback};
+use crate::commands::utils::user_message;
+use crate::config::BotConfig;
+use crate::services::ApiService;
+use crate::storage::{AlertOwner, Direction, FxAlert, Storage};
+use log::error;
+use rust_decimal::Decimal;
+use std::sync::Arc;
+use teloxide::prelude::*;
+use teloxide::types::{InlineKeyboardMarkup, Message};
+
+/// Callback data prefix of the cancel buttons under `/fxalerts`.
+pub const FXALERT_PREFIX: &str = "fxalert:";
+
+/// Arguments accepted by `/fxalert`.
+pub const FXALERT_ARGS: Signature = Signature::new(&[
+    Arg::required("from", ArgKind::Currency(catalogue::parse_code)),
+    Arg::required("to", ArgKind::Currency(catalogue::parse_code)),
+    Arg::required("condition", ArgKind::Choice(&[">", "<", "above", "below"])),
+    Arg::required("threshold", ArgKind::Amount(parse_number)),
+]);
+
+/// Most pending alerts a single chat may hold.
+const MAX_ALERTS_PER_CHAT: usize = 10;
+
+const LIST_STYLE: ListStyle = ListStyle {
+    title: "🔔 FX alerts",
+    empty: "🔕 No FX alerts in this chat. Set one with /fxalert USD PEN > 3.9",
+    prefix: FXALERT_PREFIX,
+};
+
+/// Whether `text` parses as `/fxalert` arguments.
+pub fn parses(_msg: &Message, text: &str) -> bool {
+    FXALERT_ARGS.parse(&normalize(text)).is_ok()
+}
+
+/// Accepts `USD PEN >3.9` as well as `USD PEN > 3.9`.
+fn normalize(text: &str) -> String {
+    text.replace('>', " > ").replace('<', " < ")
+}
+
+/// Sets an alert that fires once the pair's rate crosses the threshold.
+pub async fn handle_fxalert(
+    bot: Bot,
+    msg: Message,
+    text: String,
+    api: Arc<ApiService>,
+    storage: Arc<Storage>,
+) -> ResponseResult<()> {
+    let text = normalize(&text);
+    let args = match FXALERT_ARGS.parse(&text) {
+        Ok(args) => args,
+        Err(err) => {
+            bot.send_message(msg.chat.id, err.render("fxalert", &text, &FXALERT_ARGS))
+                .await?;
+            return Ok(());
+        }
+    };
+    let (from, to) = (
+        args.currency("from").unwrap_or_default(),
+        args.currency("to").unwrap_or_default(),
+    );
+    let direction = match args.choice("condition") {
+        Some(">" | "above") => Direction::Above,
+        _ => Direction::Below,
+    };
+    let threshold = args.amount("threshold").unwrap_or_default();
+
+    let locale =
+        Locale::from_language_code(msg.from.as_ref().and_then(|u| u.language_code.as_deref()));
+    let alert = FxAlert {
+        owner: AlertOwner::new(msg.chat.id.0, msg.from.as_ref().map(|u| u.id.0)),
+        from: from.to_string(),
+        to: to.to_string(),
+        direction,
+        threshold: threshold.normalize(),
+    };
+
+    // Check the current rate first so alerts that would fire straight away
+    // (or pairs no provider knows) are reported instead of stored.
+    let rate = match api.convert_currency(Decimal::ONE, from, to).await {
+        Ok(conversion) => conversion.rate,
+        Err(err) => {
+            let what = format!("FX alert rate check for {} → {}", from, to);
+            let reason = user_message(&what, &err, "no provider quotes this pair");
+            bot.send_message(
+                msg.chat.id,
+                format!("❌ Can't watch {} → {}: {}.", from, to, reason),
+            )
+            .await?;
+            return Ok(());
+        }
+    };
+    if alert.is_triggered(rate) {
+        bot.send_message(
+            msg.chat.id,
+            format!(
+                "ℹ️ {} → {} is already {} ({}), so there is nothing to wait for.",
+                from,
+                to,
+                format_rate(rate, locale),
+                describe(&alert, locale)
+            ),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    let added = storage
+        .fx_alerts
+        .update(|alerts| alerts.add(alert.clone(), MAX_ALERTS_PER_CHAT))
+        .await;
+    let reply = match added {
+        Ok(Some(id)) => format!(
+            "🔔 Alert #{} set: {} → {} {}. Now {}. Cancel it with /fxalerts.",
+            id,
+            from,
+            to,
+            describe(&alert, locale),
+            format_rate(rate, locale)
+        ),
+        Ok(None) => format!(
+            "⚠️ This chat already has {} alerts. Cancel some with /fxalerts.",
+            MAX_ALERTS_PER_CHAT
+        ),
+        Err(err) => {
+            error!("Failed to save FX alert: {}", err);
+            "❌ Failed to save the alert.".to_string()
+        }
+    };
+    bot.send_message(msg.chat.id, reply).await?;
+    Ok(())
+}
+
+/// Lists this chat's pending alerts with a cancel button for each.
+pub async fn handle_fxalerts(bot: Bot, msg: Message, storage: Arc<Storage>) -> ResponseResult<()> {
+    let locale =
+        Locale::from_language_code(msg.from.as_ref().and_then(|u| u.language_code.as_deref()));
+    let alerts = storage.fx_alerts.read(|a| a.in_chat(msg.chat.id.0)).await;
+    let (text, keyboard) = render_list(&alerts, locale);
+    bot.send_message(msg.chat.id, text)
+        .reply_markup(keyboard)
+        .await?;
+    Ok(())
+}
+
+/// Cancels an alert from its button and refreshes the list in place.
+pub async fn handle_fxalert_callback(
+    bot: Bot,
+    q: CallbackQuery,
+    data: &str,
+    storage: Arc<Storage>,
+    config: Arc<BotConfig>,
+) -> ResponseResult<()> {
+    let locale = Locale::from_language_code(q.from.language_code.as_deref());
+    handle_cancel_callback(bot, q, data, &storage.fx_alerts, &config, |alerts| {
+        render_list(alerts, locale)
+    })
+    .await
+}
+
+/// The condition part of an alert, e.g. `> 3.9`.
+fn describe(alert: &FxAlert, locale: Locale) -> String {
+    format!(
+        "{} {}",
+        alert.direction.symbol(),
+        format_rate(alert.threshold, locale)
+    )
+}
+
+fn render_list(alerts: &[FxAlert], locale: Locale) -> (String, InlineKeyboardMarkup) {
+    alerts::render_list(alerts, &LIST_STYLE, |alert| {
+        format!(
+            "#{} {} → {} {}",
+            alert.owner.id,
+            alert.from,
+            alert.to,
+            describe(alert, locale)
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_alert_args() {
+        let args = FXALERT_ARGS.parse("usd pen above 3.9").unwrap();
+        assert_eq!(args.currency("from"), Some("USD"));
+        assert_eq!(args.choice("condition"), Some("above"));
+        assert_eq!(args.amount("threshold"), Some(Decimal::new(39, 1)));
+        assert!(FXALERT_ARGS.parse("USD PEN = 3.9").is_err());
+
+        let args = FXALERT_ARGS.parse(&normalize("USD PEN <3,9")).unwrap();
+        assert_eq!(args.choice("condition"), Some("<"));
+        assert_eq!(args.amount("threshold"), Some(Decimal::new(39, 1)));
+        assert!(FXALERT_ARGS.parse("USD PEN < 0").is_err());
+        assert!(FXALERT_ARGS.parse("USD PEN < -3,9").is_err());
+    }
+
+    #[test]
+    fn test_render_list() {
+        let alert = FxAlert {
+            owner: AlertOwner {
+                id: 7,
+                ..AlertOwner::new(1, None)
+            },
+            from: "USD".to_string(),
+            to: "PEN".to_string(),
+            direction: Direction::Above,
+            threshold: Decimal::new(39, 1),
+        };
+        let (text, _) = render_list(&[alert], Locale::ENGLISH);
+        assert_eq!(
+            text,
+            "🔔 FX alerts (1):\n• #7 USD → PEN > 3.90\nTap an alert to cancel it."
+        );
+    }
+}
@@ -0,0 +1,252 @@
+// src/commands/utils/currency/chart.rs
+
+//! Renders a rate history as a PNG line chart without external fonts.
+//!
+//! Axis labels use a tiny built-in bitmap font that covers digits and the
+//! few punctuation marks needed for rates and dates; titles and sources go
+//! in the photo caption instead.
+
+use crate::services::currency::DailyRate;
+use rust_decimal::prelude::ToPrimitive;
+
+const WIDTH: usize = 800;
+const HEIGHT: usize = 400;
+const MARGIN_LEFT: usize = 90;
+const MARGIN_RIGHT: usize = 24;
+const MARGIN_TOP: usize = 20;
+const MARGIN_BOTTOM: usize = 40;
+const GRID_LINES: usize = 5;
+/// Pixels per font cell.
+const FONT_SCALE: usize = 2;
+
+type Rgb = [u8; 3];
+
+const BACKGROUND: Rgb = [255, 255, 255];
+const AXIS: Rgb = [90, 90, 90];
+const GRID: Rgb = [225, 225, 225];
+const LINE: Rgb = [33, 110, 220];
+const FILL: Rgb = [222, 234, 251];
+const TEXT: Rgb = [60, 60, 60];
+
+/// Draws `points` (at least one) and encodes the result as PNG.
+pub fn render(points: &[DailyRate]) -> Result<Vec<u8>, png::EncodingError> {
+    let mut canvas = Canvas::new();
+    let values: Vec<f64> = points
+        .iter()
+        .map(|p| p.rate.to_f64().unwrap_or_default())
+        .collect();
+
+    let (mut low, mut high) = values
+        .iter()
+        .fold((f64::MAX, f64::MIN), |(lo, hi), v| (lo.min(*v), hi.max(*v)));
+    // Pad the range so a flat series still gets a visible band.
+    let pad = ((high - low) * 0.1)
+        .max(high.abs() * 0.001)
+        .max(f64::EPSILON);
+    low -= pad;
+    high += pad;
+
+    let plot_w = WIDTH - MARGIN_LEFT - MARGIN_RIGHT;
+    let plot_h = HEIGHT - MARGIN_TOP - MARGIN_BOTTOM;
+    let bottom = MARGIN_TOP + plot_h;
+    let x_of = |i: usize| {
+        let span = values.len().saturating_sub(1).max(1);
+        MARGIN_LEFT as f64 + plot_w as f64 * i as f64 / span as f64
+    };
+    let y_of = |v: f64| MARGIN_TOP as f64 + plot_h as f64 * (high - v) / (high - low);
+
+    // Horizontal grid with value labels.
+    for step in 0..=GRID_LINES {
+        let value = low + (high - low) * step as f64 / GRID_LINES as f64;
+        let y = y_of(value).round() as usize;
+        canvas.hline(MARGIN_LEFT, WIDTH - MARGIN_RIGHT, y, GRID);
+        let label = format_value(value);
+        let x = MARGIN_LEFT.saturating_sub(8 + text_width(&label));
+        canvas.text(x, y.saturating_sub(5 * FONT_SCALE / 2), &label, TEXT);
+    }
+
+    // Area under the line, then the line itself.
+    let line: Vec<(f64, f64)> = values
+        .iter()
+        .enumerate()
+        .map(|(i, v)| (x_of(i), y_of(*v)))
+        .collect();
+    for pair in line.windows(2) {
+        let ((x0, y0), (x1, y1)) = (pair[0], pair[1]);
+        for x in x0.round() as usize..=x1.round() as usize {
+            let t = if x1 > x0 {
+                (x as f64 - x0) / (x1 - x0)
+            } else {
+                0.0
+            };
+            let y = (y0 + (y1 - y0) * t.clamp(0.0, 1.0)).round() as usize;
+            canvas.vline(x, y, bottom, FILL);
+        }
+    }
+    for pair in line.windows(2) {
+        canvas.segment(pair[0], pair[1], LINE);
+    }
+    if let [(x, y)] = line[..] {
+        canvas.dot(x, y, 3, LINE);
+    }
+
+    // Axes.
+    canvas.hline(MARGIN_LEFT, WIDTH - MARGIN_RIGHT, bottom, AXIS);
+    canvas.vline(MARGIN_LEFT, MARGIN_TOP, bottom, AXIS);
+
+    // Date labels: first, middle and last day.
+    let mut label_indices = vec![0, points.len() / 2, points.len() - 1];
+    label_indices.dedup();
+    for i in label_indices {
+        let label = points[i].date.format("%m-%d").to_string();
+        let x = (x_of(i) as usize)
+            .saturating_sub(text_width(&label) / 2)
+            .min(WIDTH - text_width(&label));
+        canvas.text(x, bottom + 10, &label, TEXT);
+    }
+
+    canvas.encode()
+}
+
+/// Four significant digits, enough to tell grid lines apart.
+fn format_value(value: f64) -> String {
+    let magnitude = value.abs().log10().floor() as i32;
+    let decimals = (3 - magnitude).clamp(0, 8) as usize;
+    format!("{:.*}", decimals, value)
+}
+
+fn text_width(text: &str) -> usize {
+    text.chars().count() * 4 * FONT_SCALE
+}
+
+/// 3×5 glyphs, one row per byte, most significant of the low three bits first.
+fn glyph(c: char) -> Option<[u8; 5]> {
+    Some(match c {
+        '0' => [0b111, 0b101, 0b101, 0b101, 0b111],
+        '1' => [0b010, 0b110, 0b010, 0b010, 0b111],
+        '2' => [0b111, 0b001, 0b111, 0b100, 0b111],
+        '3' => [0b111, 0b001, 0b111, 0b001, 0b111],
+        '4' => [0b101, 0b101, 0b111, 0b001, 0b001],
+        '5' => [0b111, 0b100, 0b111, 0b001, 0b111],
+        '6' => [0b111, 0b100, 0b111, 0b101, 0b111],
+        '7' => [0b111, 0b001, 0b001, 0b001, 0b001],
+        '8' => [0b111, 0b101, 0b111, 0b101, 0b111],
+        '9' => [0b111, 0b101, 0b111, 0b001, 0b111],
+        '.' => [0b000, 0b000, 0b000, 0b000, 0b010],
+        '-' => [0b000, 0b000, 0b111, 0b000, 0b000],
+        _ => return None,
+    })
+}
+
+struct Canvas {
+    pixels: Vec<u8>,
+}
+
+impl Canvas {
+    fn new() -> Self {
+        Self {
+            pixels: BACKGROUND.repeat(WIDTH * HEIGHT),
+        }
+    }
+
+    fn set(&mut self, x: usize, y: usize, color: Rgb) {
+        if x < WIDTH && y < HEIGHT {
+            let i = (y * WIDTH + x) * 3;
+            self.pixels[i..i + 3].copy_from_slice(&color);
+        }
+    }
+
+    fn hline(&mut self, x0: usize, x1: usize, y: usize, color: Rgb) {
+        (x0..=x1).for_each(|x| self.set(x, y, color));
+    }
+
+    fn vline(&mut self, x: usize, y0: usize, y1: usize, color: Rgb) {
+        (y0.min(y1)..=y0.max(y1)).for_each(|y| self.set(x, y, color));
+    }
+
+    fn dot(&mut self, cx: f64, cy: f64, radius: i64, color: Rgb) {
+        for dy in -radius..=radius {
+            for dx in -radius..=radius {
+                if dx * dx + dy * dy <= radius * radius {
+                    let (x, y) = (cx.round() as i64 + dx, cy.round() as i64 + dy);
+                    if x >= 0 && y >= 0 {
+                        self.set(x as usize, y as usize, color);
+                    }
+                }
+            }
+        }
+    }
+
+    /// A two-pixel-thick segment, stepped along its longer axis.
+    fn segment(&mut self, (x0, y0): (f64, f64), (x1, y1): (f64, f64), color: Rgb) {
+        let steps = (x1 - x0).abs().max((y1 - y0).abs()).ceil().max(1.0) as usize;
+        for step in 0..=steps {
+            let t = step as f64 / steps as f64;
+            self.dot(x0 + (x1 - x0) * t, y0 + (y1 - y0) * t, 1, color);
+        }
+    }
+
+    fn text(&mut self, x: usize, y: usize, text: &str, color: Rgb) {
+        for (n, c) in text.chars().enumerate() {
+            let Some(rows) = glyph(c) else { continue };
+            let left = x + n * 4 * FONT_SCALE;
+            for (row, bits) in rows.iter().enumerate() {
+                for col in 0..3 {
+                    if bits & (0b100 >> col) != 0 {
+                        for (dx, dy) in
+                            (0..FONT_SCALE).flat_map(|a| (0..FONT_SCALE).map(move |b| (a, b)))
+                        {
+                            self.set(
+                                left + col * FONT_SCALE + dx,
+                                y + row * FONT_SCALE + dy,
+                                color,
+                            );
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn encode(self) -> Result<Vec<u8>, png::EncodingError> {
+        let mut bytes = Vec::new();
+        let mut encoder = png::Encoder::new(&mut bytes, WIDTH as u32, HEIGHT as u32);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder.write_header()?.write_image_data(&self.pixels)?;
+        Ok(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+    use rust_decimal::Decimal;
+
+    #[test]
+    fn test_renders_png() {
+        let start = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+        let points: Vec<DailyRate> = (0..30)
+            .map(|i| DailyRate {
+                date: start + chrono::Days::new(i),
+                rate: Decimal::new(9200 + (i as i64 % 7) * 13, 4),
+            })
+            .collect();
+
+        let bytes = render(&points).unwrap();
+        let decoder = png::Decoder::new(bytes.as_slice());
+        let reader = decoder.read_info().unwrap();
+        assert_eq!(reader.info().width, WIDTH as u32);
+        assert_eq!(reader.info().height, HEIGHT as u32);
+
+        assert!(render(&points[..1]).is_ok());
+    }
+
+    #[test]
+    fn test_format_value() {
+        assert_eq!(format_value(0.92346), "0.9235");
+        assert_eq!(format_value(151.2345), "151.2");
+        assert_eq!(format_value(0.0000612345), "0.00006123");
+    }
+}
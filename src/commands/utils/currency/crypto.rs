@@ -0,0 +1,262 @@
+// src/commands/utils/currency/crypto.rs
+
+//! `/crypto`: current prices and 24-hour change for a handful of coins.
+
+use super::catalogue;
+use super::format::{Locale, format_money, format_number, format_rate};
+use crate::services::{ApiService, Conversion};
+use chrono::Utc;
+use log::error;
+use rust_decimal::Decimal;
+use std::fmt;
+use std::sync::Arc;
+use teloxide::prelude::*;
+use teloxide::types::Message;
+
+/// Synopsis of the `/crypto` arguments, shown on help pages.
+pub const USAGE: &str = "[<coin>...] [in <currency>]";
+
+/// Coins shown by a bare `/crypto`.
+const DEFAULT_COINS: &[&str] = &["BTC", "ETH"];
+/// Currency prices are quoted in unless another is named.
+const DEFAULT_QUOTE: &str = "USD";
+/// Most coins priced in one reply.
+const MAX_COINS: usize = 8;
+
+/// Coins to price and the currency to price them in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct CryptoQuery {
+    coins: Vec<String>,
+    quote: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum CryptoQueryError {
+    Unknown(String),
+    TwoQuotes(String, String),
+    TooManyCoins,
+}
+
+impl fmt::Display for CryptoQueryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unknown(token) => write!(f, "'{}' is not a supported coin or currency.", token),
+            Self::TwoQuotes(a, b) => write!(f, "Prices can be shown in {} or {}, not both.", a, b),
+            Self::TooManyCoins => write!(f, "At most {} coins at a time.", MAX_COINS),
+        }
+    }
+}
+
+/// Whether `text` parses as `/crypto` arguments.
+pub fn parses(_msg: &Message, text: &str) -> bool {
+    parse_query(text).is_ok()
+}
+
+/// Parses e.g. `BTC ETH`, `sol, doge in EUR`, `bitcoin GBP` or
+/// `bitcoin cash, usd coin`.
+///
+/// Crypto assets are the coins to price; a single fiat currency, with or
+/// without `in`/`to`, is what they are priced in. Names of several words
+/// are matched longest first.
+fn parse_query(text: &str) -> Result<CryptoQuery, CryptoQueryError> {
+    let mut coins: Vec<String> = Vec::new();
+    let mut quote: Option<String> = None;
+
+    for segment in text.split(',') {
+        let words: Vec<&str> = segment.split_whitespace().collect();
+        let mut i = 0;
+        while i < words.len() {
+            if ["in", "to"]
+                .iter()
+                .any(|c| c.eq_ignore_ascii_case(words[i]))
+            {
+                i += 1;
+                continue;
+            }
+            let longest = catalogue::MAX_ALIAS_WORDS.min(words.len() - i);
+            let (currency, len) = (1..=longest)
+                .rev()
+                .find_map(|len| Some((catalogue::resolve(&words[i..i + len].join(" "))?, len)))
+                .ok_or_else(|| CryptoQueryError::Unknown(words[i].to_string()))?;
+            i += len;
+
+            if catalogue::is_crypto(currency.code) {
+                if !coins.iter().any(|c| c == currency.code) {
+                    coins.push(currency.code.to_string());
+                }
+            } else {
+                match &quote {
+                    Some(existing) if existing != currency.code => {
+                        return Err(CryptoQueryError::TwoQuotes(
+                            existing.clone(),
+                            currency.code.to_string(),
+                        ));
+                    }
+                    _ => quote = Some(currency.code.to_string()),
+                }
+            }
+        }
+    }
+
+    if coins.len() > MAX_COINS {
+        return Err(CryptoQueryError::TooManyCoins);
+    }
+    if coins.is_empty() {
+        coins = DEFAULT_COINS.iter().map(|c| c.to_string()).collect();
+    }
+    Ok(CryptoQuery {
+        coins,
+        quote: quote.unwrap_or_else(|| DEFAULT_QUOTE.to_string()),
+    })
+}
+
+/// Shows the price of each coin with its 24-hour change.
+pub async fn handle_crypto(
+    bot: Bot,
+    msg: Message,
+    text: String,
+    api: Arc<ApiService>,
+) -> ResponseResult<()> {
+    let query = match parse_query(&text) {
+        Ok(query) => query,
+        Err(err) => {
+            bot.send_message(
+                msg.chat.id,
+                format!(
+                    "⚠️ {}\nUsage: /crypto {}\nExamples: /crypto, /crypto BTC ETH, /crypto SOL in EUR",
+                    err, USAGE
+                ),
+            )
+            .await?;
+            return Ok(());
+        }
+    };
+
+    let locale =
+        Locale::from_language_code(msg.from.as_ref().and_then(|u| u.language_code.as_deref()));
+    let mut lines = vec![format!("🪙 Crypto prices in {}", query.quote)];
+    let prices = match api.prices(&query.coins, &query.quote).await {
+        Ok(prices) => prices,
+        Err(err) => {
+            error!("Crypto price lookup failed: {}", err);
+            bot.send_message(msg.chat.id, "❌ Crypto prices are unavailable right now.")
+                .await?;
+            return Ok(());
+        }
+    };
+    let mut sources = Vec::new();
+    for (coin, price) in query.coins.iter().zip(prices) {
+        match price {
+            Some(price) => {
+                if !sources.contains(&price.source) {
+                    sources.push(price.source);
+                }
+                lines.push(format_price(&price, locale));
+            }
+            None => lines.push(format!("• {} ❌ no price available", coin)),
+        }
+    }
+    if !sources.is_empty() {
+        lines.push(format!(
+            "Source: {} · {}",
+            sources.join(", "),
+            Utc::now().format("%Y-%m-%d %H:%M UTC")
+        ));
+    }
+
+    bot.send_message(msg.chat.id, lines.join("\n")).await?;
+    Ok(())
+}
+
+/// One coin's line, e.g. `• BTC $97,123.45 ▲ +2.31% (24h)`.
+fn format_price(price: &Conversion, locale: Locale) -> String {
+    // Coins worth less than a unit need more than two decimals.
+    let amount = if price.rate >= Decimal::ONE {
+        format_money(price.rate, &price.to, locale)
+    } else {
+        format!("{} {}", format_rate(price.rate, locale), price.to)
+    };
+    let change = match price.change_24h {
+        Some(change) if change.is_sign_negative() && !change.is_zero() => {
+            format!(" ▼ {}% (24h)", format_number(change, 2, locale))
+        }
+        Some(change) => format!(" ▲ +{}% (24h)", format_number(change, 2, locale)),
+        None => String::new(),
+    };
+    format!("• {} {}{}", price.from, amount, change)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::DateTime;
+
+    #[test]
+    fn test_parse_query() {
+        assert_eq!(
+            parse_query("").unwrap(),
+            CryptoQuery {
+                coins: vec!["BTC".to_string(), "ETH".to_string()],
+                quote: "USD".to_string()
+            }
+        );
+        assert_eq!(
+            parse_query("sol, bitcoin in eur").unwrap(),
+            CryptoQuery {
+                coins: vec!["SOL".to_string(), "BTC".to_string()],
+                quote: "EUR".to_string()
+            }
+        );
+        assert_eq!(
+            parse_query("bitcoin cash, usd coin in eur").unwrap(),
+            CryptoQuery {
+                coins: vec!["BCH".to_string(), "USDC".to_string()],
+                quote: "EUR".to_string()
+            }
+        );
+        assert_eq!(
+            parse_query("bitcoin us dollar").unwrap(),
+            CryptoQuery {
+                coins: vec!["BTC".to_string()],
+                quote: "USD".to_string()
+            }
+        );
+        assert_eq!(
+            parse_query("BTC EUR GBP"),
+            Err(CryptoQueryError::TwoQuotes(
+                "EUR".to_string(),
+                "GBP".to_string()
+            ))
+        );
+        assert_eq!(
+            parse_query("BTC FOO"),
+            Err(CryptoQueryError::Unknown("FOO".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_format_price() {
+        let price = |from: &str, rate: &str, change: Option<&str>| Conversion {
+            amount: Decimal::ONE,
+            from: from.to_string(),
+            to: "USD".to_string(),
+            result: rate.parse().unwrap(),
+            rate: rate.parse().unwrap(),
+            fetched_at: DateTime::UNIX_EPOCH,
+            change_24h: change.map(|c| c.parse().unwrap()),
+            source: "CoinGecko",
+        };
+        assert_eq!(
+            format_price(&price("BTC", "97123.456", Some("2.3145")), Locale::ENGLISH),
+            "• BTC $97,123.46 ▲ +2.31% (24h)"
+        );
+        assert_eq!(
+            format_price(&price("DOGE", "0.1623456", Some("-1.2")), Locale::ENGLISH),
+            "• DOGE 0.162346 USD ▼ -1.20% (24h)"
+        );
+        assert_eq!(
+            format_price(&price("ETH", "3000", None), Locale::ENGLISH),
+            "• ETH $3,000.00"
+        );
+    }
+}
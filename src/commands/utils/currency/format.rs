@@ -0,0 +1,187 @@
+// src/commands/utils/currency/format.rs
+
+//! Locale-aware rendering of money amounts and exchange rates.
+
+use super::catalogue;
+use rust_decimal::{Decimal, RoundingStrategy};
+
+/// Number formatting conventions for a user's language.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Locale {
+    pub decimal: char,
+    pub group: char,
+    /// Whether the symbol follows the number (`12,50 €`) or precedes it (`€12.50`).
+    pub symbol_after: bool,
+}
+
+impl Locale {
+    pub const ENGLISH: Locale = Locale {
+        decimal: '.',
+        group: ',',
+        symbol_after: false,
+    };
+
+    /// Picks conventions from a Telegram `language_code` such as `de` or `pt-br`.
+    pub fn from_language_code(code: Option<&str>) -> Self {
+        let language = code
+            .unwrap_or("en")
+            .split(['-', '_'])
+            .next()
+            .unwrap_or("en")
+            .to_ascii_lowercase();
+
+        match language.as_str() {
+            "de" | "es" | "it" | "pt" | "nl" | "id" | "tr" | "da" | "el" | "ro" | "hr" | "sl" => {
+                Locale {
+                    decimal: ',',
+                    group: '.',
+                    symbol_after: true,
+                }
+            }
+            "fr" | "ru" | "uk" | "pl" | "cs" | "sk" | "sv" | "fi" | "nb" | "no" | "hu" | "bg" => {
+                Locale {
+                    decimal: ',',
+                    group: '\u{202f}',
+                    symbol_after: true,
+                }
+            }
+            _ => Self::ENGLISH,
+        }
+    }
+}
+
+/// Number of decimal places for a currency (ISO 4217 minor units).
+///
+/// Codes missing from the catalogue default to two.
+pub fn minor_units(code: &str) -> u32 {
+    catalogue::by_code(code).map_or(2, |c| c.minor_units)
+}
+
+/// Rounds `amount` to the currency's minor units, half away from zero.
+pub fn round_to_minor_units(amount: Decimal, code: &str) -> Decimal {
+    amount.round_dp_with_strategy(minor_units(code), RoundingStrategy::MidpointAwayFromZero)
+}
+
+/// Formats `amount` in `code` for `locale`, e.g. `$1,234.50` or `1.234,50 €`.
+///
+/// Currencies without a well-known symbol are shown with their code.
+/// Crypto amounts drop trailing zeros (`₿0.50`, not `₿0.50000000`).
+pub fn format_money(amount: Decimal, code: &str, locale: Locale) -> String {
+    let rounded = round_to_minor_units(amount, code);
+    let dp = if catalogue::is_crypto(code) {
+        rounded.normalize().scale().clamp(2, minor_units(code))
+    } else {
+        minor_units(code)
+    };
+    let number = format_number(rounded, dp, locale);
+
+    let symbol = catalogue::by_code(code).and_then(|c| c.symbol);
+    match (symbol, locale.symbol_after) {
+        (Some(symbol), false) => format!("{}{}", symbol, number),
+        (Some(symbol), true) => format!("{} {}", number, symbol),
+        (None, _) => format!("{} {}", number, code),
+    }
+}
+
+/// Formats an exchange rate with enough significant digits to be useful
+/// for both strong (`0.000061`) and weak (`150.23`) currency pairs.
+pub fn format_rate(rate: Decimal, locale: Locale) -> String {
+    const SIGNIFICANT: u32 = 6;
+
+    let rate = rate.abs();
+    let dp = if rate >= Decimal::ONE {
+        let integer_digits = rate.trunc().to_string().len() as u32;
+        SIGNIFICANT.saturating_sub(integer_digits).max(2)
+    } else {
+        let fraction = rate.fract().to_string();
+        let digits = fraction.trim_start_matches("0.");
+        let leading_zeros = (digits.len() - digits.trim_start_matches('0').len()) as u32;
+        leading_zeros + SIGNIFICANT
+    };
+
+    let rounded = rate
+        .round_dp_with_strategy(dp, RoundingStrategy::MidpointAwayFromZero)
+        .normalize();
+    format_number(rounded, rounded.scale().max(2), locale)
+}
+
+/// Renders `value` with exactly `dp` decimals and locale separators.
+pub fn format_number(value: Decimal, dp: u32, locale: Locale) -> String {
+    let fixed = format!("{:.*}", dp as usize, value.abs());
+    let (integer, fraction) = fixed.split_once('.').unwrap_or((&fixed, ""));
+
+    let mut grouped = String::with_capacity(integer.len() + integer.len() / 3);
+    for (i, digit) in integer.chars().enumerate() {
+        if i > 0 && (integer.len() - i) % 3 == 0 {
+            grouped.push(locale.group);
+        }
+        grouped.push(digit);
+    }
+
+    let sign = if value.is_sign_negative() && !value.is_zero() {
+        "-"
+    } else {
+        ""
+    };
+    if fraction.is_empty() {
+        format!("{}{}", sign, grouped)
+    } else {
+        format!("{}{}{}{}", sign, grouped, locale.decimal, fraction)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn d(s: &str) -> Decimal {
+        Decimal::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn test_rounds_to_minor_units() {
+        assert_eq!(round_to_minor_units(d("92.34567812"), "EUR"), d("92.35"));
+        assert_eq!(round_to_minor_units(d("1234.5"), "JPY"), d("1235"));
+        assert_eq!(round_to_minor_units(d("1.23456"), "KWD"), d("1.235"));
+        assert_eq!(
+            round_to_minor_units(d("0.123456789"), "BTC"),
+            d("0.12345679")
+        );
+    }
+
+    #[test]
+    fn test_format_money_by_locale() {
+        let de = Locale::from_language_code(Some("de"));
+        let fr = Locale::from_language_code(Some("fr-FR"));
+
+        assert_eq!(
+            format_money(d("1234.5"), "USD", Locale::ENGLISH),
+            "$1,234.50"
+        );
+        assert_eq!(format_money(d("1234.5"), "EUR", de), "1.234,50 €");
+        assert_eq!(format_money(d("0.5"), "BTC", Locale::ENGLISH), "₿0.50");
+        assert_eq!(
+            format_money(d("0.000123456"), "ETH", Locale::ENGLISH),
+            "Ξ0.00012346"
+        );
+        assert_eq!(format_money(d("1234.5"), "EUR", fr), "1\u{202f}234,50 €");
+        assert_eq!(
+            format_money(d("150000.4"), "JPY", Locale::ENGLISH),
+            "¥150,000"
+        );
+        assert_eq!(format_money(d("3.5"), "CHF", Locale::ENGLISH), "3.50 CHF");
+        assert_eq!(format_money(d("0.1234"), "KWD", de), "0,123 KWD");
+    }
+
+    #[test]
+    fn test_format_rate() {
+        assert_eq!(format_rate(d("0.923456789"), Locale::ENGLISH), "0.923457");
+        assert_eq!(
+            format_rate(d("0.0000612345"), Locale::ENGLISH),
+            "0.0000612345"
+        );
+        assert_eq!(format_rate(d("151.2345"), Locale::ENGLISH), "151.235");
+        assert_eq!(format_rate(d("1.1"), Locale::ENGLISH), "1.10");
+    }
+}
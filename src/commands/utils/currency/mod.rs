@@ -0,0 +1,370 @@
+use crate::commands::args::{Arg, ArgKind, Signature};
+use crate::commands::utils::user_message;
+use crate::services::{ApiService, Conversion, RateHistory};
+use chrono::{Days, Timelike, Utc};
+use log::error;
+use rust_decimal::Decimal;
+use std::sync::Arc;
+use std::time::Duration;
+use teloxide::{
+    prelude::*,
+    types::{InputFile, Message},
+};
+
+pub mod alerts;
+pub mod chart;
+pub mod crypto;
+pub mod format;
+pub mod query;
+
+pub use crate::services::currency::catalogue;
+use catalogue::{CRYPTOCURRENCIES, CURRENCIES, Currency};
+use format::{Locale, format_money, format_rate};
+use query::parse_query;
+
+//
+// ## API Provider
+// [exchangerate.host](https://exchangerate.host)
+//
+// ## API Documentation
+// [https://exchangerate.host/documentation](https://exchangerate.host/documentation)
+//
+// ## API Endpoint
+// `/convert` – Converts an amount from one currency to another.
+//
+// ## Required Parameters
+// - `access_key`: Your API key (required for authentication).
+// - `from`: The base currency (e.g., "USD").
+// - `to`: The target currency (e.g., "EUR").
+// - `amount`: The amount to convert (e.g., 100.0).
+//
+// ## Terms of Use
+// - An API key is required. You can obtain one by signing up at [exchangerate.host](https://exchangerate.host).
+// - The free plan allows up to **100 requests per month**. Calls are metered
+//   per billing period (`API_QUOTAS`, default soft 80 / hard 100); admins can
+//   check usage with `/quota`.
+// - Higher tiers are available for increased usage and additional features.
+// - Usage beyond the free tier may require upgrading to a paid plan.
+// For more about pricing, visit: [https://exchangerate.host/pricing](https://exchangerate.host/pricing)
+//
+// ## Fallbacks
+// Rates come from the provider chain in `services::currency`, so the keyless
+// Frankfurter (ECB) API or a static rates file can answer when
+// exchangerate.host is unconfigured, failing or out of quota. Crypto assets
+// (`0.5 BTC EUR`) are priced by CoinGecko through the same chain, cache and
+// quotas.
+
+/// Synopsis of the `/currency` query grammar, shown on help pages.
+pub const USAGE: &str = "<amount> <from> [to|in] <target>[,<target>...] [YYYY-MM-DD]";
+
+/// Arguments accepted by `/fxchart`.
+pub const FXCHART_ARGS: Signature = Signature::new(&[
+    Arg::required("from", ArgKind::Currency(catalogue::parse_code)),
+    Arg::required("to", ArgKind::Currency(catalogue::parse_code)),
+    Arg::optional("period", ArgKind::Duration),
+]);
+
+/// Chart period when none is given.
+const DEFAULT_CHART_DAYS: u64 = 30;
+/// Longest period a chart may cover.
+const MAX_CHART_DAYS: u64 = 365;
+
+/// Arguments accepted by `/currencies`.
+pub const CURRENCIES_ARGS: Signature = Signature::new(&[Arg::optional("search", ArgKind::Text)]);
+
+/// Most search results listed in one reply.
+const MAX_SEARCH_RESULTS: usize = 20;
+
+/// Whether `text` parses as `/currency` arguments.
+pub fn parses(_msg: &Message, text: &str) -> bool {
+    parse_query(text).is_ok()
+}
+
+/// Converts an amount into one or more currencies, showing the rate used
+/// and when it was published.
+pub async fn handle_currency(
+    bot: Bot,
+    msg: Message,
+    text: String,
+    api: Arc<ApiService>,
+) -> ResponseResult<()> {
+    let query = match parse_query(&text) {
+        Ok(query) => query,
+        Err(err) => {
+            bot.send_message(
+                msg.chat.id,
+                format!(
+                    "⚠️ {}\nUsage: /currency {}\nExamples: /currency 10 USD to EUR, \
+                     /currency $10 in €, /currency 1.5k JPY to USD,EUR, \
+                     /currency 100 USD EUR 2025-01-31, /currency 0.5 BTC EUR",
+                    err, USAGE
+                ),
+            )
+            .await?;
+            return Ok(());
+        }
+    };
+
+    let locale =
+        Locale::from_language_code(msg.from.as_ref().and_then(|u| u.language_code.as_deref()));
+    let mut lines = Vec::with_capacity(query.targets.len());
+    for target in &query.targets {
+        let conversion = match query.date {
+            Some(date) => {
+                api.convert_currency_at(query.amount, &query.from, target, date)
+                    .await
+            }
+            None => {
+                api.convert_currency(query.amount, &query.from, target)
+                    .await
+            }
+        };
+        match conversion {
+            Ok(conversion) => lines.push(format_conversion(&conversion, locale)),
+            Err(err) => {
+                let what = format!("Currency conversion {} → {}", query.from, target);
+                let reason = user_message(&what, &err, "no provider quotes this pair");
+                lines.push(format!("❌ {} → {}: {}.", query.from, target, reason));
+            }
+        }
+    }
+
+    bot.send_message(msg.chat.id, lines.join("\n\n")).await?;
+    Ok(())
+}
+
+/// Lists the supported currencies, or those matching a search term such as
+/// a code, name, unit or country.
+pub async fn handle_currencies(bot: Bot, msg: Message, text: String) -> ResponseResult<()> {
+    let reply = match CURRENCIES_ARGS.parse(&text) {
+        Ok(args) => match args.text("search") {
+            Some(term) => format_search(term, &catalogue::search(term)),
+            None => format_catalogue(),
+        },
+        Err(err) => err.render("currencies", &text, &CURRENCIES_ARGS),
+    };
+    bot.send_message(msg.chat.id, reply).await?;
+    Ok(())
+}
+
+/// All codes grouped by initial letter.
+fn format_catalogue() -> String {
+    let mut lines = vec![format!("💱 {} supported currencies:", CURRENCIES.len())];
+    for group in CURRENCIES.chunk_by(|a, b| a.code[..1] == b.code[..1]) {
+        let codes: Vec<_> = group.iter().map(|c| c.code).collect();
+        lines.push(codes.join(" "));
+    }
+    let coins: Vec<_> = CRYPTOCURRENCIES.iter().map(|c| c.code).collect();
+    lines.push(format!("🪙 Crypto: {}", coins.join(" ")));
+    lines.push("Search by name or country, e.g. /currencies peso".to_string());
+    lines.join("\n")
+}
+
+/// One line per match, capped at [`MAX_SEARCH_RESULTS`].
+fn format_search(term: &str, matches: &[&Currency]) -> String {
+    if matches.is_empty() {
+        return format!("🔍 No currency matches '{}'.", term);
+    }
+
+    let mut lines = vec![format!("🔍 Currencies matching '{}':", term)];
+    lines.extend(matches.iter().take(MAX_SEARCH_RESULTS).map(|c| {
+        let symbol = c.symbol.map(|s| format!(" ({})", s)).unwrap_or_default();
+        format!("• {} — {}{}", c.code, c.name, symbol)
+    }));
+    if matches.len() > MAX_SEARCH_RESULTS {
+        lines.push(format!("…and {} more.", matches.len() - MAX_SEARCH_RESULTS));
+    }
+    lines.join("\n")
+}
+
+/// Renders one conversion with its rate and publication time, rounding
+/// amounts to each currency's minor units.
+fn format_conversion(c: &Conversion, locale: Locale) -> String {
+    format!(
+        "🔄 {} = {}\n📈 1 {} = {} {} · {} · {}",
+        format_money(c.amount, &c.from, locale),
+        format_money(c.result, &c.to, locale),
+        c.from,
+        format_rate(c.rate, locale),
+        c.to,
+        // Daily reference rates carry only a date.
+        if c.fetched_at.num_seconds_from_midnight() == 0 {
+            c.fetched_at.format("%Y-%m-%d")
+        } else {
+            c.fetched_at.format("%Y-%m-%d %H:%M UTC")
+        },
+        c.source
+    )
+}
+
+/// Sends a line chart of a pair's daily rates over a period such as `30d`.
+pub async fn handle_fxchart(
+    bot: Bot,
+    msg: Message,
+    text: String,
+    api: Arc<ApiService>,
+) -> ResponseResult<()> {
+    let args = match FXCHART_ARGS.parse(&text) {
+        Ok(args) => args,
+        Err(err) => {
+            bot.send_message(msg.chat.id, err.render("fxchart", &text, &FXCHART_ARGS))
+                .await?;
+            return Ok(());
+        }
+    };
+    let (from, to) = (
+        args.currency("from").unwrap_or_default(),
+        args.currency("to").unwrap_or_default(),
+    );
+    let days = match chart_days(args.duration("period")) {
+        Some(days) => days,
+        None => {
+            bot.send_message(
+                msg.chat.id,
+                format!(
+                    "⚠️ Charts cover 2 to {} days, e.g. /fxchart {} {} 30d",
+                    MAX_CHART_DAYS, from, to
+                ),
+            )
+            .await?;
+            return Ok(());
+        }
+    };
+
+    let end = Utc::now().date_naive();
+    let start = end - Days::new(days);
+    let history = match api.rate_history(from, to, start, end).await {
+        Ok(history) => history,
+        Err(err) => {
+            let what = format!("Rate history for {} → {}", from, to);
+            let reason = user_message(&what, &err, "no provider has history for this pair");
+            bot.send_message(
+                msg.chat.id,
+                format!("❌ No rate history for {} → {}: {}.", from, to, reason),
+            )
+            .await?;
+            return Ok(());
+        }
+    };
+
+    let png = match chart::render(&history.points) {
+        Ok(png) => png,
+        Err(err) => {
+            error!("Chart rendering failed: {}", err);
+            bot.send_message(msg.chat.id, "❌ Failed to draw the chart.")
+                .await?;
+            return Ok(());
+        }
+    };
+
+    let locale =
+        Locale::from_language_code(msg.from.as_ref().and_then(|u| u.language_code.as_deref()));
+    bot.send_photo(msg.chat.id, InputFile::memory(png).file_name("fxchart.png"))
+        .caption(chart_caption(&history, days, locale))
+        .await?;
+    Ok(())
+}
+
+/// Whole days covered by a chart period, if within the allowed range.
+fn chart_days(period: Option<Duration>) -> Option<u64> {
+    let days = period.map_or(DEFAULT_CHART_DAYS, |p| p.as_secs().div_ceil(86_400));
+    (2..=MAX_CHART_DAYS).contains(&days).then_some(days)
+}
+
+/// Title, range and change summary shown under the chart.
+fn chart_caption(history: &RateHistory, days: u64, locale: Locale) -> String {
+    let rates = history.points.iter().map(|p| p.rate);
+    let low = rates.clone().min().unwrap_or_default();
+    let high = rates.max().unwrap_or_default();
+    let (first, last) = match (history.points.first(), history.points.last()) {
+        (Some(first), Some(last)) => (first, last),
+        _ => return format!("📈 {} → {}", history.from, history.to),
+    };
+    let change = if first.rate.is_zero() {
+        Decimal::ZERO
+    } else {
+        ((last.rate - first.rate) / first.rate * Decimal::ONE_HUNDRED).round_dp(2)
+    };
+
+    format!(
+        "📈 {} → {} · last {} days\nLow {} · High {} · Last {} ({})\nChange: {}{}%\nSource: {}",
+        history.from,
+        history.to,
+        days,
+        format_rate(low, locale),
+        format_rate(high, locale),
+        format_rate(last.rate, locale),
+        last.date.format("%Y-%m-%d"),
+        if change.is_sign_positive() { "+" } else { "" },
+        format::format_number(change, 2, locale),
+        history.source
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::args::ArgErrorKind;
+    use chrono::TimeZone;
+    use rust_decimal::Decimal;
+
+    #[test]
+    fn test_parse_currency_args() {
+        let query = parse_query("100 USD EUR").unwrap();
+        assert_eq!(query.amount, Decimal::from(100));
+        assert_eq!(query.from, "USD");
+        assert_eq!(query.targets, vec!["EUR".to_string()]);
+
+        assert!(parse_query("USD EUR").is_err());
+        assert!(parse_query("abc USD EUR").is_err());
+        assert!(parse_query("100 USD EUR XYZ").is_err());
+    }
+
+    #[test]
+    fn test_format_conversion_rounds_to_minor_units() {
+        let conversion = Conversion {
+            amount: Decimal::from(100),
+            from: "USD".to_string(),
+            to: "EUR".to_string(),
+            result: "92.34567812".parse().unwrap(),
+            rate: "0.9234567812".parse().unwrap(),
+            fetched_at: chrono::Utc.with_ymd_and_hms(2025, 1, 31, 12, 0, 0).unwrap(),
+            change_24h: None,
+            source: "Frankfurter (ECB)",
+        };
+        assert_eq!(
+            format_conversion(&conversion, Locale::ENGLISH),
+            "🔄 $100.00 = €92.35\n📈 1 USD = 0.923457 EUR · 2025-01-31 12:00 UTC · Frankfurter (ECB)"
+        );
+    }
+
+    #[test]
+    fn test_fxchart_args_validate_codes() {
+        let args = FXCHART_ARGS.parse("usd btc 2w").unwrap();
+        assert_eq!(args.currency("from"), Some("USD"));
+        assert_eq!(args.currency("to"), Some("BTC"));
+
+        let err = FXCHART_ARGS.parse("abc USD").unwrap_err();
+        assert_eq!((err.kind, err.position), (ArgErrorKind::Invalid, 0));
+        let err = FXCHART_ARGS.parse("USD EUR 30d XYZ").unwrap_err();
+        assert_eq!((err.kind, err.position), (ArgErrorKind::Unexpected, 3));
+    }
+
+    #[test]
+    fn test_chart_days() {
+        assert_eq!(chart_days(None), Some(30));
+        assert_eq!(
+            chart_days(Some(Duration::from_secs(2 * 7 * 86_400))),
+            Some(14)
+        );
+        assert_eq!(chart_days(Some(Duration::from_secs(60))), None);
+        assert_eq!(chart_days(Some(Duration::from_secs(400 * 86_400))), None);
+    }
+
+    #[test]
+    fn test_format_search() {
+        let reply = format_search("yen", &catalogue::search("yen"));
+        assert!(reply.starts_with("🔍 Currencies matching 'yen':\n• JPY — Japanese Yen (¥)"));
+        assert_eq!(format_search("zzz", &[]), "🔍 No currency matches 'zzz'.");
+    }
+}
@@ -0,0 +1,493 @@
+// src/commands/utils/currency/query.rs
+
+//! Grammar for `/currency` queries.
+//!
+//! Accepted shapes include `10 USD to EUR`, `$10 in €`, `10usd eur`,
+//! `1.5k JPY to USD,EUR,GBP`, `10,50 EUR USD` and `1 bitcoin cash in usd
+//! coin`. Several targets must be comma-separated. A trailing
+//! `[on] YYYY-MM-DD` asks for a historical rate.
+
+use super::catalogue;
+use chrono::{NaiveDate, Utc};
+use rust_decimal::Decimal;
+use std::fmt;
+use std::str::FromStr;
+
+/// Most targets converted in one reply.
+pub const MAX_TARGETS: usize = 5;
+
+/// Largest amount accepted; anything above is almost certainly a typo.
+pub const MAX_AMOUNT: i64 = 1_000_000_000_000;
+
+/// A parsed conversion request.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CurrencyQuery {
+    pub amount: Decimal,
+    pub from: String,
+    pub targets: Vec<String>,
+    /// Day whose rate should be used; `None` for the latest rate.
+    pub date: Option<NaiveDate>,
+}
+
+/// Reasons a query is rejected.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum QueryError {
+    Empty,
+    MissingAmount,
+    BadAmount(String),
+    NonPositiveAmount,
+    AmountTooLarge,
+    UnknownCurrency(String),
+    MissingSource,
+    MissingTarget,
+    TooManyTargets,
+    BadDate(String),
+    FutureDate,
+    Unexpected(String),
+}
+
+impl fmt::Display for QueryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "Tell me what to convert."),
+            Self::MissingAmount => write!(f, "How much should I convert?"),
+            Self::BadAmount(token) => write!(f, "'{}' is not a valid amount.", token),
+            Self::NonPositiveAmount => write!(f, "The amount must be greater than zero."),
+            Self::AmountTooLarge => write!(f, "That amount is too large to convert."),
+            Self::UnknownCurrency(token) => write!(
+                f,
+                "'{}' is not a currency I know. Try /currencies {}",
+                token, token
+            ),
+            Self::MissingSource => write!(f, "Which currency are you converting from?"),
+            Self::MissingTarget => write!(f, "Which currency should I convert to?"),
+            Self::TooManyTargets => {
+                write!(
+                    f,
+                    "I can convert to at most {} currencies at once.",
+                    MAX_TARGETS
+                )
+            }
+            Self::BadDate(token) => write!(f, "'{}' is not a valid date (use YYYY-MM-DD).", token),
+            Self::FutureDate => write!(f, "I can't see rates from the future."),
+            Self::Unexpected(token) => write!(
+                f,
+                "I didn't expect '{}' there. Separate several targets with commas.",
+                token
+            ),
+        }
+    }
+}
+
+impl std::error::Error for QueryError {}
+
+/// Words that may sit between the source and the targets.
+const CONNECTORS: &[&str] = &["to", "in", "into", "as", "=", "->", "→"];
+
+/// A lexical unit of a query.
+#[derive(Debug, Clone, PartialEq)]
+enum Lexeme {
+    Number(String),
+    Word(String),
+    Symbol(String),
+    /// `YYYY-MM-DD`, validated by the parser.
+    Date(String),
+    Separator,
+}
+
+/// Splits a query into numbers, words, symbols and list separators.
+///
+/// A comma between digits belongs to the number (`10,50`); anywhere else it
+/// separates list items. A minus sign directly before a digit starts a
+/// (negative) number so it can be rejected with a clear message.
+fn lex(input: &str) -> Vec<Lexeme> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut lexemes = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let next_is_digit = chars.get(i + 1).is_some_and(|n| n.is_ascii_digit());
+
+        if c.is_whitespace() {
+            i += 1;
+        } else if let Some(date) = date_at(&chars[i..]) {
+            i += date.len();
+            lexemes.push(Lexeme::Date(date));
+        } else if c.is_ascii_digit() || ((c == '.' || c == '-') && next_is_digit) {
+            let start = i;
+            i += 1;
+            while i < chars.len() {
+                let c = chars[i];
+                let next_is_digit = chars.get(i + 1).is_some_and(|n| n.is_ascii_digit());
+                if c.is_ascii_digit() || ((c == '.' || c == ',') && next_is_digit) {
+                    i += 1;
+                } else {
+                    break;
+                }
+            }
+            lexemes.push(Lexeme::Number(chars[start..i].iter().collect()));
+        } else if c.is_alphabetic() {
+            let start = i;
+            while i < chars.len() && chars[i].is_alphabetic() {
+                i += 1;
+            }
+            lexemes.push(Lexeme::Word(chars[start..i].iter().collect()));
+        } else if c == ',' || c == ';' {
+            lexemes.push(Lexeme::Separator);
+            i += 1;
+        } else {
+            let start = i;
+            while i < chars.len() {
+                let c = chars[i];
+                if c.is_whitespace() || c.is_alphanumeric() || c == ',' || c == ';' {
+                    break;
+                }
+                i += 1;
+            }
+            lexemes.push(Lexeme::Symbol(chars[start..i].iter().collect()));
+        }
+    }
+    lexemes
+}
+
+/// Parses a `/currency` query.
+///
+/// # Errors
+///
+/// Returns a [`QueryError`] describing the first problem found.
+pub fn parse_query(input: &str) -> Result<CurrencyQuery, QueryError> {
+    let lexemes = lex(input);
+    if lexemes.is_empty() {
+        return Err(QueryError::Empty);
+    }
+    let mut rest = lexemes.as_slice();
+
+    // Optional currency before the amount: `$10`, `USD 10`.
+    let mut from = None;
+    if let [first, Lexeme::Number(_), ..] = rest {
+        if let Some(code) = currency_of(first) {
+            from = Some(code);
+            rest = &rest[1..];
+        }
+    }
+
+    let amount = if let [Lexeme::Number(number), tail @ ..] = rest {
+        let mut raw = number.clone();
+        rest = tail;
+        // Magnitude suffix glued to the number: `1.5k`, `2m`.
+        let multiplier = match rest.first() {
+            Some(Lexeme::Word(w)) if is_magnitude(w) && glued(input, number, w) => {
+                raw.push_str(w);
+                rest = &rest[1..];
+                magnitude(w)
+            }
+            _ => Decimal::ONE,
+        };
+        let amount = parse_number(number)
+            .and_then(|n| n.checked_mul(multiplier))
+            .ok_or(QueryError::BadAmount(raw))?;
+        if amount <= Decimal::ZERO {
+            return Err(QueryError::NonPositiveAmount);
+        }
+        if amount > Decimal::from(MAX_AMOUNT) {
+            return Err(QueryError::AmountTooLarge);
+        }
+        amount
+    } else {
+        return Err(QueryError::MissingAmount);
+    };
+
+    if from.is_none() {
+        let first = rest.first().ok_or(QueryError::MissingSource)?;
+        let (code, len) = currency_at(rest).ok_or_else(|| unknown(first))?;
+        from = Some(code);
+        rest = &rest[len..];
+    }
+
+    if let Some(first) = rest.first() {
+        if is_connector(first) {
+            rest = &rest[1..];
+        }
+    }
+
+    let mut date = None;
+    if let [head @ .., Lexeme::Date(raw)] = rest {
+        let parsed = NaiveDate::parse_from_str(raw, "%Y-%m-%d")
+            .map_err(|_| QueryError::BadDate(raw.clone()))?;
+        if parsed > Utc::now().date_naive() {
+            return Err(QueryError::FutureDate);
+        }
+        date = Some(parsed);
+        rest = match head {
+            [init @ .., Lexeme::Word(on)] if on.eq_ignore_ascii_case("on") => init,
+            _ => head,
+        };
+    }
+
+    let mut targets = Vec::new();
+    while let Some(first) = rest.first() {
+        if *first == Lexeme::Separator {
+            rest = &rest[1..];
+            continue;
+        }
+        let (code, len) = currency_at(rest).ok_or_else(|| unknown(first))?;
+        if !targets.contains(&code) {
+            targets.push(code);
+        }
+        rest = &rest[len..];
+        match rest.first() {
+            None | Some(Lexeme::Separator) => {}
+            Some(item) => return Err(QueryError::Unexpected(text_of(item))),
+        }
+    }
+
+    if targets.is_empty() {
+        return Err(QueryError::MissingTarget);
+    }
+    if targets.len() > MAX_TARGETS {
+        return Err(QueryError::TooManyTargets);
+    }
+
+    Ok(CurrencyQuery {
+        amount,
+        from: from.unwrap_or_default(),
+        targets,
+        date,
+    })
+}
+
+/// A `YYYY-MM-DD` shape at the start of `chars`, not followed by a digit.
+fn date_at(chars: &[char]) -> Option<String> {
+    const SHAPE: &[u8] = b"dddd-dd-dd";
+    let candidate = chars.get(..SHAPE.len())?;
+    let matches = candidate.iter().zip(SHAPE).all(|(c, s)| match s {
+        b'd' => c.is_ascii_digit(),
+        _ => *c == '-',
+    });
+    let delimited = !chars.get(SHAPE.len()).is_some_and(|c| c.is_ascii_digit());
+    (matches && delimited).then(|| candidate.iter().collect())
+}
+
+/// Parses a number that may use `,` or `.` as decimal or grouping separator.
+///
+/// When both appear, the last one is the decimal separator (`1.234,56`,
+/// `1,234.56`). A lone separator followed by exactly three digits in several
+/// groups is grouping (`1,000,000`); otherwise it's a decimal (`10,50`).
+pub fn parse_number(raw: &str) -> Option<Decimal> {
+    let last_comma = raw.rfind(',');
+    let last_dot = raw.rfind('.');
+
+    let normalized = match (last_comma, last_dot) {
+        (Some(c), Some(d)) if c > d => raw.replace('.', "").replace(',', "."),
+        (Some(_), Some(_)) => raw.replace(',', ""),
+        (Some(_), None) => normalize_single_separator(raw, ','),
+        (None, Some(_)) => normalize_single_separator(raw, '.'),
+        (None, None) => raw.to_string(),
+    };
+    Decimal::from_str(&normalized).ok()
+}
+
+fn normalize_single_separator(raw: &str, separator: char) -> String {
+    let groups: Vec<&str> = raw.split(separator).collect();
+    let is_grouping =
+        groups.len() > 2 || (separator == ',' && groups.len() == 2 && groups[1].len() == 3);
+    if is_grouping && groups[1..].iter().all(|g| g.len() == 3) {
+        groups.concat()
+    } else if groups.len() == 2 {
+        format!("{}.{}", groups[0], groups[1])
+    } else {
+        // Malformed grouping such as `1,00,0`: let the float parser reject it.
+        raw.to_string()
+    }
+}
+
+fn is_magnitude(word: &str) -> bool {
+    matches!(word, "k" | "K" | "m" | "M")
+}
+
+fn magnitude(word: &str) -> Decimal {
+    match word {
+        "k" | "K" => Decimal::ONE_THOUSAND,
+        _ => Decimal::from(1_000_000),
+    }
+}
+
+/// Whether `suffix` directly follows `number` in the original input.
+fn glued(input: &str, number: &str, suffix: &str) -> bool {
+    input.contains(&format!("{}{}", number, suffix))
+}
+
+fn is_connector(lexeme: &Lexeme) -> bool {
+    match lexeme {
+        Lexeme::Word(w) | Lexeme::Symbol(w) => CONNECTORS.iter().any(|c| c.eq_ignore_ascii_case(w)),
+        _ => false,
+    }
+}
+
+/// The currency at the start of `lexemes` and how many lexemes it spans,
+/// preferring the longest multi-word alias (`usd coin` over `usd`).
+fn currency_at(lexemes: &[Lexeme]) -> Option<(String, usize)> {
+    let words = lexemes
+        .iter()
+        .take(catalogue::MAX_ALIAS_WORDS)
+        .take_while(|l| matches!(l, Lexeme::Word(_)))
+        .count();
+    (2..=words)
+        .rev()
+        .find_map(|n| {
+            let phrase: Vec<_> = lexemes[..n].iter().map(text_of).collect();
+            catalogue::resolve(&phrase.join(" ")).map(|c| (c.code.to_string(), n))
+        })
+        .or_else(|| Some((currency_of(lexemes.first()?)?, 1)))
+}
+
+/// Resolves a lexeme to an ISO 4217 code through the catalogue, accepting
+/// codes (`usd`), aliases (`dollars`, `yen`) and symbols (`$`, `€`).
+fn currency_of(lexeme: &Lexeme) -> Option<String> {
+    let currency = match lexeme {
+        Lexeme::Word(word) => catalogue::resolve(word),
+        Lexeme::Symbol(symbol) => catalogue::by_symbol(symbol),
+        _ => None,
+    };
+    currency.map(|c| c.code.to_string())
+}
+
+fn text_of(lexeme: &Lexeme) -> String {
+    match lexeme {
+        Lexeme::Number(s) | Lexeme::Word(s) | Lexeme::Symbol(s) | Lexeme::Date(s) => s.clone(),
+        Lexeme::Separator => ",".to_string(),
+    }
+}
+
+fn unknown(lexeme: &Lexeme) -> QueryError {
+    QueryError::UnknownCurrency(text_of(lexeme))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn query(amount: &str, from: &str, targets: &[&str]) -> CurrencyQuery {
+        CurrencyQuery {
+            amount: Decimal::from_str(amount).unwrap(),
+            date: None,
+            from: from.to_string(),
+            targets: targets.iter().map(|t| t.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_query_shapes() {
+        assert_eq!(
+            parse_query("10 USD to EUR"),
+            Ok(query("10", "USD", &["EUR"]))
+        );
+        assert_eq!(
+            parse_query("100 USD EUR"),
+            Ok(query("100", "USD", &["EUR"]))
+        );
+        assert_eq!(parse_query("$10 in €"), Ok(query("10", "USD", &["EUR"])));
+        assert_eq!(parse_query("10usd eur"), Ok(query("10", "USD", &["EUR"])));
+        assert_eq!(
+            parse_query("1.5k JPY to USD,EUR, GBP"),
+            Ok(query("1500", "JPY", &["USD", "EUR", "GBP"]))
+        );
+        assert_eq!(
+            parse_query("10,50 EUR USD"),
+            Ok(query("10.5", "EUR", &["USD"]))
+        );
+        assert_eq!(parse_query("2 usd -> pen"), Ok(query("2", "USD", &["PEN"])));
+        assert_eq!(
+            parse_query("10 dollars in yen"),
+            Ok(query("10", "USD", &["JPY"]))
+        );
+    }
+
+    #[test]
+    fn test_multi_word_aliases() {
+        assert_eq!(
+            parse_query("1 bitcoin cash in usd coin"),
+            Ok(query("1", "BCH", &["USDC"]))
+        );
+        assert_eq!(
+            parse_query("100 usd to papua new guinea, south africa"),
+            Ok(query("100", "USD", &["PGK", "ZAR"]))
+        );
+        assert_eq!(parse_query("₿0.5 usd"), Ok(query("0.5", "BTC", &["USD"])));
+        assert_eq!(
+            parse_query("10 usd south"),
+            Err(QueryError::UnknownCurrency("south".into()))
+        );
+    }
+
+    #[test]
+    fn test_historical_date() {
+        let date = NaiveDate::from_ymd_opt(2025, 1, 31);
+        assert_eq!(
+            parse_query("100 USD EUR 2025-01-31"),
+            Ok(CurrencyQuery {
+                date,
+                ..query("100", "USD", &["EUR"])
+            })
+        );
+        assert_eq!(
+            parse_query("100 USD to EUR,GBP on 2025-01-31")
+                .unwrap()
+                .date,
+            date
+        );
+        assert_eq!(
+            parse_query("100 USD EUR 2025-02-30"),
+            Err(QueryError::BadDate("2025-02-30".into()))
+        );
+        assert_eq!(
+            parse_query("1 USD EUR 2999-01-01"),
+            Err(QueryError::FutureDate)
+        );
+    }
+
+    #[test]
+    fn test_query_errors() {
+        assert_eq!(parse_query(""), Err(QueryError::Empty));
+        assert_eq!(parse_query("10 USD"), Err(QueryError::MissingTarget));
+        assert_eq!(parse_query("USD to EUR"), Err(QueryError::MissingAmount));
+        assert_eq!(
+            parse_query("10 USD to EUR XYZ"),
+            Err(QueryError::Unexpected("XYZ".into()))
+        );
+        assert_eq!(
+            parse_query("10 USD EUo"),
+            Err(QueryError::UnknownCurrency("EUo".into()))
+        );
+        assert_eq!(parse_query("0 USD EUR"), Err(QueryError::NonPositiveAmount));
+        assert_eq!(
+            parse_query("-5 USD EUR"),
+            Err(QueryError::NonPositiveAmount)
+        );
+        assert_eq!(
+            parse_query("5000000m USD EUR"),
+            Err(QueryError::AmountTooLarge)
+        );
+        assert_eq!(
+            parse_query("1 USD to A,B,C,D,E,F").unwrap_err(),
+            QueryError::UnknownCurrency("A".into())
+        );
+        assert_eq!(
+            parse_query("1 USD to EUR,GBP,JPY,CHF,CAD,AUD"),
+            Err(QueryError::TooManyTargets)
+        );
+    }
+
+    #[test]
+    fn test_parse_number_separators() {
+        let n = |s: &str| Decimal::from_str(s).ok();
+        assert_eq!(parse_number("10,50"), n("10.50"));
+        assert_eq!(parse_number("1,000"), n("1000"));
+        assert_eq!(parse_number("1.234,56"), n("1234.56"));
+        assert_eq!(parse_number("1,234.56"), n("1234.56"));
+        assert_eq!(parse_number("1.000.000"), n("1000000"));
+        assert_eq!(parse_number("2.5"), n("2.5"));
+        assert_eq!(parse_number("1,00,0"), None);
+    }
+}
@@ -1,8 +1,15 @@
+use crate::commands::args::{Arg, ArgKind, Signature};
 use teloxide::{prelude::*, types::Message};
 
+/// Arguments accepted by `/echo`.
+pub const ARGS: Signature = Signature::new(&[Arg::required("text", ArgKind::Text)]);
+
 /// Echoes back whatever message the user provides after the /echo command.
 pub async fn handle_echo(bot: Bot, msg: Message, text: String) -> ResponseResult<()> {
-    bot.send_message(msg.chat.id, format!("You said: {text}"))
-        .await?;
+    let reply = match ARGS.parse(&text) {
+        Ok(_) => format!("You said: {text}"),
+        Err(err) => err.render("echo", &text, &ARGS),
+    };
+    bot.send_message(msg.chat.id, reply).await?;
     Ok(())
 }
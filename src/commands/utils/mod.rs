@@ -1,5 +1,25 @@
 // src/commands/utils/mod.rs
 
+use crate::services::ServiceError;
+
+pub mod alerts;
 pub mod currency;
 pub mod echo;
-pub mod weather;
\ No newline at end of file
+pub mod weather;
+
+/// Logs a failed provider lookup of `what` and returns the short reason to
+/// reply with: `not_found` when the provider doesn't know what was asked
+/// for, a fixed line otherwise. Details stay in the log; provider errors
+/// aren't for users.
+pub fn user_message(what: &str, err: &ServiceError, not_found: &'static str) -> &'static str {
+    match err {
+        ServiceError::NotFound { .. } => {
+            log::warn!("{} failed: {}", what, err);
+            not_found
+        }
+        _ => {
+            log::error!("{} failed: {}", what, err);
+            "unavailable right now"
+        }
+    }
+}
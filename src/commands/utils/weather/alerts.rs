@@ -0,0 +1,459 @@
+// src/commands/utils/weather/alerts.rs
+
+//! `/weatheralert` and `/weatheralerts`: warnings when a city's forecast
+//! crosses wind, gust or rain thresholds. The checks themselves run in
+//! `jobs::weather_alerts`.
+
+use super::{NO_CITY, UnitLabels, failure, speed, user_prefs, whole};
+use crate::commands::args::{Arg, ArgError, ArgKind, Signature, Value};
+use crate::commands::utils::alerts::{self, ListStyle, handle_cancel_callback};
+use crate::config::BotConfig;
+use crate::services::{ApiService, HourlyForecast, WeatherData};
+use crate::storage::{AlertOwner, Metric, Storage, Units, WeatherAlert, WeatherCondition};
+use chrono::{NaiveDateTime, NaiveTime, TimeDelta};
+use log::error;
+use std::sync::Arc;
+use teloxide::prelude::*;
+use teloxide::types::{InlineKeyboardMarkup, Message};
+
+/// Callback data prefix of the cancel buttons under `/weatheralerts`.
+pub const WXALERT_PREFIX: &str = "wxalert:";
+
+/// Arguments accepted by `/weatheralert`. The city may be left out in
+/// favour of the user's saved one; the conditions must come after it.
+pub const ARGS: Signature = Signature::new(&[
+    Arg::optional("city", ArgKind::City),
+    Arg::variadic(
+        "condition",
+        ArgKind::Custom {
+            expected: "a condition like wind>50, gust>70 or rain>80%",
+            parse: |token| parse_condition(token).map(|_| token.to_string()),
+        },
+    ),
+]);
+
+/// Most alerts a single chat may hold.
+const MAX_ALERTS_PER_CHAT: usize = 10;
+
+const LIST_STYLE: ListStyle = ListStyle {
+    title: "⛈️ Weather alerts",
+    empty: "🔕 No weather alerts in this chat. Set one with /weatheralert Lima wind>50 rain>80%",
+    prefix: WXALERT_PREFIX,
+};
+
+/// How far ahead of the latest observation the forecast is watched.
+const LOOKAHEAD: TimeDelta = TimeDelta::hours(24);
+
+/// Splits `Buenos Aires wind>50 rain>80%` into the city, if any, and the
+/// conditions.
+fn parse_args(text: &str) -> Result<(Option<String>, Vec<WeatherCondition>), ArgError> {
+    let args = ARGS.parse(text)?;
+    let conditions = args
+        .all("condition")
+        .filter_map(|value| match value {
+            Value::Text(token) => parse_condition(token),
+            _ => None,
+        })
+        .collect();
+    Ok((args.text("city").map(str::to_string), conditions))
+}
+
+/// Accepts `wind > 50` as well as `wind>50`.
+fn normalize(text: &str) -> String {
+    text.split('>').map(str::trim).collect::<Vec<_>>().join(">")
+}
+
+/// Whether `text` parses as `/weatheralert` arguments.
+pub fn parses(_msg: &Message, text: &str) -> bool {
+    parse_args(&normalize(text)).is_ok()
+}
+
+/// Parses `wind>50`, `gust>70` or `rain>80%`.
+fn parse_condition(token: &str) -> Option<WeatherCondition> {
+    let (name, threshold) = token.split_once('>')?;
+    let metric = match name.to_ascii_lowercase().as_str() {
+        "wind" => Metric::Wind,
+        "gust" | "gusts" => Metric::Gust,
+        "rain" => Metric::Rain,
+        _ => return None,
+    };
+    let threshold = match metric {
+        Metric::Rain => threshold.strip_suffix('%').unwrap_or(threshold),
+        _ => threshold,
+    };
+    let threshold: u32 = threshold.parse().ok()?;
+    if metric == Metric::Rain && threshold >= 100 {
+        return None;
+    }
+    Some(WeatherCondition { metric, threshold })
+}
+
+/// Starts watching a city's forecast for the given thresholds.
+pub async fn handle_weatheralert(
+    bot: Bot,
+    msg: Message,
+    text: String,
+    api: Arc<ApiService>,
+    storage: Arc<Storage>,
+) -> ResponseResult<()> {
+    let text = normalize(&text);
+    let prefs = user_prefs(&msg, &storage).await;
+    let (city, conditions) = match parse_args(&text) {
+        Ok((city, conditions)) => match city.or(prefs.city) {
+            Some(city) => (city, conditions),
+            None => {
+                bot.send_message(msg.chat.id, NO_CITY).await?;
+                return Ok(());
+            }
+        },
+        Err(err) => {
+            bot.send_message(msg.chat.id, err.render("weatheralert", &text, &ARGS))
+                .await?;
+            return Ok(());
+        }
+    };
+
+    let location = match api.get_weather(&city).await {
+        Ok(data) => data.location.unwrap_or_else(|| city.clone()),
+        Err(err) => {
+            bot.send_message(msg.chat.id, failure(&city, &err)).await?;
+            return Ok(());
+        }
+    };
+
+    let alert = WeatherAlert {
+        owner: AlertOwner::new(msg.chat.id.0, msg.from.as_ref().map(|u| u.id.0)),
+        city,
+        units: prefs.units,
+        conditions,
+        reported: Vec::new(),
+    };
+    let added = storage
+        .weather_alerts
+        .update(|alerts| alerts.add(alert.clone(), MAX_ALERTS_PER_CHAT))
+        .await;
+    let reply = match added {
+        Ok(Some(id)) => format!(
+            "⛈️ Weather alert #{} set for {}: {}. Cancel it with /weatheralerts.",
+            id,
+            location,
+            describe(&alert)
+        ),
+        Ok(None) => format!(
+            "⚠️ This chat already has {} weather alerts. Cancel some with /weatheralerts.",
+            MAX_ALERTS_PER_CHAT
+        ),
+        Err(err) => {
+            error!("Failed to save weather alert: {}", err);
+            "❌ Failed to save the alert.".to_string()
+        }
+    };
+    bot.send_message(msg.chat.id, reply).await?;
+    Ok(())
+}
+
+/// Lists this chat's weather alerts with a cancel button for each.
+pub async fn handle_weatheralerts(
+    bot: Bot,
+    msg: Message,
+    storage: Arc<Storage>,
+) -> ResponseResult<()> {
+    let alerts = storage
+        .weather_alerts
+        .read(|a| a.in_chat(msg.chat.id.0))
+        .await;
+    let (text, keyboard) = render_list(&alerts);
+    bot.send_message(msg.chat.id, text)
+        .reply_markup(keyboard)
+        .await?;
+    Ok(())
+}
+
+/// Cancels an alert from its button and refreshes the list in place.
+pub async fn handle_weatheralert_callback(
+    bot: Bot,
+    q: CallbackQuery,
+    data: &str,
+    storage: Arc<Storage>,
+    config: Arc<BotConfig>,
+) -> ResponseResult<()> {
+    handle_cancel_callback(bot, q, data, &storage.weather_alerts, &config, render_list).await
+}
+
+/// A spell of forecast slots in a row meeting one condition.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Event {
+    pub metric: Metric,
+    /// Start of the first slot crossing the threshold.
+    pub from: NaiveDateTime,
+    /// End of the last one.
+    pub until: NaiveDateTime,
+    /// Highest reading during the event.
+    pub peak: u32,
+}
+
+/// The local time of the latest observation and the events in the next
+/// 24 hours of forecast, in the order they start for each condition.
+/// Events already under way count, so callers can tell when one ends.
+pub fn forecast_events(
+    alert: &WeatherAlert,
+    data: &WeatherData,
+) -> Option<(NaiveDateTime, Vec<Event>)> {
+    let now = data
+        .current
+        .as_ref()
+        .and_then(|c| c.observed_at)
+        .or_else(|| data.days.first().map(|d| d.date.and_time(NaiveTime::MIN)))?;
+    let slot_length = TimeDelta::hours(3);
+
+    let mut events: Vec<Event> = Vec::new();
+    for day in &data.days {
+        for slot in &day.hourly {
+            let start = slot.start;
+            let end = start + slot_length;
+            if end <= now || start >= now + LOOKAHEAD {
+                continue;
+            }
+
+            for condition in &alert.conditions {
+                let Some(value) = reading(slot, condition.metric, alert.units) else {
+                    continue;
+                };
+                if !condition.is_met(value) {
+                    continue;
+                }
+                match events
+                    .iter_mut()
+                    .rev()
+                    .find(|e| e.metric == condition.metric)
+                    .filter(|e| e.until == start)
+                {
+                    Some(event) => {
+                        event.until = end;
+                        event.peak = event.peak.max(value);
+                    }
+                    None => events.push(Event {
+                        metric: condition.metric,
+                        from: start,
+                        until: end,
+                        peak: value,
+                    }),
+                }
+            }
+        }
+    }
+    Some((now, events))
+}
+
+/// The message posted when an alert fires.
+pub fn format_notification(alert: &WeatherAlert, location: &str, events: &[Event]) -> String {
+    let mut lines = vec![format!(
+        "⛈️ Weather alert #{} for {}",
+        alert.owner.id, location
+    )];
+    lines.extend(events.iter().map(|event| {
+        format!(
+            "• {} up to {} from {}",
+            label(event.metric),
+            with_unit(event.metric, event.peak, alert.units),
+            event.from.format("%a %d %b %H:%M")
+        )
+    }));
+    lines.push(format!(
+        "Watching: {}. Stop with /weatheralerts.",
+        describe(alert)
+    ));
+    lines.join("\n")
+}
+
+/// The forecast's value for `metric` in a slot, in the alert's units.
+fn reading(slot: &HourlyForecast, metric: Metric, units: Units) -> Option<u32> {
+    let value = match metric {
+        Metric::Wind => speed(units, slot.wind_speed?),
+        Metric::Gust => speed(units, slot.wind_gust?),
+        Metric::Rain => whole(slot.chance_of_rain?),
+    };
+    u32::try_from(value).ok()
+}
+
+fn label(metric: Metric) -> &'static str {
+    match metric {
+        Metric::Wind => "🌬️ Wind",
+        Metric::Gust => "💨 Gusts",
+        Metric::Rain => "☔ Rain chance",
+    }
+}
+
+fn with_unit(metric: Metric, value: u32, units: Units) -> String {
+    match metric {
+        Metric::Rain => format!("{}%", value),
+        Metric::Wind | Metric::Gust => format!("{} {}", value, UnitLabels::of(units).speed),
+    }
+}
+
+/// The conditions of an alert, e.g. `wind > 50 km/h, rain > 80%`.
+fn describe(alert: &WeatherAlert) -> String {
+    alert
+        .conditions
+        .iter()
+        .map(|c| {
+            format!(
+                "{} > {}",
+                c.metric.name(),
+                with_unit(c.metric, c.threshold, alert.units)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn render_list(alerts: &[WeatherAlert]) -> (String, InlineKeyboardMarkup) {
+    alerts::render_list(alerts, &LIST_STYLE, |alert| {
+        format!("#{} {}: {}", alert.owner.id, alert.city, describe(alert))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::{Condition, CurrentConditions, DailyForecast};
+    use chrono::NaiveDate;
+
+    #[test]
+    fn test_parse_args() {
+        let (city, conditions) = parse_args(&normalize("Buenos Aires wind > 50 rain>80%")).unwrap();
+        assert_eq!(city.as_deref(), Some("Buenos Aires"));
+        assert_eq!(
+            conditions,
+            [
+                WeatherCondition {
+                    metric: Metric::Wind,
+                    threshold: 50
+                },
+                WeatherCondition {
+                    metric: Metric::Rain,
+                    threshold: 80
+                },
+            ]
+        );
+        assert_eq!(parse_args("gusts>70").unwrap().0, None);
+        let render = |text: &str| {
+            parse_args(text)
+                .unwrap_err()
+                .render("weatheralert", text, &ARGS)
+        };
+        assert!(render("Lima").starts_with("⚠️ Missing <condition>..."));
+        assert!(render("Lima wind>50%").contains("/weatheralert Lima »wind>50%«"));
+        assert!(render("Lima rain>100%").contains("»rain>100%«"));
+        assert!(render("Lima wind>50 Cusco").contains("/weatheralert Lima wind>50 »Cusco«"));
+    }
+
+    #[test]
+    fn test_forecast_events_join_slots_across_midnight() {
+        let slot = |date: NaiveDate, hour: u32, wind: f64, rain: f64| HourlyForecast {
+            start: date.and_hms_opt(hour, 0, 0).unwrap(),
+            condition: Condition::Unknown,
+            description: None,
+            temperature: Some(20.0),
+            chance_of_rain: Some(rain),
+            wind_speed: Some(wind),
+            wind_gust: None,
+        };
+        let day = |date: NaiveDate, hourly| DailyForecast {
+            date,
+            max_temperature: Some(25.0),
+            min_temperature: Some(15.0),
+            hourly,
+        };
+        let (today, tomorrow) = (
+            NaiveDate::from_ymd_opt(2025, 1, 31).unwrap(),
+            NaiveDate::from_ymd_opt(2025, 2, 1).unwrap(),
+        );
+        let data = WeatherData {
+            current: Some(CurrentConditions {
+                observed_at: today.and_hms_opt(10, 30, 0),
+                ..CurrentConditions::default()
+            }),
+            days: vec![
+                day(
+                    today,
+                    vec![
+                        slot(today, 6, 70.0, 0.0),
+                        slot(today, 9, 55.0, 90.0),
+                        slot(today, 12, 10.0, 10.0),
+                        slot(today, 18, 65.0, 10.0),
+                        slot(today, 21, 75.0, 10.0),
+                    ],
+                ),
+                day(
+                    tomorrow,
+                    vec![slot(tomorrow, 0, 80.0, 0.0), slot(tomorrow, 6, 90.0, 0.0)],
+                ),
+            ],
+            ..WeatherData::default()
+        };
+
+        let alert = WeatherAlert {
+            owner: AlertOwner {
+                id: 1,
+                ..AlertOwner::new(1, None)
+            },
+            city: "Lima".to_string(),
+            units: Units::Metric,
+            conditions: vec![
+                WeatherCondition {
+                    metric: Metric::Wind,
+                    threshold: 50,
+                },
+                WeatherCondition {
+                    metric: Metric::Rain,
+                    threshold: 80,
+                },
+            ],
+            reported: Vec::new(),
+        };
+        let (now, events) = forecast_events(&alert, &data).unwrap();
+        assert_eq!(now.to_string(), "2025-01-31 10:30:00");
+        let summary: Vec<_> = events
+            .iter()
+            .map(|e| (e.metric, e.from.to_string(), e.until.to_string(), e.peak))
+            .collect();
+        let at = |s: &str| s.to_string();
+        assert_eq!(
+            summary,
+            [
+                (
+                    Metric::Wind,
+                    at("2025-01-31 09:00:00"),
+                    at("2025-01-31 12:00:00"),
+                    55
+                ),
+                (
+                    Metric::Rain,
+                    at("2025-01-31 09:00:00"),
+                    at("2025-01-31 12:00:00"),
+                    90
+                ),
+                // 18:00 to 03:00 is one storm, even though it spans two days.
+                (
+                    Metric::Wind,
+                    at("2025-01-31 18:00:00"),
+                    at("2025-02-01 03:00:00"),
+                    80
+                ),
+                (
+                    Metric::Wind,
+                    at("2025-02-01 06:00:00"),
+                    at("2025-02-01 09:00:00"),
+                    90
+                ),
+            ]
+        );
+        assert_eq!(
+            format_notification(&alert, "Lima, Peru", &events[2..3]),
+            "⛈️ Weather alert #1 for Lima, Peru\n\
+             • 🌬️ Wind up to 80 km/h from Fri 31 Jan 18:00\n\
+             Watching: wind > 50 km/h, rain > 80%. Stop with /weatheralerts."
+        );
+    }
+}
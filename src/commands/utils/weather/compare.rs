@@ -0,0 +1,320 @@
+// src/commands/utils/weather/compare.rs
+
+//! `/weather Lima, Madrid, Berlin`: current conditions for several cities
+//! side by side.
+
+use super::{UnitLabels, describe, format_report, speed, temperature};
+use crate::commands::utils::user_message;
+use crate::services::{ApiService, WeatherData};
+use crate::storage::Units;
+use std::sync::Arc;
+use teloxide::prelude::*;
+use teloxide::types::{Message, ParseMode};
+use teloxide::utils::html;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+/// Most cities a single comparison may list.
+pub const MAX_CITIES: usize = 8;
+
+/// Lookups in flight at once, to stay polite towards the weather providers.
+const CONCURRENCY: usize = 3;
+
+/// Longest city name shown before it's cut short.
+const NAME_WIDTH: usize = 14;
+
+/// Characters that always separate compared cities, besides `vs`.
+const SEPARATORS: &[char] = &[';', '|'];
+
+/// Cities to compare, as split from the command text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CityList {
+    pub cities: Vec<String>,
+    /// The whole text, when the list may instead be one `City, Region`
+    /// name. It is looked up first, and the cities are compared only if
+    /// that fails.
+    pub single: Option<String>,
+}
+
+/// Splits `Lima, Madrid, Berlin`, `Lima; Madrid | Berlin` or `Lima vs
+/// Madrid` into its cities, or returns `None` when the text names a single
+/// place.
+///
+/// With `;`, `|` or `vs` present, commas stay inside names (`Paris, France
+/// vs Rome`). Otherwise three or more comma-separated parts are compared,
+/// while two (`Paris, Texas` or `Lima, Madrid`) are first tried as a single
+/// place.
+pub fn split_cities(text: &str) -> Option<CityList> {
+    let is_vs = |word: &&str| ["vs", "vs."].iter().any(|v| v.eq_ignore_ascii_case(word));
+    let mut cities = Vec::new();
+    for part in text.split(SEPARATORS) {
+        let words: Vec<&str> = part.split_whitespace().collect();
+        cities.extend(
+            words
+                .split(is_vs)
+                .filter(|name| !name.is_empty())
+                .map(|name| name.join(" ")),
+        );
+    }
+    if cities.len() > 1 {
+        return Some(CityList {
+            cities,
+            single: None,
+        });
+    }
+
+    let parts: Vec<String> = text
+        .split(',')
+        .map(|part| part.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|part| !part.is_empty())
+        .collect();
+    match parts.as_slice() {
+        [] | [_] => None,
+        [_, _] => Some(CityList {
+            single: Some(parts.join(", ")),
+            cities: parts,
+        }),
+        _ => Some(CityList {
+            cities: parts,
+            single: None,
+        }),
+    }
+}
+
+/// Sends one table with the current weather of every city. Cities that fail
+/// to load are listed below it instead of failing the whole reply. A list
+/// that may be a single place gets that place's report if it is found.
+pub async fn handle_compare(
+    bot: Bot,
+    msg: Message,
+    list: CityList,
+    api: Arc<ApiService>,
+    units: Units,
+) -> ResponseResult<()> {
+    let CityList { cities, single } = list;
+    if cities.len() > MAX_CITIES {
+        bot.send_message(
+            msg.chat.id,
+            format!("⚠️ At most {} cities at a time.", MAX_CITIES),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    if let Some(place) = single {
+        if let Ok(data) = api.get_weather(&place).await {
+            bot.send_message(msg.chat.id, format_report(&data, &place, units))
+                .await?;
+            return Ok(());
+        }
+    }
+
+    let results = fetch_all(api.clone(), &cities).await;
+    let rows: Vec<_> = cities.into_iter().zip(results).collect();
+    bot.send_message(msg.chat.id, format_table(&rows, units))
+        .parse_mode(ParseMode::Html)
+        .await?;
+    Ok(())
+}
+
+/// Looks every city up with at most `CONCURRENCY` requests at a time,
+/// keeping the input order.
+async fn fetch_all(
+    api: Arc<ApiService>,
+    cities: &[String],
+) -> Vec<Result<WeatherData, &'static str>> {
+    let permits = Arc::new(Semaphore::new(CONCURRENCY));
+    let mut tasks = JoinSet::new();
+    for (index, city) in cities.iter().cloned().enumerate() {
+        let (api, permits) = (api.clone(), permits.clone());
+        tasks.spawn(async move {
+            let _permit = permits.acquire_owned().await;
+            let result = api
+                .get_weather(&city)
+                .await
+                .map_err(|err| user_message(&format!("Weather for {}", city), &err, "not found"));
+            (index, result)
+        });
+    }
+
+    let mut results: Vec<Result<WeatherData, &'static str>> =
+        vec![Err("unavailable"); cities.len()];
+    while let Some(joined) = tasks.join_next().await {
+        match joined {
+            Ok((index, result)) => results[index] = result,
+            Err(err) => log::error!("Weather lookup task failed: {}", err),
+        }
+    }
+    results
+}
+
+/// An HTML `<pre>` table with one row per city, followed by the failures.
+fn format_table(rows: &[(String, Result<WeatherData, &'static str>)], units: Units) -> String {
+    let labels = UnitLabels::of(units);
+    let mut table: Vec<[String; 5]> = vec![[
+        "City".to_string(),
+        "Temp".to_string(),
+        "Feels".to_string(),
+        "Wind".to_string(),
+        "Sky".to_string(),
+    ]];
+    let mut failures = Vec::new();
+
+    for (query, result) in rows {
+        let Some((data, now)) = result
+            .as_ref()
+            .ok()
+            .and_then(|data| data.current.as_ref().map(|now| (data, now)))
+        else {
+            let reason = result
+                .as_ref()
+                .err()
+                .copied()
+                .unwrap_or("no current weather");
+            failures.push(format!(
+                "⚠️ {}: {}",
+                html::escape(query),
+                html::escape(reason)
+            ));
+            continue;
+        };
+
+        let name = data
+            .location
+            .as_deref()
+            .and_then(|l| l.split(", ").next())
+            .unwrap_or(query);
+        let reading = |value: Option<i64>, unit: &str| {
+            value.map_or_else(|| "-".to_string(), |v| format!("{}{}", v, unit))
+        };
+        let degrees = |celsius: Option<f64>| {
+            reading(celsius.map(|c| temperature(units, c)), labels.temperature)
+        };
+        table.push([
+            truncate(name, NAME_WIDTH),
+            degrees(now.temperature),
+            degrees(now.feels_like),
+            reading(
+                now.wind_speed.map(|kmh| speed(units, kmh)),
+                &format!(" {}", labels.speed),
+            ),
+            describe(now.description.as_deref(), now.condition)
+                .unwrap_or_default()
+                .to_string(),
+        ]);
+    }
+
+    let mut lines = Vec::new();
+    if table.len() > 1 {
+        let widths: Vec<usize> = (0..4)
+            .map(|col| {
+                table
+                    .iter()
+                    .map(|row| row[col].chars().count())
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+        let body: Vec<String> = table
+            .iter()
+            .map(|row| {
+                let name = format!("{:<width$}", row[0], width = widths[0]);
+                let numbers = (1..4)
+                    .map(|col| format!("{:>width$}", row[col], width = widths[col]))
+                    .collect::<Vec<_>>()
+                    .join("  ");
+                format!("{}  {}  {}", name, numbers, row[4])
+                    .trim_end()
+                    .to_string()
+            })
+            .collect();
+        lines.push(format!("<pre>{}</pre>", html::escape(&body.join("\n"))));
+    }
+    lines.extend(failures);
+    lines.join("\n")
+}
+
+fn truncate(text: &str, width: usize) -> String {
+    if text.chars().count() <= width {
+        return text.to_string();
+    }
+    let mut cut: String = text.chars().take(width - 1).collect();
+    cut.push('…');
+    cut
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::CurrentConditions;
+
+    fn weather(area: &str, temp: f64, wind: f64, desc: &str) -> WeatherData {
+        WeatherData {
+            location: Some(format!("{}, Somewhere", area)),
+            current: Some(CurrentConditions {
+                description: Some(desc.to_string()),
+                temperature: Some(temp),
+                feels_like: Some(temp),
+                wind_speed: Some(wind),
+                ..CurrentConditions::default()
+            }),
+            ..WeatherData::default()
+        }
+    }
+
+    #[test]
+    fn test_split_cities() {
+        let list = |cities: &[&str], single: Option<&str>| {
+            Some(CityList {
+                cities: cities.iter().map(|c| c.to_string()).collect(),
+                single: single.map(str::to_string),
+            })
+        };
+        assert_eq!(
+            split_cities("Lima, Madrid, Berlin"),
+            list(&["Lima", "Madrid", "Berlin"], None)
+        );
+        assert_eq!(
+            split_cities("Lima; Madrid|Berlin ;"),
+            list(&["Lima", "Madrid", "Berlin"], None)
+        );
+        assert_eq!(
+            split_cities("Paris, France VS Portland, OR"),
+            list(&["Paris, France", "Portland, OR"], None)
+        );
+        assert_eq!(
+            split_cities("Lima,  Buenos   Aires"),
+            list(&["Lima", "Buenos Aires"], Some("Lima, Buenos Aires"))
+        );
+        assert_eq!(
+            split_cities("Paris, France"),
+            list(&["Paris", "France"], Some("Paris, France"))
+        );
+        assert_eq!(
+            split_cities("Portland, OR"),
+            list(&["Portland", "OR"], Some("Portland, OR"))
+        );
+        assert_eq!(split_cities("Buenos Aires"), None);
+        assert_eq!(split_cities("Lima;"), None);
+        assert_eq!(split_cities("Lima,"), None);
+    }
+
+    #[test]
+    fn test_format_table_aligns_and_reports_failures() {
+        let rows = vec![
+            ("lima".to_string(), Ok(weather("Lima", 18.0, 15.0, "Sunny"))),
+            ("Atlantis".to_string(), Err("not found")),
+            (
+                "madrid".to_string(),
+                Ok(weather("Madrid", -2.0, 5.0, "Light snow")),
+            ),
+        ];
+        assert_eq!(
+            format_table(&rows, Units::Metric),
+            "<pre>City    Temp  Feels     Wind  Sky\n\
+             Lima    18°C   18°C  15 km/h  Sunny\n\
+             Madrid  -2°C   -2°C   5 km/h  Light snow</pre>\n\
+             ⚠️ Atlantis: not found"
+        );
+    }
+}
@@ -0,0 +1,332 @@
+// src/commands/utils/weather/digest.rs
+
+//! `/weather subscribe`, `unsubscribe` and `subscriptions`: a daily summary
+//! posted to the chat at a set local time. Deliveries run in
+//! `jobs::weather_digest`.
+
+use super::forecast::format_day;
+use super::{NO_CITY, condition_emoji, failure, summarize, user_prefs};
+use crate::config::BotConfig;
+use crate::services::{ApiService, WeatherData};
+use crate::storage::{Storage, Units, WeatherSubscription};
+use chrono::{FixedOffset, NaiveTime, Utc};
+use log::error;
+use std::fmt;
+use std::sync::Arc;
+use teloxide::{prelude::*, types::Message};
+
+/// Most digests a single chat may receive.
+const MAX_SUBSCRIPTIONS_PER_CHAT: usize = 5;
+
+/// Digest-management subcommands of `/weather`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Subcommand {
+    Subscribe,
+    Unsubscribe,
+    List,
+}
+
+impl Subcommand {
+    /// Splits `subscribe Lima 07:30` into the subcommand and its arguments.
+    pub fn split(text: &str) -> Option<(Self, &str)> {
+        let text = text.trim();
+        let (first, rest) = text.split_once(char::is_whitespace).unwrap_or((text, ""));
+        let subcommand = match first.to_ascii_lowercase().as_str() {
+            "subscribe" => Self::Subscribe,
+            "unsubscribe" => Self::Unsubscribe,
+            "subscriptions" => Self::List,
+            _ => return None,
+        };
+        Some((subcommand, rest.trim()))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum SubscribeArgsError {
+    MissingTime,
+    BadTime(String),
+}
+
+impl fmt::Display for SubscribeArgsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingTime => write!(f, "At what time? Give it as HH:MM, e.g. 07:30."),
+            Self::BadTime(time) => write!(f, "'{}' is not a time; use HH:MM, e.g. 07:30.", time),
+        }
+    }
+}
+
+/// Whether `text` parses as `/weather subscribe` arguments.
+pub fn parses_subscribe(text: &str) -> bool {
+    parse_subscribe(text).is_ok()
+}
+
+/// Splits `Buenos Aires 07:30` into the city, if any, and the time.
+fn parse_subscribe(text: &str) -> Result<(Option<String>, NaiveTime), SubscribeArgsError> {
+    let mut tokens: Vec<&str> = text.split_whitespace().collect();
+    let Some(last) = tokens.pop() else {
+        return Err(SubscribeArgsError::MissingTime);
+    };
+    let time = match NaiveTime::parse_from_str(last, "%H:%M") {
+        Ok(time) => time,
+        Err(_) if last.contains(':') => return Err(SubscribeArgsError::BadTime(last.to_string())),
+        Err(_) => return Err(SubscribeArgsError::MissingTime),
+    };
+    let city = (!tokens.is_empty()).then(|| tokens.join(" "));
+    Ok((city, time))
+}
+
+/// Runs a digest subcommand of `/weather`.
+pub async fn handle_subcommand(
+    bot: Bot,
+    msg: Message,
+    subcommand: Subcommand,
+    text: String,
+    api: Arc<ApiService>,
+    storage: Arc<Storage>,
+    config: Arc<BotConfig>,
+) -> ResponseResult<()> {
+    match subcommand {
+        Subcommand::Subscribe => handle_subscribe(bot, msg, text, api, storage).await,
+        Subcommand::Unsubscribe => handle_unsubscribe(bot, msg, text, storage, config).await,
+        Subcommand::List => handle_list(bot, msg, storage).await,
+    }
+}
+
+async fn handle_subscribe(
+    bot: Bot,
+    msg: Message,
+    text: String,
+    api: Arc<ApiService>,
+    storage: Arc<Storage>,
+) -> ResponseResult<()> {
+    let prefs = user_prefs(&msg, &storage).await;
+    let (city, time) = match parse_subscribe(&text) {
+        Ok((city, time)) => match city.or(prefs.city) {
+            Some(city) => (city, time),
+            None => {
+                bot.send_message(msg.chat.id, NO_CITY).await?;
+                return Ok(());
+            }
+        },
+        Err(err) => {
+            bot.send_message(
+                msg.chat.id,
+                format!(
+                    "⚠️ {}\nUsage: /weather subscribe [city] <HH:MM>\nExample: /weather subscribe Lima 07:30",
+                    err
+                ),
+            )
+            .await?;
+            return Ok(());
+        }
+    };
+
+    let chat_id = msg.chat.id.0;
+    let existing = storage.weather_subs.read(|s| s.in_chat(chat_id)).await;
+    let replaces = existing.iter().any(|s| s.city.eq_ignore_ascii_case(&city));
+    if !replaces && existing.len() >= MAX_SUBSCRIPTIONS_PER_CHAT {
+        bot.send_message(
+            msg.chat.id,
+            format!(
+                "⚠️ This chat already has {} daily digests. Drop one with /weather unsubscribe <city>.",
+                MAX_SUBSCRIPTIONS_PER_CHAT
+            ),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    // Fetching now checks the city exists and tells us its UTC offset.
+    let data = match api.get_weather(&city).await {
+        Ok(data) => data,
+        Err(err) => {
+            bot.send_message(msg.chat.id, failure(&city, &err)).await?;
+            return Ok(());
+        }
+    };
+    let location = data.location.unwrap_or_else(|| city.clone());
+    let offset = data.utc_offset;
+
+    let mut subscription = WeatherSubscription {
+        chat_id,
+        user_id: msg.from.as_ref().map(|u| u.id.0),
+        city,
+        time,
+        utc_offset: offset.map_or(0, |o| o.local_minus_utc() / 60),
+        last_sent: None,
+    };
+    // Starting after today's time has passed waits for tomorrow, and
+    // before it doesn't catch up on yesterday's.
+    let local = subscription.local_now(Utc::now());
+    subscription.last_sent = if local.time() >= time {
+        Some(local.date())
+    } else {
+        local.date().pred_opt()
+    };
+
+    let reply = match storage
+        .weather_subs
+        .update(|s| s.upsert(subscription))
+        .await
+    {
+        Ok(replaced) => format!(
+            "🌅 {} Every day at {} {} time this chat gets the weather for {}.",
+            if replaced { "Updated!" } else { "Subscribed!" },
+            time.format("%H:%M"),
+            if offset.is_some() { "local" } else { "UTC" },
+            location
+        ),
+        Err(err) => {
+            error!("Failed to save weather subscription: {}", err);
+            "❌ Failed to save the subscription.".to_string()
+        }
+    };
+    bot.send_message(msg.chat.id, reply).await?;
+    Ok(())
+}
+
+/// Stops the chat's digest for a city, or all of them. Only the subscriber
+/// or a bot admin may stop a digest.
+async fn handle_unsubscribe(
+    bot: Bot,
+    msg: Message,
+    city: String,
+    storage: Arc<Storage>,
+    config: Arc<BotConfig>,
+) -> ResponseResult<()> {
+    let Some(user) = msg.from.as_ref().map(|u| u.id) else {
+        return Ok(());
+    };
+    let allowed = |s: &WeatherSubscription| s.user_id == Some(user.0) || config.is_admin(user);
+    let city = Some(city.as_str()).filter(|c| !c.is_empty());
+    let reply = match storage
+        .weather_subs
+        .update(|s| s.remove(msg.chat.id.0, city, allowed))
+        .await
+    {
+        Ok((0, 0)) => match city {
+            Some(city) => format!("This chat has no daily digest for {}.", city),
+            None => "This chat has no daily digests.".to_string(),
+        },
+        Ok((0, _)) => "Only the subscriber or an admin can stop a daily digest.".to_string(),
+        Ok((removed, refused)) => {
+            let mut reply = match removed {
+                1 => "🔕 Unsubscribed from the daily digest.".to_string(),
+                n => format!("🔕 Unsubscribed from {} daily digests.", n),
+            };
+            if refused > 0 {
+                reply.push_str(&format!(" {} set up by others stayed.", refused));
+            }
+            reply
+        }
+        Err(err) => {
+            error!("Failed to remove weather subscription: {}", err);
+            "❌ Failed to unsubscribe.".to_string()
+        }
+    };
+    bot.send_message(msg.chat.id, reply).await?;
+    Ok(())
+}
+
+async fn handle_list(bot: Bot, msg: Message, storage: Arc<Storage>) -> ResponseResult<()> {
+    let subscriptions = storage
+        .weather_subs
+        .read(|s| s.in_chat(msg.chat.id.0))
+        .await;
+    bot.send_message(msg.chat.id, render_list(&subscriptions))
+        .await?;
+    Ok(())
+}
+
+fn render_list(subscriptions: &[WeatherSubscription]) -> String {
+    if subscriptions.is_empty() {
+        return "No daily digests in this chat. Try /weather subscribe Lima 07:30.".to_string();
+    }
+    let mut lines = vec!["🌅 Daily weather digests:".to_string()];
+    lines.extend(subscriptions.iter().map(|s| {
+        let offset = FixedOffset::east_opt(s.utc_offset * 60)
+            .map_or_else(|| "UTC".to_string(), |o| format!("UTC{}", o));
+        format!("• {} at {} ({})", s.city, s.time.format("%H:%M"), offset)
+    }));
+    lines.push("Stop one with /weather unsubscribe <city>.".to_string());
+    lines.join("\n")
+}
+
+/// The morning summary: today's outlook followed by current conditions.
+pub fn format_digest(data: &WeatherData, query: &str, units: Units) -> String {
+    let location = data.location.as_deref().unwrap_or(query);
+    let mut lines = vec![format!("🌅 Today in {}", location)];
+    if let Some(today) = data.days.first() {
+        lines.push(format_day(today, units));
+    }
+    if let Some(now) = &data.current {
+        lines.push(format!(
+            "Now {} {}",
+            condition_emoji(now.condition),
+            summarize(now, units)
+        ));
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::{Condition, CurrentConditions, DailyForecast};
+    use chrono::NaiveDate;
+
+    #[test]
+    fn test_subcommands_and_args() {
+        assert_eq!(
+            Subcommand::split("Subscribe Buenos Aires 7:30"),
+            Some((Subcommand::Subscribe, "Buenos Aires 7:30"))
+        );
+        assert_eq!(
+            Subcommand::split("subscriptions"),
+            Some((Subcommand::List, ""))
+        );
+        assert_eq!(Subcommand::split("Lima"), None);
+
+        let seven_thirty = NaiveTime::from_hms_opt(7, 30, 0).unwrap();
+        assert_eq!(
+            parse_subscribe("Buenos Aires 7:30"),
+            Ok((Some("Buenos Aires".to_string()), seven_thirty))
+        );
+        assert_eq!(parse_subscribe("07:30"), Ok((None, seven_thirty)));
+        assert_eq!(
+            parse_subscribe("Lima"),
+            Err(SubscribeArgsError::MissingTime)
+        );
+        assert_eq!(
+            parse_subscribe("Lima 25:00"),
+            Err(SubscribeArgsError::BadTime("25:00".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_format_digest() {
+        let data = WeatherData {
+            location: Some("Lima, Peru".to_string()),
+            current: Some(CurrentConditions {
+                condition: Condition::PartlyCloudy,
+                description: Some("Partly cloudy".to_string()),
+                temperature: Some(18.0),
+                ..CurrentConditions::default()
+            }),
+            days: vec![DailyForecast {
+                date: NaiveDate::from_ymd_opt(2025, 1, 31).unwrap(),
+                max_temperature: Some(27.0),
+                min_temperature: Some(20.0),
+                hourly: Vec::new(),
+            }],
+            ..WeatherData::default()
+        };
+        assert_eq!(
+            format_digest(&data, "lima", Units::Imperial),
+            "🌅 Today in Lima, Peru\n\
+             Fri 31 Jan 🌤️ 81° / 68°F\n\
+             Now ⛅ Partly cloudy, 64°F"
+        );
+    }
+}
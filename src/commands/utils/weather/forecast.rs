@@ -0,0 +1,269 @@
+// src/commands/utils/weather/forecast.rs
+
+//! `/forecast`: daily outlook for up to three days, or today hour by hour.
+
+use super::{
+    UnitLabels, condition_emoji, describe, failure, speed, temperature, user_prefs, whole,
+};
+use crate::commands::args::{Arg, ArgError, ArgKind, Signature};
+use crate::services::{ApiService, Condition, DailyForecast, HourlyForecast, WeatherData};
+use crate::storage::{Storage, Units};
+use std::sync::Arc;
+use teloxide::{prelude::*, types::Message};
+
+/// Days the weather providers forecast, today included.
+const MAX_DAYS: u32 = 3;
+
+/// Arguments accepted by `/forecast`. The city may be left out in favour
+/// of the user's saved one.
+pub const ARGS: Signature = Signature::new(&[
+    Arg::optional("city", ArgKind::City),
+    Arg::optional(
+        "days",
+        ArgKind::Number {
+            min: 1,
+            max: MAX_DAYS,
+        },
+    ),
+    Arg::optional("view", ArgKind::Choice(&["today", "hourly"])),
+]);
+
+/// What the user asked to see.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum View {
+    Daily(u32),
+    Hourly,
+}
+
+/// Splits `Buenos Aires 2` or `Lima today` into the city and the view.
+fn parse_args(text: &str) -> Result<(Option<String>, View), ArgError> {
+    let args = ARGS.parse(text)?;
+    let view = match (args.choice("view"), args.number("days")) {
+        (Some(_), _) => View::Hourly,
+        (None, days) => View::Daily(days.unwrap_or(MAX_DAYS)),
+    };
+    Ok((args.text("city").map(str::to_string), view))
+}
+
+/// Reply to `/forecast` from a user without a saved city.
+const NO_CITY: &str = "⚠️ Which city? Try /forecast Lima, or save one with /setcity Lima.";
+
+/// Sends the daily or hourly forecast for a city, or for the user's saved city.
+pub async fn handle_forecast(
+    bot: Bot,
+    msg: Message,
+    text: String,
+    api: Arc<ApiService>,
+    storage: Arc<Storage>,
+) -> ResponseResult<()> {
+    let prefs = user_prefs(&msg, &storage).await;
+    let (city, view) = match parse_args(&text) {
+        Ok((city, view)) => match city.or(prefs.city) {
+            Some(city) => (city, view),
+            None => {
+                bot.send_message(msg.chat.id, NO_CITY).await?;
+                return Ok(());
+            }
+        },
+        Err(err) => {
+            bot.send_message(msg.chat.id, err.render("forecast", &text, &ARGS))
+                .await?;
+            return Ok(());
+        }
+    };
+
+    let reply = match api.get_weather(&city).await {
+        Ok(data) => match view {
+            View::Daily(days) => format_daily(&data, &city, days, prefs.units),
+            View::Hourly => format_hourly(&data, &city, prefs.units),
+        },
+        Err(err) => failure(&city, &err),
+    };
+
+    bot.send_message(msg.chat.id, reply).await?;
+    Ok(())
+}
+
+/// One line per day: icon, high / low, rain chance and description.
+fn format_daily(data: &WeatherData, query: &str, days: u32, units: Units) -> String {
+    let location = data.location.clone().unwrap_or_else(|| query.to_string());
+    if data.days.is_empty() {
+        return format!("❌ No forecast available for {}", location);
+    }
+
+    let days = (days as usize).min(data.days.len());
+    let mut lines = vec![format!("📅 {}-day forecast for {}", days, location)];
+    lines.extend(
+        data.days
+            .iter()
+            .take(days)
+            .map(|day| format_day(day, units)),
+    );
+    lines.join("\n")
+}
+
+pub(super) fn format_day(day: &DailyForecast, units: Units) -> String {
+    let midday = day.midday();
+    let degrees = |celsius: Option<f64>| {
+        celsius.map_or_else(|| "-".to_string(), |c| temperature(units, c).to_string())
+    };
+    let mut line = format!(
+        "{} {} {}° / {}{}",
+        day.date.format("%a %d %b"),
+        condition_emoji(midday.map_or(Condition::Unknown, |h| h.condition)),
+        degrees(day.max_temperature),
+        degrees(day.min_temperature),
+        UnitLabels::of(units).temperature
+    );
+    if let Some(rain) = day.chance_of_rain() {
+        line.push_str(&format!(" · ☔ {}%", whole(rain)));
+    }
+    if let Some(desc) = midday.and_then(|h| describe(h.description.as_deref(), h.condition)) {
+        line.push_str(&format!(" · {}", desc));
+    }
+    line
+}
+
+/// Today's three-hourly slots.
+fn format_hourly(data: &WeatherData, query: &str, units: Units) -> String {
+    let location = data.location.clone().unwrap_or_else(|| query.to_string());
+    let Some(today) = data.days.first().filter(|d| !d.hourly.is_empty()) else {
+        return format!("❌ No hourly forecast available for {}", location);
+    };
+
+    let mut lines = vec![format!(
+        "🕒 Today in {} ({})",
+        location,
+        today.date.format("%a %d %b")
+    )];
+    lines.extend(today.hourly.iter().map(|slot| format_slot(slot, units)));
+    lines.join("\n")
+}
+
+fn format_slot(slot: &HourlyForecast, units: Units) -> String {
+    let labels = UnitLabels::of(units);
+    let mut line = format!(
+        "{} {}",
+        slot.start.format("%H:%M"),
+        condition_emoji(slot.condition)
+    );
+    if let Some(temp) = slot.temperature {
+        line.push_str(&format!(
+            " {}{}",
+            temperature(units, temp),
+            labels.temperature
+        ));
+    }
+    if let Some(rain) = slot.chance_of_rain {
+        line.push_str(&format!(" · ☔ {}%", whole(rain)));
+    }
+    if let Some(wind) = slot.wind_speed {
+        line.push_str(&format!(" · 🌬️ {} {}", speed(units, wind), labels.speed));
+    }
+    line
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn slot(
+        date: NaiveDate,
+        hour: u32,
+        temp: f64,
+        rain: f64,
+        condition: Condition,
+    ) -> HourlyForecast {
+        HourlyForecast {
+            start: date.and_hms_opt(hour, 0, 0).unwrap(),
+            condition,
+            description: Some("Patchy rain".to_string()),
+            temperature: Some(temp),
+            chance_of_rain: Some(rain),
+            wind_speed: Some(10.0),
+            wind_gust: None,
+        }
+    }
+
+    fn sample() -> WeatherData {
+        let today = NaiveDate::from_ymd_opt(2025, 1, 31).unwrap();
+        WeatherData {
+            location: Some("Lima, Peru".to_string()),
+            days: vec![
+                DailyForecast {
+                    date: today,
+                    max_temperature: Some(27.0),
+                    min_temperature: Some(20.0),
+                    hourly: vec![
+                        slot(today, 9, 22.0, 10.0, Condition::Clear),
+                        slot(today, 12, 26.0, 40.0, Condition::Rain),
+                    ],
+                },
+                DailyForecast {
+                    date: today.succ_opt().unwrap(),
+                    max_temperature: Some(26.0),
+                    min_temperature: Some(19.0),
+                    hourly: Vec::new(),
+                },
+            ],
+            ..WeatherData::default()
+        }
+    }
+
+    #[test]
+    fn test_parse_args() {
+        assert_eq!(
+            parse_args("Buenos Aires 2").unwrap(),
+            (Some("Buenos Aires".to_string()), View::Daily(2))
+        );
+        assert_eq!(
+            parse_args("lima TODAY").unwrap(),
+            (Some("lima".to_string()), View::Hourly)
+        );
+        assert_eq!(
+            parse_args("Paris").unwrap(),
+            (Some("Paris".to_string()), View::Daily(3))
+        );
+        let err = parse_args("Paris 7").unwrap_err();
+        assert!(
+            err.render("forecast", "Paris 7", &ARGS)
+                .contains("/forecast Paris »7«")
+        );
+        assert_eq!(parse_args("today").unwrap(), (None, View::Hourly));
+        assert_eq!(parse_args("").unwrap(), (None, View::Daily(3)));
+    }
+
+    #[test]
+    fn test_format_daily() {
+        assert_eq!(
+            format_daily(&sample(), "lima", 3, Units::Metric),
+            "📅 2-day forecast for Lima, Peru\n\
+             Fri 31 Jan 🌧️ 27° / 20°C · ☔ 40% · Patchy rain\n\
+             Sat 01 Feb 🌤️ 26° / 19°C"
+        );
+    }
+
+    #[test]
+    fn test_format_hourly() {
+        assert_eq!(
+            format_hourly(&sample(), "lima", Units::Metric),
+            "🕒 Today in Lima, Peru (Fri 31 Jan)\n\
+             09:00 ☀️ 22°C · ☔ 10% · 🌬️ 10 km/h\n\
+             12:00 🌧️ 26°C · ☔ 40% · 🌬️ 10 km/h"
+        );
+    }
+
+    #[test]
+    fn test_imperial_units() {
+        let data = sample();
+        assert_eq!(
+            format_day(&data.days[0], Units::Imperial),
+            "Fri 31 Jan 🌧️ 81° / 68°F · ☔ 40% · Patchy rain"
+        );
+        assert_eq!(
+            format_slot(&data.days[0].hourly[0], Units::Imperial),
+            "09:00 ☀️ 72°F · ☔ 10% · 🌬️ 6 mph"
+        );
+    }
+}
@@ -0,0 +1,392 @@
+use crate::commands::args::{Arg, ArgKind, Signature};
+use crate::commands::utils::user_message;
+use crate::config::BotConfig;
+use crate::services::{ApiService, Condition, CurrentConditions, ServiceError, WeatherData};
+use crate::storage::{Storage, Units, UserPrefs};
+use std::sync::Arc;
+use teloxide::{prelude::*, types::Message};
+
+pub mod alerts;
+pub mod compare;
+pub mod digest;
+pub mod forecast;
+pub mod prefs;
+
+/// Arguments accepted by `/weather`.
+pub const ARGS: Signature = Signature::new(&[Arg::optional("city", ArgKind::City)]);
+
+/// Reports the current weather for a city, for the location or venue the
+/// command replies to, or for the user's saved city. Several cities
+/// separated by commas, `;`, `|` or `vs` are compared; the `subscribe`,
+/// `unsubscribe` and `subscriptions` subcommands manage daily digests.
+pub async fn handle_weather(
+    bot: Bot,
+    msg: Message,
+    city: String,
+    api: Arc<ApiService>,
+    storage: Arc<Storage>,
+    config: Arc<BotConfig>,
+) -> ResponseResult<()> {
+    if let Some((subcommand, rest)) = digest::Subcommand::split(&city) {
+        let rest = rest.to_string();
+        return digest::handle_subcommand(bot, msg, subcommand, rest, api, storage, config).await;
+    }
+
+    let prefs = user_prefs(&msg, &storage).await;
+    if let Some(list) = compare::split_cities(&city) {
+        return compare::handle_compare(bot, msg, list, api, prefs.units).await;
+    }
+    if city.trim().is_empty() {
+        if let Some(place) = msg.reply_to_message().and_then(SharedPlace::from_message) {
+            return send_weather_at(bot, &msg, &place, api, prefs.units).await;
+        }
+    }
+
+    let city = match ARGS.parse(&city) {
+        Ok(args) => match args.text("city").map(str::to_string).or(prefs.city) {
+            Some(city) => city,
+            None => {
+                bot.send_message(msg.chat.id, NO_CITY).await?;
+                return Ok(());
+            }
+        },
+        Err(err) => {
+            bot.send_message(msg.chat.id, err.render("weather", &city, &ARGS))
+                .await?;
+            return Ok(());
+        }
+    };
+
+    let reply = report(&api, &city, prefs.units).await;
+    bot.send_message(msg.chat.id, reply).await?;
+    Ok(())
+}
+
+/// Whether `text` parses as `/weather` arguments; only a digest
+/// subscription can be malformed.
+pub fn parses(_msg: &Message, text: &str) -> bool {
+    match digest::Subcommand::split(text) {
+        Some((digest::Subcommand::Subscribe, rest)) => digest::parses_subscribe(rest),
+        _ => true,
+    }
+}
+
+/// The current weather report for a city, or why there is none.
+async fn report(api: &ApiService, city: &str, units: Units) -> String {
+    match api.get_weather(city).await {
+        Ok(data) => format_report(&data, city, units),
+        Err(err) => failure(city, &err),
+    }
+}
+
+/// The reply when the weather for `place` can't be fetched.
+fn failure(place: &str, err: &ServiceError) -> String {
+    let reason = user_message(&format!("Weather for {}", place), err, "city not found");
+    format!("❌ Weather for {}: {}.", place, reason)
+}
+
+/// Reply to a bare weather command from a user without a saved city.
+const NO_CITY: &str = "⚠️ Which city? Try /weather Lima, or save one with /setcity Lima.";
+
+/// Answers a location or venue shared in a private chat with its weather.
+pub async fn handle_location(
+    bot: Bot,
+    msg: Message,
+    api: Arc<ApiService>,
+    storage: Arc<Storage>,
+) -> ResponseResult<()> {
+    let Some(place) = SharedPlace::from_message(&msg) else {
+        return Ok(());
+    };
+    let prefs = user_prefs(&msg, &storage).await;
+    send_weather_at(bot, &msg, &place, api, prefs.units).await
+}
+
+/// The sender's saved weather settings, or the defaults.
+pub async fn user_prefs(msg: &Message, storage: &Storage) -> UserPrefs {
+    match msg.from.as_ref() {
+        Some(user) => storage.weather_prefs.read(|p| p.get(user.id.0)).await,
+        None => UserPrefs::default(),
+    }
+}
+
+/// Coordinates from a location or venue message.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SharedPlace {
+    pub latitude: f64,
+    pub longitude: f64,
+    /// Venue name, or the coordinates for a bare location.
+    pub label: String,
+}
+
+impl SharedPlace {
+    pub fn from_message(msg: &Message) -> Option<Self> {
+        if let Some(venue) = msg.venue() {
+            return Some(Self {
+                latitude: venue.location.latitude,
+                longitude: venue.location.longitude,
+                label: venue.title.clone(),
+            });
+        }
+        msg.location().map(|location| Self {
+            latitude: location.latitude,
+            longitude: location.longitude,
+            label: format!("{:.4}, {:.4}", location.latitude, location.longitude),
+        })
+    }
+}
+
+async fn send_weather_at(
+    bot: Bot,
+    msg: &Message,
+    place: &SharedPlace,
+    api: Arc<ApiService>,
+    units: Units,
+) -> ResponseResult<()> {
+    let reply = match api.get_weather_at(place.latitude, place.longitude).await {
+        Ok(data) => format_report(&data, &place.label, units),
+        Err(err) => failure(&place.label, &err),
+    };
+    bot.send_message(msg.chat.id, reply).await?;
+    Ok(())
+}
+
+/// Unit suffixes of a measurement system.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnitLabels {
+    pub temperature: &'static str,
+    pub speed: &'static str,
+    pub pressure: &'static str,
+    pub distance: &'static str,
+}
+
+impl UnitLabels {
+    pub fn of(units: Units) -> Self {
+        match units {
+            Units::Metric => Self {
+                temperature: "°C",
+                speed: "km/h",
+                pressure: "hPa",
+                distance: "km",
+            },
+            Units::Imperial => Self {
+                temperature: "°F",
+                speed: "mph",
+                pressure: "inHg",
+                distance: "mi",
+            },
+        }
+    }
+}
+
+/// A °C reading in `units`, rounded for display.
+pub fn temperature(units: Units, celsius: f64) -> i64 {
+    match units {
+        Units::Metric => whole(celsius),
+        Units::Imperial => whole(celsius * 9.0 / 5.0 + 32.0),
+    }
+}
+
+/// A km/h reading in `units`, rounded for display.
+pub fn speed(units: Units, kmh: f64) -> i64 {
+    match units {
+        Units::Metric => whole(kmh),
+        Units::Imperial => whole(kmh / 1.609344),
+    }
+}
+
+/// An hPa reading in `units`, rounded for display.
+pub fn pressure(units: Units, hpa: f64) -> i64 {
+    match units {
+        Units::Metric => whole(hpa),
+        Units::Imperial => whole(hpa * 0.02953),
+    }
+}
+
+/// A km reading in `units`, rounded for display.
+pub fn distance(units: Units, km: f64) -> i64 {
+    match units {
+        Units::Metric => whole(km),
+        Units::Imperial => whole(km / 1.609344),
+    }
+}
+
+/// A reading rounded to a whole number; `-0.3` shows as `0`.
+pub fn whole(value: f64) -> i64 {
+    value.round() as i64
+}
+
+/// The provider's wording of a condition, or a plain one when it sent none.
+pub fn describe(description: Option<&str>, condition: Condition) -> Option<&str> {
+    description
+        .map(str::trim)
+        .filter(|d| !d.is_empty())
+        .or((condition != Condition::Unknown).then(|| condition.label()))
+}
+
+/// Compact multi-line report of the current conditions, headed by the
+/// place the provider resolved `query` to. Readings it lacks are omitted.
+pub fn format_report(data: &WeatherData, query: &str, units: Units) -> String {
+    let location = data.location.clone().unwrap_or_else(|| query.to_string());
+    let Some(now) = &data.current else {
+        return format!("❌ No current weather for {}", location);
+    };
+
+    let labels = UnitLabels::of(units);
+    let wind = |kmh| {
+        let direction = now.wind_compass().unwrap_or_default();
+        let wind = format!(
+            "🌬️ Wind {} {} {}",
+            speed(units, kmh),
+            labels.speed,
+            direction
+        );
+        wind.trim_end().to_string()
+    };
+    let details: Vec<String> = [
+        now.humidity.map(|h| format!("💧 Humidity {}%", whole(h))),
+        now.wind_speed.map(wind),
+        now.pressure
+            .map(|p| format!("🧭 {} {}", pressure(units, p), labels.pressure)),
+        now.uv_index.map(|uv| format!("☀️ UV {}", whole(uv))),
+        now.visibility
+            .map(|v| format!("👁️ Visibility {} {}", distance(units, v), labels.distance)),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+
+    let emoji = condition_emoji(now.condition);
+    let mut lines = vec![
+        format!("{} Weather in {}", emoji, location),
+        summarize(now, units),
+    ];
+    lines.extend(details.chunks(2).map(|pair| pair.join(" · ")));
+    lines.join("\n")
+}
+
+/// Description and temperature, e.g. `Partly cloudy, 18°C (feels like 17°C)`.
+pub fn summarize(now: &CurrentConditions, units: Units) -> String {
+    let mut summary = describe(now.description.as_deref(), now.condition)
+        .unwrap_or("unknown")
+        .to_string();
+
+    let unit = UnitLabels::of(units).temperature;
+    if let Some(temp) = now.temperature.map(|t| temperature(units, t)) {
+        summary.push_str(&format!(", {}{}", temp, unit));
+        let feels_like = now.feels_like.map(|t| temperature(units, t));
+        if let Some(feels_like) = feels_like.filter(|&f| f != temp) {
+            summary.push_str(&format!(" (feels like {}{})", feels_like, unit));
+        }
+    }
+    summary
+}
+
+/// Emoji for a weather condition.
+pub fn condition_emoji(condition: Condition) -> &'static str {
+    match condition {
+        Condition::Clear => "☀️",
+        Condition::PartlyCloudy => "⛅",
+        Condition::Cloudy => "☁️",
+        Condition::Fog => "🌫️",
+        Condition::Thunderstorm => "⛈️",
+        Condition::FreezingRain | Condition::Snow => "🌨️",
+        Condition::Drizzle | Condition::Rain => "🌧️",
+        Condition::Unknown => "🌤️",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn paris() -> WeatherData {
+        WeatherData {
+            location: Some("Paris, France".to_string()),
+            current: Some(CurrentConditions {
+                condition: Condition::PartlyCloudy,
+                description: Some("Partly cloudy".to_string()),
+                temperature: Some(18.4),
+                feels_like: Some(17.2),
+                humidity: Some(62.0),
+                wind_speed: Some(15.3),
+                wind_direction: Some(315.0),
+                pressure: Some(1015.2),
+                uv_index: Some(3.1),
+                visibility: Some(10.0),
+                ..CurrentConditions::default()
+            }),
+            ..WeatherData::default()
+        }
+    }
+
+    #[test]
+    fn test_format_report() {
+        assert_eq!(
+            format_report(&paris(), "paris", Units::Metric),
+            "⛅ Weather in Paris, France\n\
+             Partly cloudy, 18°C (feels like 17°C)\n\
+             💧 Humidity 62% · 🌬️ Wind 15 km/h NW\n\
+             🧭 1015 hPa · ☀️ UV 3\n\
+             👁️ Visibility 10 km"
+        );
+        assert_eq!(
+            format_report(&paris(), "paris", Units::Imperial),
+            "⛅ Weather in Paris, France\n\
+             Partly cloudy, 65°F (feels like 63°F)\n\
+             💧 Humidity 62% · 🌬️ Wind 10 mph NW\n\
+             🧭 30 inHg · ☀️ UV 3\n\
+             👁️ Visibility 6 mi"
+        );
+    }
+
+    #[test]
+    fn test_shared_place_from_location_and_venue() {
+        let message = |extra: &str| -> Message {
+            serde_json::from_str(&format!(
+                r#"{{"message_id": 1, "date": 0,
+                    "chat": {{"id": 1, "type": "private", "first_name": "A"}}, {}}}"#,
+                extra
+            ))
+            .unwrap()
+        };
+
+        let location = message(r#""location": {"latitude": -12.0464, "longitude": -77.0428}"#);
+        assert_eq!(
+            SharedPlace::from_message(&location).map(|p| p.label),
+            Some("-12.0464, -77.0428".to_string())
+        );
+
+        let venue = message(
+            r#""location": {"latitude": 48.8584, "longitude": 2.2945},
+               "venue": {"location": {"latitude": 48.8584, "longitude": 2.2945},
+                         "title": "Eiffel Tower", "address": "Champ de Mars"}"#,
+        );
+        let place = SharedPlace::from_message(&venue).unwrap();
+        assert_eq!(place.label, "Eiffel Tower");
+        assert_eq!(place.latitude, 48.8584);
+
+        assert_eq!(SharedPlace::from_message(&message(r#""text": "hi""#)), None);
+    }
+
+    #[test]
+    fn test_sparse_and_empty_payloads() {
+        let sparse = WeatherData {
+            current: Some(CurrentConditions {
+                temperature: Some(5.0),
+                ..CurrentConditions::default()
+            }),
+            ..WeatherData::default()
+        };
+        assert_eq!(
+            format_report(&sparse, "Oslo", Units::Metric),
+            "🌤️ Weather in Oslo\nunknown, 5°C"
+        );
+
+        let empty = WeatherData::default();
+        assert_eq!(
+            format_report(&empty, "Nowhere", Units::Metric),
+            "❌ No current weather for Nowhere"
+        );
+    }
+}
@@ -0,0 +1,188 @@
+// src/commands/utils/weather/prefs.rs
+
+//! `/setcity` and `/units`: per-user defaults for every weather command.
+
+use super::{failure, user_prefs};
+use crate::commands::args::{Arg, ArgKind, Signature};
+use crate::services::ApiService;
+use crate::storage::{Storage, Units};
+use log::error;
+use std::sync::Arc;
+use teloxide::{prelude::*, types::Message};
+
+/// Arguments accepted by `/setcity`.
+pub const SETCITY_ARGS: Signature = Signature::new(&[Arg::optional("city", ArgKind::City)]);
+
+/// Arguments accepted by `/units`.
+pub const UNITS_ARGS: Signature = Signature::new(&[Arg::optional(
+    "system",
+    ArgKind::Choice(&["metric", "imperial"]),
+)]);
+
+/// Words that forget the saved city instead of naming one.
+const CLEAR_WORDS: &[&str] = &["clear", "reset"];
+
+/// Saves the city used by `/weather` and `/forecast` when none is given,
+/// after checking that a weather provider knows it. Without arguments,
+/// shows it; `/setcity clear` (or `reset`) forgets it.
+pub async fn handle_setcity(
+    bot: Bot,
+    msg: Message,
+    text: String,
+    api: Arc<ApiService>,
+    storage: Arc<Storage>,
+) -> ResponseResult<()> {
+    let Some(user) = msg.from.as_ref().map(|u| u.id) else {
+        return Ok(());
+    };
+    let args = match SETCITY_ARGS.parse(&text) {
+        Ok(args) => args,
+        Err(err) => {
+            bot.send_message(msg.chat.id, err.render("setcity", &text, &SETCITY_ARGS))
+                .await?;
+            return Ok(());
+        }
+    };
+
+    let Some(city) = args.text("city").map(str::to_string) else {
+        let reply = match user_prefs(&msg, &storage).await.city {
+            Some(city) => format!(
+                "📍 Your city is {}. Change it with /setcity <city> or forget it with /setcity clear.",
+                city
+            ),
+            None => "📍 No city saved yet. Try /setcity Lima.".to_string(),
+        };
+        bot.send_message(msg.chat.id, reply).await?;
+        return Ok(());
+    };
+
+    if is_clear(&city) {
+        let reply = match storage
+            .weather_prefs
+            .update(|p| p.set_city(user.0, None))
+            .await
+        {
+            Ok(()) => "📍 Forgot your city. Weather commands need one again.".to_string(),
+            Err(err) => {
+                error!("Failed to clear weather city: {}", err);
+                "❌ Failed to forget your city.".to_string()
+            }
+        };
+        bot.send_message(msg.chat.id, reply).await?;
+        return Ok(());
+    }
+
+    let location = match api.get_weather(&city).await {
+        Ok(data) => data.location.unwrap_or_else(|| city.clone()),
+        Err(err) => {
+            bot.send_message(msg.chat.id, failure(&city, &err)).await?;
+            return Ok(());
+        }
+    };
+
+    let reply = match storage
+        .weather_prefs
+        .update(|p| p.set_city(user.0, Some(city)))
+        .await
+    {
+        Ok(()) => format!(
+            "📍 Saved {}. Now /weather and /forecast work without a city.",
+            location
+        ),
+        Err(err) => {
+            error!("Failed to save weather city: {}", err);
+            "❌ Failed to save your city.".to_string()
+        }
+    };
+    bot.send_message(msg.chat.id, reply).await?;
+    Ok(())
+}
+
+/// Switches the sender between metric and imperial units. Without
+/// arguments, shows the current choice.
+pub async fn handle_units(
+    bot: Bot,
+    msg: Message,
+    text: String,
+    storage: Arc<Storage>,
+) -> ResponseResult<()> {
+    let Some(user) = msg.from.as_ref().map(|u| u.id) else {
+        return Ok(());
+    };
+    let args = match UNITS_ARGS.parse(&text) {
+        Ok(args) => args,
+        Err(err) => {
+            bot.send_message(msg.chat.id, err.render("units", &text, &UNITS_ARGS))
+                .await?;
+            return Ok(());
+        }
+    };
+
+    let Some(units) = args.choice("system").map(parse_units) else {
+        let current = user_prefs(&msg, &storage).await.units;
+        bot.send_message(
+            msg.chat.id,
+            format!(
+                "📏 You're using {}. Switch with /units metric or /units imperial.",
+                describe(current)
+            ),
+        )
+        .await?;
+        return Ok(());
+    };
+
+    let reply = match storage
+        .weather_prefs
+        .update(|p| p.set_units(user.0, units))
+        .await
+    {
+        Ok(()) => format!("📏 Weather is now shown in {}.", describe(units)),
+        Err(err) => {
+            error!("Failed to save weather units: {}", err);
+            "❌ Failed to save your units.".to_string()
+        }
+    };
+    bot.send_message(msg.chat.id, reply).await?;
+    Ok(())
+}
+
+fn is_clear(city: &str) -> bool {
+    CLEAR_WORDS.iter().any(|w| w.eq_ignore_ascii_case(city))
+}
+
+fn parse_units(choice: &str) -> Units {
+    match choice {
+        "imperial" => Units::Imperial,
+        _ => Units::Metric,
+    }
+}
+
+fn describe(units: Units) -> &'static str {
+    match units {
+        Units::Metric => "metric units (°C, km/h)",
+        Units::Imperial => "imperial units (°F, mph)",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clear_words() {
+        let args = SETCITY_ARGS.parse("Reset").unwrap();
+        assert!(args.text("city").is_some_and(is_clear));
+        assert!(!is_clear("Clearwater"));
+    }
+
+    #[test]
+    fn test_units_args() {
+        let args = UNITS_ARGS.parse("Imperial").unwrap();
+        assert_eq!(
+            args.choice("system").map(parse_units),
+            Some(Units::Imperial)
+        );
+        assert_eq!(UNITS_ARGS.parse("").unwrap().choice("system"), None);
+        assert!(UNITS_ARGS.parse("kelvin").is_err());
+    }
+}
@@ -0,0 +1,56 @@
+// src/config.rs
+
+//! Operator settings read from the environment at startup.
+
+use std::collections::HashSet;
+use teloxide::types::UserId;
+
+/// Bot-wide settings that affect which commands are available.
+#[derive(Debug, Default, Clone)]
+pub struct BotConfig {
+    /// Telegram user IDs allowed to run admin-only commands (`BOT_ADMINS`).
+    pub admins: HashSet<UserId>,
+    /// Canonical command names switched off by the operator (`DISABLED_COMMANDS`).
+    pub disabled_commands: HashSet<String>,
+}
+
+impl BotConfig {
+    /// Reads comma-separated `BOT_ADMINS` and `DISABLED_COMMANDS`.
+    ///
+    /// Malformed admin IDs are logged and skipped.
+    pub fn from_env() -> Self {
+        let admins = std::env::var("BOT_ADMINS")
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|id| !id.is_empty())
+            .filter_map(|id| match id.parse() {
+                Ok(id) => Some(UserId(id)),
+                Err(_) => {
+                    log::warn!("Ignoring invalid BOT_ADMINS entry: {}", id);
+                    None
+                }
+            })
+            .collect();
+
+        let disabled_commands = std::env::var("DISABLED_COMMANDS")
+            .unwrap_or_default()
+            .split(',')
+            .map(|name| name.trim().trim_start_matches('/').to_lowercase())
+            .filter(|name| !name.is_empty())
+            .collect();
+
+        Self {
+            admins,
+            disabled_commands,
+        }
+    }
+
+    pub fn is_admin(&self, user: UserId) -> bool {
+        self.admins.contains(&user)
+    }
+
+    pub fn is_disabled(&self, command: &str) -> bool {
+        self.disabled_commands.contains(command)
+    }
+}
@@ -0,0 +1,26 @@
+// src/jobs/admin_notices.rs
+
+//! Delivers operational notices from the service layer to bot admins.
+
+use crate::config::BotConfig;
+use std::sync::Arc;
+use teloxide::prelude::*;
+use tokio::sync::mpsc::UnboundedReceiver;
+
+/// Sends every notice received on `notices` to each admin's private chat.
+///
+/// Runs until the sending side is dropped. Delivery failures (e.g. an admin
+/// who never started the bot) are logged and skipped.
+pub async fn run(bot: Bot, config: Arc<BotConfig>, mut notices: UnboundedReceiver<String>) {
+    while let Some(notice) = notices.recv().await {
+        if config.admins.is_empty() {
+            log::warn!("No BOT_ADMINS configured to receive: {}", notice);
+            continue;
+        }
+        for admin in &config.admins {
+            if let Err(err) = bot.send_message(*admin, notice.as_str()).await {
+                log::warn!("Failed to notify admin {}: {}", admin, err);
+            }
+        }
+    }
+}
@@ -0,0 +1,80 @@
+// src/jobs/fx_alerts.rs
+
+//! Periodically checks pending FX alerts and notifies their chats.
+
+use crate::commands::utils::currency::format::{Locale, format_rate};
+use crate::services::ApiService;
+use crate::storage::{FxAlert, Storage};
+use rust_decimal::Decimal;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+use teloxide::prelude::*;
+
+/// How often pending alerts are evaluated. Rates come through the
+/// `ApiService` cache, so this does not translate into upstream calls.
+const CHECK_INTERVAL: Duration = Duration::from_secs(15 * 60);
+
+/// Checks every alert on a fixed interval, forever.
+pub async fn run(bot: Bot, api: Arc<ApiService>, storage: Arc<Storage>) {
+    let mut interval = tokio::time::interval(CHECK_INTERVAL);
+    loop {
+        interval.tick().await;
+        check(&bot, &api, &storage).await;
+    }
+}
+
+/// Fetches each watched pair once, then fires and removes the alerts whose
+/// threshold has been crossed.
+async fn check(bot: &Bot, api: &ApiService, storage: &Storage) {
+    let alerts = storage.fx_alerts.read(|a| a.all().to_vec()).await;
+    let pairs: HashSet<_> = alerts
+        .iter()
+        .map(|a| (a.from.clone(), a.to.clone()))
+        .collect();
+
+    let mut rates = HashMap::with_capacity(pairs.len());
+    for (from, to) in pairs {
+        match api.convert_currency(Decimal::ONE, &from, &to).await {
+            Ok(conversion) => {
+                rates.insert((from, to), conversion.rate);
+            }
+            Err(err) => log::warn!("FX alert check for {} → {} failed: {}", from, to, err),
+        }
+    }
+
+    for alert in alerts {
+        let Some(&rate) = rates.get(&(alert.from.clone(), alert.to.clone())) else {
+            continue;
+        };
+        if !alert.is_triggered(rate) {
+            continue;
+        }
+        if let Err(err) = bot
+            .send_message(ChatId(alert.owner.chat_id), notification(&alert, rate))
+            .await
+        {
+            log::warn!("Failed to deliver FX alert #{}: {}", alert.owner.id, err);
+        }
+        if let Err(err) = storage.fx_alerts.update(|a| a.remove(alert.owner.id)).await {
+            log::error!(
+                "Failed to remove fired FX alert #{}: {}",
+                alert.owner.id,
+                err
+            );
+        }
+    }
+}
+
+fn notification(alert: &FxAlert, rate: Decimal) -> String {
+    let locale = Locale::ENGLISH;
+    format!(
+        "🔔 {} → {} is now {} (alert #{}: {} {})",
+        alert.from,
+        alert.to,
+        format_rate(rate, locale),
+        alert.owner.id,
+        alert.direction.symbol(),
+        format_rate(alert.threshold, locale)
+    )
+}
@@ -0,0 +1,64 @@
+// src/jobs/joke_of_the_day.rs
+
+//! Posts the joke of the day to chats subscribed with `/jotd subscribe`.
+
+use crate::commands::fun::joke::daily::{format_jotd, todays_joke};
+use crate::services::ApiService;
+use crate::storage::Storage;
+use chrono::{DateTime, NaiveDate, Utc};
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+use std::sync::Arc;
+use std::time::Duration;
+use teloxide::prelude::*;
+
+/// How often subscriptions are checked for a joke that's due.
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Checks every subscription on a fixed interval, forever.
+pub async fn run(bot: Bot, api: Arc<ApiService>, storage: Arc<Storage>) {
+    let mut interval = tokio::time::interval(CHECK_INTERVAL);
+    loop {
+        interval.tick().await;
+        deliver(&bot, &api, &storage, Utc::now()).await;
+    }
+}
+
+/// Sends the joke of the day to every chat it's due in. Each day's joke is
+/// picked once, so all subscribers get the same one.
+async fn deliver(bot: &Bot, api: &ApiService, storage: &Storage, now: DateTime<Utc>) {
+    let due = storage.jotd.read(|j| j.due(now)).await;
+    // Usually all today's, but a delivery just before midnight may still be
+    // catching up on yesterday's.
+    let mut jokes: HashMap<NaiveDate, Option<String>> = HashMap::new();
+    for (subscription, date) in due {
+        let joke = match jokes.entry(date) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(todays_joke(api, storage, date).await),
+        };
+        // Left due, so the next check retries within the grace period.
+        let Some(joke) = joke.as_deref() else {
+            continue;
+        };
+
+        if let Err(err) = bot
+            .send_message(ChatId(subscription.chat_id), format_jotd(joke))
+            .await
+        {
+            log::warn!(
+                "Failed to deliver joke of the day to chat {}: {}",
+                subscription.chat_id,
+                err
+            );
+            // Not marked sent, so the next check retries it.
+            continue;
+        }
+        if let Err(err) = storage
+            .jotd
+            .update(|j| j.mark_sent(subscription.chat_id, date))
+            .await
+        {
+            log::error!("Failed to record joke of the day: {}", err);
+        }
+    }
+}
@@ -0,0 +1,9 @@
+// src/jobs/mod.rs
+
+//! Background tasks spawned at startup that run alongside the dispatcher.
+
+pub mod admin_notices;
+pub mod fx_alerts;
+pub mod joke_of_the_day;
+pub mod weather_alerts;
+pub mod weather_digest;
@@ -0,0 +1,96 @@
+// src/jobs/weather_alerts.rs
+
+//! Periodically checks the forecast behind each weather alert and warns
+//! its chat about new events.
+
+use crate::commands::utils::weather::alerts::{forecast_events, format_notification};
+use crate::services::{ApiService, WeatherData};
+use crate::storage::Storage;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use teloxide::prelude::*;
+
+/// How often alerts are evaluated. wttr.in refreshes its forecast a few
+/// times an hour at most, so checking more often finds nothing new.
+const CHECK_INTERVAL: Duration = Duration::from_secs(30 * 60);
+
+/// Checks every alert on a fixed interval, forever.
+pub async fn run(bot: Bot, api: Arc<ApiService>, storage: Arc<Storage>) {
+    let mut interval = tokio::time::interval(CHECK_INTERVAL);
+    loop {
+        interval.tick().await;
+        check(&bot, &api, &storage).await;
+    }
+}
+
+/// Fetches each watched city once, then posts the events each alert hasn't
+/// reported yet. An event counts as reported until the forecast shows it
+/// over, so a storm lasting past midnight is reported only once.
+async fn check(bot: &Bot, api: &ApiService, storage: &Storage) {
+    let alerts = storage.weather_alerts.read(|a| a.all().to_vec()).await;
+
+    let mut weather: HashMap<String, Option<WeatherData>> = HashMap::new();
+    for mut alert in alerts {
+        let key = alert.city.to_lowercase();
+        if !weather.contains_key(&key) {
+            let data = match api.get_weather(&alert.city).await {
+                Ok(data) => Some(data),
+                Err(err) => {
+                    log::warn!("Weather alert check for {} failed: {}", alert.city, err);
+                    None
+                }
+            };
+            weather.insert(key.clone(), data);
+        }
+        let Some(data) = &weather[&key] else {
+            continue;
+        };
+        let Some((now, events)) = forecast_events(&alert, data) else {
+            continue;
+        };
+
+        let new: Vec<_> = events
+            .iter()
+            .filter(|e| !alert.is_reported(e.metric, e.from))
+            .cloned()
+            .collect();
+        if !new.is_empty() {
+            let location = data.location.as_deref().unwrap_or(&alert.city);
+            if let Err(err) = bot
+                .send_message(
+                    ChatId(alert.owner.chat_id),
+                    format_notification(&alert, location, &new),
+                )
+                .await
+            {
+                log::warn!(
+                    "Failed to deliver weather alert #{}: {}",
+                    alert.owner.id,
+                    err
+                );
+                // Not recorded, so the next check tries again.
+                continue;
+            }
+        }
+
+        let before = alert.reported.clone();
+        let spans: Vec<_> = events.iter().map(|e| (e.metric, e.from, e.until)).collect();
+        alert.record(&spans, now);
+        if alert.reported == before {
+            continue;
+        }
+        let id = alert.owner.id;
+        if let Err(err) = storage
+            .weather_alerts
+            .update(|a| {
+                if let Some(stored) = a.get_mut(id) {
+                    stored.reported = alert.reported;
+                }
+            })
+            .await
+        {
+            log::error!("Failed to record weather alert #{}: {}", id, err);
+        }
+    }
+}
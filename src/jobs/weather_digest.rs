@@ -0,0 +1,85 @@
+// src/jobs/weather_digest.rs
+
+//! Posts the daily weather digests chats subscribed to with
+//! `/weather subscribe`, each at its city's local time.
+
+use crate::commands::utils::weather::digest::format_digest;
+use crate::services::{ApiService, WeatherData};
+use crate::storage::{Storage, Units};
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use teloxide::prelude::*;
+
+/// How often subscriptions are checked for a digest that's due.
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Checks every subscription on a fixed interval, forever.
+pub async fn run(bot: Bot, api: Arc<ApiService>, storage: Arc<Storage>) {
+    let mut interval = tokio::time::interval(CHECK_INTERVAL);
+    loop {
+        interval.tick().await;
+        deliver(&bot, &api, &storage, Utc::now()).await;
+    }
+}
+
+/// Sends every digest that's due, fetching each city once however many
+/// chats follow it. Weather comes through the `ApiService` cache, so digests
+/// due in the same few minutes share a single upstream call too.
+async fn deliver(bot: &Bot, api: &ApiService, storage: &Storage, now: DateTime<Utc>) {
+    let due: Vec<_> = storage
+        .weather_subs
+        .read(|s| {
+            s.all()
+                .iter()
+                .filter_map(|s| Some((s.clone(), s.due_date(now)?)))
+                .collect()
+        })
+        .await;
+
+    let mut weather: HashMap<String, Option<WeatherData>> = HashMap::new();
+    for (subscription, date) in due {
+        let key = subscription.city.to_lowercase();
+        if !weather.contains_key(&key) {
+            let data = match api.get_weather(&subscription.city).await {
+                Ok(data) => Some(data),
+                Err(err) => {
+                    log::warn!("Weather digest for {} failed: {}", subscription.city, err);
+                    None
+                }
+            };
+            weather.insert(key.clone(), data);
+        }
+        // Left due, so the next check retries within the grace period.
+        let Some(data) = &weather[&key] else {
+            continue;
+        };
+
+        let units = match subscription.user_id {
+            Some(user) => storage.weather_prefs.read(|p| p.get(user).units).await,
+            None => Units::default(),
+        };
+        let digest = format_digest(data, &subscription.city, units);
+        if let Err(err) = bot.send_message(ChatId(subscription.chat_id), digest).await {
+            log::warn!(
+                "Failed to deliver weather digest to chat {}: {}",
+                subscription.chat_id,
+                err
+            );
+            // Not marked sent, so the next check retries it.
+            continue;
+        }
+
+        let offset = data
+            .utc_offset
+            .map_or(subscription.utc_offset, |o| o.local_minus_utc() / 60);
+        if let Err(err) = storage
+            .weather_subs
+            .update(|s| s.mark_sent(subscription.chat_id, &subscription.city, date, offset))
+            .await
+        {
+            log::error!("Failed to record weather digest: {}", err);
+        }
+    }
+}
@@ -11,10 +11,18 @@ use dotenv::dotenv;
 use log::{error, info};
 
 mod commands;
+mod config;
+mod jobs;
 mod services;
+mod storage;
 
-use crate::commands::{dispatch_command, fallback::unknown_command::unrecognized, Command};
+use crate::commands::{
+    access::Cooldowns, dispatch_callback, dispatch_command, dispatch_location,
+    fallback::unknown_command::unrecognized, Command,
+};
+use crate::config::BotConfig;
 use crate::services::ApiService;
+use crate::storage::Storage;
 use dptree::deps;
 use std::sync::Arc;
 
@@ -31,9 +39,17 @@ fn build_command_schema() -> UpdateHandler<RequestError> {
         )
         .branch(
             Update::filter_message()
-                .filter(|msg: Message| msg.text().map_or(false, |t| t.starts_with('/')))
+                .filter(|msg: Message| msg.text().is_some_and(|t| t.starts_with('/')))
                 .endpoint(unrecognized),
         )
+        .branch(
+            Update::filter_message()
+                .filter(|msg: Message| {
+                    msg.chat.is_private() && (msg.location().is_some() || msg.venue().is_some())
+                })
+                .endpoint(dispatch_location),
+        )
+        .branch(Update::filter_callback_query().endpoint(dispatch_callback))
 }
 
 /// Initializes the bot and verifies connectivity.
@@ -65,14 +81,29 @@ async fn main() {
         }
     };
 
-    let api_service = Arc::new(ApiService::new(
-        std::env::var("EXCHANGERATE_TOKEN").ok(),
-    ));
+    let data_dir = std::env::var("DATA_DIR").unwrap_or_else(|_| "data".to_string());
+    let storage = match Storage::open(&data_dir) {
+        Ok(storage) => Arc::new(storage),
+        Err(err) => {
+            error!("Failed to open storage in {}: {}", data_dir, err);
+            std::process::exit(1);
+        }
+    };
+    let config = Arc::new(BotConfig::from_env());
+    let cooldowns = Arc::new(Cooldowns::default());
+
+    let (notices, notices_rx) = tokio::sync::mpsc::unbounded_channel();
+    let api_service = Arc::new(ApiService::from_env(storage.clone(), notices));
+    tokio::spawn(jobs::admin_notices::run(bot.clone(), config.clone(), notices_rx));
+    tokio::spawn(jobs::fx_alerts::run(bot.clone(), api_service.clone(), storage.clone()));
+    tokio::spawn(jobs::weather_digest::run(bot.clone(), api_service.clone(), storage.clone()));
+    tokio::spawn(jobs::weather_alerts::run(bot.clone(), api_service.clone(), storage.clone()));
+    tokio::spawn(jobs::joke_of_the_day::run(bot.clone(), api_service.clone(), storage.clone()));
 
     let command_handler = build_command_schema();
 
     Dispatcher::builder(bot, command_handler)
-        .dependencies(deps![api_service])
+        .dependencies(deps![api_service, storage, config, cooldowns])
         .default_handler(|upd| async move {
             log::warn!("Unhandled update: {:?}", upd);
         })
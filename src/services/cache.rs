@@ -0,0 +1,61 @@
+// src/services/cache.rs
+
+//! In-memory cache whose readers decide how old an entry may be.
+
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Values remembered with the time they were stored.
+///
+/// Each lookup passes its own `max_age`, so one cache can serve both callers
+/// that need fresh data and callers happy with anything recent enough.
+/// Entries older than `retention` are dropped on insert.
+pub struct TtlCache<K, V> {
+    retention: Duration,
+    entries: Mutex<HashMap<K, (Instant, V)>>,
+}
+
+impl<K, V> TtlCache<K, V>
+where
+    K: Eq + Hash,
+    V: Clone,
+{
+    pub fn new(retention: Duration) -> Self {
+        Self {
+            retention,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// The value for `key` if it was stored less than `max_age` ago.
+    pub fn get(&self, key: &K, max_age: Duration) -> Option<V> {
+        let entries = self.entries.lock().expect("cache lock poisoned");
+        entries
+            .get(key)
+            .filter(|(stored_at, _)| stored_at.elapsed() < max_age)
+            .map(|(_, value)| value.clone())
+    }
+
+    pub fn insert(&self, key: K, value: V) {
+        let mut entries = self.entries.lock().expect("cache lock poisoned");
+        entries.retain(|_, (stored_at, _)| stored_at.elapsed() < self.retention);
+        entries.insert(key, (Instant::now(), value));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_max_age_is_per_lookup() {
+        let cache = TtlCache::new(Duration::from_secs(60));
+        cache.insert("USD/EUR", 0.92);
+
+        assert_eq!(cache.get(&"USD/EUR", Duration::from_secs(60)), Some(0.92));
+        assert_eq!(cache.get(&"USD/EUR", Duration::ZERO), None);
+        assert_eq!(cache.get(&"USD/GBP", Duration::from_secs(60)), None);
+    }
+}
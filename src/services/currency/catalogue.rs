@@ -0,0 +1,720 @@
+// src/services/currency/catalogue.rs
+
+//! Embedded ISO 4217 catalogue of circulating currencies, plus the crypto
+//! assets the rate providers can price. It is the one list of supported
+//! coins: CoinGecko looks up its IDs here and `/crypto` parses against it.
+//!
+//! Queries are validated against these tables before any provider is called,
+//! so typos never spend API quota. Aliases cover common unit names and the
+//! countries that use each currency ("dollar", "yen", "peru").
+
+/// One ISO 4217 currency or crypto asset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Currency {
+    pub code: &'static str,
+    pub name: &'static str,
+    /// Symbol used when formatting; `None` falls back to the code.
+    pub symbol: Option<&'static str>,
+    /// Digits after the decimal point (0 for JPY, 3 for KWD).
+    pub minor_units: u32,
+    /// Lowercase unit names and countries that resolve to this currency.
+    pub aliases: &'static [&'static str],
+    /// CoinGecko's API ID; set for crypto assets only.
+    pub coingecko_id: Option<&'static str>,
+}
+
+impl Currency {
+    const fn new(
+        code: &'static str,
+        name: &'static str,
+        symbol: Option<&'static str>,
+        minor_units: u32,
+        aliases: &'static [&'static str],
+    ) -> Self {
+        Self {
+            code,
+            name,
+            symbol,
+            minor_units,
+            aliases,
+            coingecko_id: None,
+        }
+    }
+
+    /// Marks a crypto asset with its CoinGecko ID.
+    const fn coin(mut self, coingecko_id: &'static str) -> Self {
+        self.coingecko_id = Some(coingecko_id);
+        self
+    }
+}
+
+/// All supported currencies, sorted by code.
+pub const CURRENCIES: &[Currency] = &[
+    Currency::new(
+        "AED",
+        "UAE Dirham",
+        None,
+        2,
+        &["dirham", "uae", "emirates", "dubai"],
+    ),
+    Currency::new(
+        "AFN",
+        "Afghan Afghani",
+        Some("؋"),
+        2,
+        &["afghani", "afghanistan"],
+    ),
+    Currency::new("ALL", "Albanian Lek", None, 2, &["lek", "albania"]),
+    Currency::new("AMD", "Armenian Dram", Some("֏"), 2, &["dram", "armenia"]),
+    Currency::new(
+        "ANG",
+        "Netherlands Antillean Guilder",
+        None,
+        2,
+        &["guilder", "curacao"],
+    ),
+    Currency::new("AOA", "Angolan Kwanza", None, 2, &["kwanza", "angola"]),
+    Currency::new("ARS", "Argentine Peso", None, 2, &["argentina"]),
+    Currency::new(
+        "AUD",
+        "Australian Dollar",
+        Some("A$"),
+        2,
+        &["australia", "aussie"],
+    ),
+    Currency::new("AWG", "Aruban Florin", None, 2, &["aruba"]),
+    Currency::new(
+        "AZN",
+        "Azerbaijani Manat",
+        Some("₼"),
+        2,
+        &["manat", "azerbaijan"],
+    ),
+    Currency::new(
+        "BAM",
+        "Bosnia-Herzegovina Convertible Mark",
+        None,
+        2,
+        &["bosnia"],
+    ),
+    Currency::new("BBD", "Barbadian Dollar", None, 2, &["barbados"]),
+    Currency::new(
+        "BDT",
+        "Bangladeshi Taka",
+        Some("৳"),
+        2,
+        &["taka", "bangladesh"],
+    ),
+    Currency::new("BGN", "Bulgarian Lev", None, 2, &["lev", "bulgaria"]),
+    Currency::new("BHD", "Bahraini Dinar", None, 3, &["bahrain"]),
+    Currency::new("BIF", "Burundian Franc", None, 0, &["burundi"]),
+    Currency::new("BMD", "Bermudian Dollar", None, 2, &["bermuda"]),
+    Currency::new("BND", "Brunei Dollar", None, 2, &["brunei"]),
+    Currency::new(
+        "BOB",
+        "Bolivian Boliviano",
+        Some("Bs"),
+        2,
+        &["boliviano", "bolivia"],
+    ),
+    Currency::new(
+        "BRL",
+        "Brazilian Real",
+        Some("R$"),
+        2,
+        &["real", "reais", "brazil"],
+    ),
+    Currency::new("BSD", "Bahamian Dollar", None, 2, &["bahamas"]),
+    Currency::new(
+        "BTN",
+        "Bhutanese Ngultrum",
+        None,
+        2,
+        &["ngultrum", "bhutan"],
+    ),
+    Currency::new("BWP", "Botswana Pula", None, 2, &["pula", "botswana"]),
+    Currency::new("BYN", "Belarusian Ruble", None, 2, &["belarus"]),
+    Currency::new("BZD", "Belize Dollar", None, 2, &["belize"]),
+    Currency::new(
+        "CAD",
+        "Canadian Dollar",
+        Some("C$"),
+        2,
+        &["canada", "loonie"],
+    ),
+    Currency::new("CDF", "Congolese Franc", None, 2, &["congo"]),
+    Currency::new(
+        "CHF",
+        "Swiss Franc",
+        None,
+        2,
+        &["franc", "switzerland", "swiss", "liechtenstein"],
+    ),
+    Currency::new("CLP", "Chilean Peso", None, 0, &["chile"]),
+    Currency::new(
+        "CNY",
+        "Chinese Yuan",
+        Some("CN¥"),
+        2,
+        &["yuan", "renminbi", "rmb", "china"],
+    ),
+    Currency::new("COP", "Colombian Peso", None, 2, &["colombia"]),
+    Currency::new(
+        "CRC",
+        "Costa Rican Colón",
+        Some("₡"),
+        2,
+        &["colon", "costa rica"],
+    ),
+    Currency::new("CUP", "Cuban Peso", None, 2, &["cuba"]),
+    Currency::new(
+        "CVE",
+        "Cape Verdean Escudo",
+        None,
+        2,
+        &["escudo", "cape verde"],
+    ),
+    Currency::new(
+        "CZK",
+        "Czech Koruna",
+        Some("Kč"),
+        2,
+        &["koruna", "czechia", "czech"],
+    ),
+    Currency::new("DJF", "Djiboutian Franc", None, 0, &["djibouti"]),
+    Currency::new(
+        "DKK",
+        "Danish Krone",
+        None,
+        2,
+        &["denmark", "greenland", "faroe"],
+    ),
+    Currency::new("DOP", "Dominican Peso", None, 2, &["dominican republic"]),
+    Currency::new("DZD", "Algerian Dinar", None, 2, &["algeria"]),
+    Currency::new("EGP", "Egyptian Pound", Some("E£"), 2, &["egypt"]),
+    Currency::new("ERN", "Eritrean Nakfa", None, 2, &["nakfa", "eritrea"]),
+    Currency::new("ETB", "Ethiopian Birr", None, 2, &["birr", "ethiopia"]),
+    Currency::new(
+        "EUR",
+        "Euro",
+        Some("€"),
+        2,
+        &[
+            "euro",
+            "eurozone",
+            "europe",
+            "germany",
+            "france",
+            "spain",
+            "italy",
+            "portugal",
+            "netherlands",
+            "belgium",
+            "austria",
+            "ireland",
+            "finland",
+            "greece",
+            "croatia",
+            "slovakia",
+            "slovenia",
+            "estonia",
+            "latvia",
+            "lithuania",
+            "luxembourg",
+            "malta",
+            "cyprus",
+            "montenegro",
+            "kosovo",
+            "andorra",
+            "monaco",
+        ],
+    ),
+    Currency::new("FJD", "Fijian Dollar", None, 2, &["fiji"]),
+    Currency::new("FKP", "Falkland Islands Pound", None, 2, &["falklands"]),
+    Currency::new(
+        "GBP",
+        "British Pound",
+        Some("£"),
+        2,
+        &[
+            "pound", "sterling", "quid", "uk", "britain", "england", "scotland", "wales",
+        ],
+    ),
+    Currency::new("GEL", "Georgian Lari", Some("₾"), 2, &["lari", "georgia"]),
+    Currency::new("GHS", "Ghanaian Cedi", Some("GH₵"), 2, &["cedi", "ghana"]),
+    Currency::new("GIP", "Gibraltar Pound", None, 2, &["gibraltar"]),
+    Currency::new("GMD", "Gambian Dalasi", None, 2, &["dalasi", "gambia"]),
+    Currency::new("GNF", "Guinean Franc", None, 0, &["guinea"]),
+    Currency::new(
+        "GTQ",
+        "Guatemalan Quetzal",
+        Some("Q"),
+        2,
+        &["quetzal", "guatemala"],
+    ),
+    Currency::new("GYD", "Guyanese Dollar", None, 2, &["guyana"]),
+    Currency::new("HKD", "Hong Kong Dollar", Some("HK$"), 2, &["hong kong"]),
+    Currency::new("HNL", "Honduran Lempira", None, 2, &["lempira", "honduras"]),
+    Currency::new("HTG", "Haitian Gourde", None, 2, &["gourde", "haiti"]),
+    Currency::new(
+        "HUF",
+        "Hungarian Forint",
+        Some("Ft"),
+        2,
+        &["forint", "hungary"],
+    ),
+    Currency::new(
+        "IDR",
+        "Indonesian Rupiah",
+        Some("Rp"),
+        2,
+        &["rupiah", "indonesia"],
+    ),
+    Currency::new(
+        "ILS",
+        "Israeli New Shekel",
+        Some("₪"),
+        2,
+        &["shekel", "israel"],
+    ),
+    Currency::new("INR", "Indian Rupee", Some("₹"), 2, &["rupee", "india"]),
+    Currency::new("IQD", "Iraqi Dinar", None, 3, &["iraq"]),
+    Currency::new("IRR", "Iranian Rial", None, 2, &["iran"]),
+    Currency::new("ISK", "Icelandic Króna", None, 0, &["iceland"]),
+    Currency::new("JMD", "Jamaican Dollar", None, 2, &["jamaica"]),
+    Currency::new("JOD", "Jordanian Dinar", None, 3, &["jordan"]),
+    Currency::new("JPY", "Japanese Yen", Some("¥"), 0, &["yen", "japan"]),
+    Currency::new("KES", "Kenyan Shilling", None, 2, &["kenya"]),
+    Currency::new("KGS", "Kyrgyzstani Som", None, 2, &["kyrgyzstan"]),
+    Currency::new("KHR", "Cambodian Riel", None, 2, &["riel", "cambodia"]),
+    Currency::new("KMF", "Comorian Franc", None, 0, &["comoros"]),
+    Currency::new(
+        "KRW",
+        "South Korean Won",
+        Some("₩"),
+        0,
+        &["won", "korea", "south korea"],
+    ),
+    Currency::new("KWD", "Kuwaiti Dinar", None, 3, &["dinar", "kuwait"]),
+    Currency::new("KYD", "Cayman Islands Dollar", None, 2, &["cayman"]),
+    Currency::new(
+        "KZT",
+        "Kazakhstani Tenge",
+        Some("₸"),
+        2,
+        &["tenge", "kazakhstan"],
+    ),
+    Currency::new("LAK", "Lao Kip", Some("₭"), 2, &["kip", "laos"]),
+    Currency::new("LBP", "Lebanese Pound", None, 2, &["lebanon"]),
+    Currency::new("LKR", "Sri Lankan Rupee", None, 2, &["sri lanka"]),
+    Currency::new("LRD", "Liberian Dollar", None, 2, &["liberia"]),
+    Currency::new("LSL", "Lesotho Loti", None, 2, &["loti", "lesotho"]),
+    Currency::new("LYD", "Libyan Dinar", None, 3, &["libya"]),
+    Currency::new("MAD", "Moroccan Dirham", None, 2, &["morocco"]),
+    Currency::new("MDL", "Moldovan Leu", None, 2, &["moldova"]),
+    Currency::new("MGA", "Malagasy Ariary", None, 2, &["ariary", "madagascar"]),
+    Currency::new(
+        "MKD",
+        "Macedonian Denar",
+        None,
+        2,
+        &["denar", "north macedonia"],
+    ),
+    Currency::new(
+        "MMK",
+        "Myanmar Kyat",
+        None,
+        2,
+        &["kyat", "myanmar", "burma"],
+    ),
+    Currency::new(
+        "MNT",
+        "Mongolian Tögrög",
+        Some("₮"),
+        2,
+        &["tugrik", "mongolia"],
+    ),
+    Currency::new(
+        "MOP",
+        "Macanese Pataca",
+        None,
+        2,
+        &["pataca", "macau", "macao"],
+    ),
+    Currency::new(
+        "MRU",
+        "Mauritanian Ouguiya",
+        None,
+        2,
+        &["ouguiya", "mauritania"],
+    ),
+    Currency::new("MUR", "Mauritian Rupee", None, 2, &["mauritius"]),
+    Currency::new(
+        "MVR",
+        "Maldivian Rufiyaa",
+        None,
+        2,
+        &["rufiyaa", "maldives"],
+    ),
+    Currency::new("MWK", "Malawian Kwacha", None, 2, &["malawi"]),
+    Currency::new("MXN", "Mexican Peso", Some("MX$"), 2, &["peso", "mexico"]),
+    Currency::new(
+        "MYR",
+        "Malaysian Ringgit",
+        Some("RM"),
+        2,
+        &["ringgit", "malaysia"],
+    ),
+    Currency::new(
+        "MZN",
+        "Mozambican Metical",
+        None,
+        2,
+        &["metical", "mozambique"],
+    ),
+    Currency::new("NAD", "Namibian Dollar", None, 2, &["namibia"]),
+    Currency::new("NGN", "Nigerian Naira", Some("₦"), 2, &["naira", "nigeria"]),
+    Currency::new(
+        "NIO",
+        "Nicaraguan Córdoba",
+        None,
+        2,
+        &["cordoba", "nicaragua"],
+    ),
+    Currency::new("NOK", "Norwegian Krone", None, 2, &["krone", "norway"]),
+    Currency::new("NPR", "Nepalese Rupee", None, 2, &["nepal"]),
+    Currency::new(
+        "NZD",
+        "New Zealand Dollar",
+        Some("NZ$"),
+        2,
+        &["new zealand", "kiwi"],
+    ),
+    Currency::new("OMR", "Omani Rial", None, 3, &["oman"]),
+    Currency::new("PAB", "Panamanian Balboa", None, 2, &["balboa", "panama"]),
+    Currency::new(
+        "PEN",
+        "Peruvian Sol",
+        Some("S/"),
+        2,
+        &["sol", "soles", "peru"],
+    ),
+    Currency::new(
+        "PGK",
+        "Papua New Guinean Kina",
+        None,
+        2,
+        &["kina", "papua new guinea"],
+    ),
+    Currency::new("PHP", "Philippine Peso", Some("₱"), 2, &["philippines"]),
+    Currency::new("PKR", "Pakistani Rupee", None, 2, &["pakistan"]),
+    Currency::new("PLN", "Polish Złoty", Some("zł"), 2, &["zloty", "poland"]),
+    Currency::new(
+        "PYG",
+        "Paraguayan Guaraní",
+        Some("₲"),
+        0,
+        &["guarani", "paraguay"],
+    ),
+    Currency::new("QAR", "Qatari Riyal", None, 2, &["qatar"]),
+    Currency::new("RON", "Romanian Leu", None, 2, &["leu", "romania"]),
+    Currency::new("RSD", "Serbian Dinar", None, 2, &["serbia"]),
+    Currency::new(
+        "RUB",
+        "Russian Ruble",
+        Some("₽"),
+        2,
+        &["ruble", "rouble", "russia"],
+    ),
+    Currency::new("RWF", "Rwandan Franc", None, 0, &["rwanda"]),
+    Currency::new(
+        "SAR",
+        "Saudi Riyal",
+        None,
+        2,
+        &["riyal", "saudi", "saudi arabia"],
+    ),
+    Currency::new(
+        "SBD",
+        "Solomon Islands Dollar",
+        None,
+        2,
+        &["solomon islands"],
+    ),
+    Currency::new("SCR", "Seychellois Rupee", None, 2, &["seychelles"]),
+    Currency::new("SDG", "Sudanese Pound", None, 2, &["sudan"]),
+    Currency::new("SEK", "Swedish Krona", None, 2, &["krona", "sweden"]),
+    Currency::new("SGD", "Singapore Dollar", Some("S$"), 2, &["singapore"]),
+    Currency::new("SHP", "Saint Helena Pound", None, 2, &["saint helena"]),
+    Currency::new(
+        "SLE",
+        "Sierra Leonean Leone",
+        None,
+        2,
+        &["leone", "sierra leone"],
+    ),
+    Currency::new("SOS", "Somali Shilling", None, 2, &["somalia"]),
+    Currency::new("SRD", "Surinamese Dollar", None, 2, &["suriname"]),
+    Currency::new("SSP", "South Sudanese Pound", None, 2, &["south sudan"]),
+    Currency::new(
+        "STN",
+        "São Tomé and Príncipe Dobra",
+        None,
+        2,
+        &["dobra", "sao tome"],
+    ),
+    Currency::new("SYP", "Syrian Pound", None, 2, &["syria"]),
+    Currency::new(
+        "SZL",
+        "Swazi Lilangeni",
+        None,
+        2,
+        &["lilangeni", "eswatini", "swaziland"],
+    ),
+    Currency::new("THB", "Thai Baht", Some("฿"), 2, &["baht", "thailand"]),
+    Currency::new(
+        "TJS",
+        "Tajikistani Somoni",
+        None,
+        2,
+        &["somoni", "tajikistan"],
+    ),
+    Currency::new("TMT", "Turkmenistani Manat", None, 2, &["turkmenistan"]),
+    Currency::new("TND", "Tunisian Dinar", None, 3, &["tunisia"]),
+    Currency::new("TOP", "Tongan Paʻanga", None, 2, &["paanga", "tonga"]),
+    Currency::new(
+        "TRY",
+        "Turkish Lira",
+        Some("₺"),
+        2,
+        &["lira", "turkey", "turkiye"],
+    ),
+    Currency::new(
+        "TTD",
+        "Trinidad and Tobago Dollar",
+        None,
+        2,
+        &["trinidad", "tobago"],
+    ),
+    Currency::new("TWD", "New Taiwan Dollar", Some("NT$"), 2, &["taiwan"]),
+    Currency::new("TZS", "Tanzanian Shilling", None, 2, &["tanzania"]),
+    Currency::new(
+        "UAH",
+        "Ukrainian Hryvnia",
+        Some("₴"),
+        2,
+        &["hryvnia", "ukraine"],
+    ),
+    Currency::new("UGX", "Ugandan Shilling", None, 0, &["uganda"]),
+    Currency::new(
+        "USD",
+        "US Dollar",
+        Some("$"),
+        2,
+        &[
+            "dollar",
+            "buck",
+            "usa",
+            "us",
+            "america",
+            "united states",
+            "ecuador",
+            "el salvador",
+        ],
+    ),
+    Currency::new("UYU", "Uruguayan Peso", None, 2, &["uruguay"]),
+    Currency::new("UZS", "Uzbekistani Som", None, 2, &["som", "uzbekistan"]),
+    Currency::new(
+        "VES",
+        "Venezuelan Bolívar",
+        None,
+        2,
+        &["bolivar", "venezuela"],
+    ),
+    Currency::new("VND", "Vietnamese Đồng", Some("₫"), 0, &["dong", "vietnam"]),
+    Currency::new("VUV", "Vanuatu Vatu", None, 0, &["vatu", "vanuatu"]),
+    Currency::new("WST", "Samoan Tālā", None, 2, &["tala", "samoa"]),
+    Currency::new(
+        "XAF",
+        "Central African CFA Franc",
+        None,
+        0,
+        &["cameroon", "gabon", "chad"],
+    ),
+    Currency::new("XCD", "East Caribbean Dollar", None, 2, &["east caribbean"]),
+    Currency::new(
+        "XOF",
+        "West African CFA Franc",
+        None,
+        0,
+        &[
+            "cfa",
+            "senegal",
+            "ivory coast",
+            "mali",
+            "benin",
+            "togo",
+            "niger",
+            "burkina faso",
+        ],
+    ),
+    Currency::new(
+        "XPF",
+        "CFP Franc",
+        None,
+        0,
+        &["cfp", "new caledonia", "french polynesia"],
+    ),
+    Currency::new("YER", "Yemeni Rial", None, 2, &["yemen"]),
+    Currency::new(
+        "ZAR",
+        "South African Rand",
+        Some("R"),
+        2,
+        &["rand", "south africa"],
+    ),
+    Currency::new("ZMW", "Zambian Kwacha", None, 2, &["kwacha", "zambia"]),
+    Currency::new("ZWG", "Zimbabwe Gold", None, 2, &["zimbabwe"]),
+];
+
+/// Supported crypto assets, sorted by ticker. Minor units are the display
+/// precision, capped at eight digits.
+pub const CRYPTOCURRENCIES: &[Currency] = &[
+    Currency::new("ADA", "Cardano", None, 6, &["cardano"]).coin("cardano"),
+    Currency::new("AVAX", "Avalanche", None, 8, &["avalanche"]).coin("avalanche-2"),
+    Currency::new("BCH", "Bitcoin Cash", None, 8, &["bitcoin cash"]).coin("bitcoin-cash"),
+    Currency::new("BNB", "BNB", None, 8, &["binance coin"]).coin("binancecoin"),
+    Currency::new("BTC", "Bitcoin", Some("₿"), 8, &["bitcoin", "xbt"]).coin("bitcoin"),
+    Currency::new("DOGE", "Dogecoin", None, 8, &["dogecoin"]).coin("dogecoin"),
+    Currency::new("DOT", "Polkadot", None, 8, &["polkadot"]).coin("polkadot"),
+    Currency::new("ETH", "Ether", Some("Ξ"), 8, &["ether", "ethereum"]).coin("ethereum"),
+    Currency::new("LINK", "Chainlink", None, 8, &["chainlink"]).coin("chainlink"),
+    Currency::new("LTC", "Litecoin", None, 8, &["litecoin"]).coin("litecoin"),
+    Currency::new("SOL", "Solana", None, 8, &["solana"]).coin("solana"),
+    Currency::new("TRX", "TRON", None, 6, &["tron"]).coin("tron"),
+    Currency::new("USDC", "USD Coin", None, 6, &["usd coin"]).coin("usd-coin"),
+    Currency::new("USDT", "Tether", None, 6, &["tether"]).coin("tether"),
+    Currency::new("XLM", "Stellar Lumen", None, 7, &["stellar", "lumen"]).coin("stellar"),
+    Currency::new("XRP", "XRP", None, 6, &["ripple"]).coin("ripple"),
+];
+
+/// Most words in any alias ("papua new guinea").
+pub const MAX_ALIAS_WORDS: usize = 3;
+
+/// Every fiat currency followed by every crypto asset.
+fn all() -> impl Iterator<Item = &'static Currency> {
+    CURRENCIES.iter().chain(CRYPTOCURRENCIES)
+}
+
+/// Looks up a currency by its exact ISO code or crypto ticker, ignoring case.
+pub fn by_code(code: &str) -> Option<&'static Currency> {
+    let code = code.to_ascii_uppercase();
+    let find = |table: &'static [Currency]| {
+        table
+            .binary_search_by(|c| c.code.cmp(code.as_str()))
+            .ok()
+            .map(|i| &table[i])
+    };
+    find(CURRENCIES).or_else(|| find(CRYPTOCURRENCIES))
+}
+
+/// Validates a currency argument: a known code in any case, uppercased.
+pub fn parse_code(token: &str) -> Option<String> {
+    by_code(token).map(|c| c.code.to_string())
+}
+
+/// Whether `code` is one of the [`CRYPTOCURRENCIES`].
+pub fn is_crypto(code: &str) -> bool {
+    coingecko_id(code).is_some()
+}
+
+/// CoinGecko's ID for a ticker such as `BTC`.
+pub fn coingecko_id(code: &str) -> Option<&'static str> {
+    by_code(code).and_then(|c| c.coingecko_id)
+}
+
+/// Looks up a currency by its formatting symbol (`$`, `€`, `S/`, `₿`).
+pub fn by_symbol(symbol: &str) -> Option<&'static Currency> {
+    all().find(|c| c.symbol == Some(symbol))
+}
+
+/// Resolves a code or alias such as `usd`, `dollars` or `yen`.
+///
+/// A trailing plural `s` is ignored when the word itself isn't known.
+pub fn resolve(word: &str) -> Option<&'static Currency> {
+    let word = word.trim().to_lowercase();
+    let by_alias = |alias: &str| all().find(|c| c.aliases.contains(&alias));
+
+    by_code(&word)
+        .or_else(|| by_alias(&word))
+        .or_else(|| word.strip_suffix('s').and_then(by_alias))
+}
+
+/// Finds currencies and crypto assets whose code, name or aliases contain
+/// `term`.
+///
+/// An exact code or alias match is listed first.
+pub fn search(term: &str) -> Vec<&'static Currency> {
+    let term = term.trim().to_lowercase();
+    let exact = resolve(&term);
+    let mut matches: Vec<_> = exact.into_iter().collect();
+
+    matches.extend(all().filter(|c| {
+        Some(*c) != exact
+            && (c.code.eq_ignore_ascii_case(&term)
+                || c.name.to_lowercase().contains(&term)
+                || c.aliases.iter().any(|a| a.contains(term.as_str())))
+    }));
+    matches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_table_is_sorted_by_code() {
+        assert!(CURRENCIES.windows(2).all(|w| w[0].code < w[1].code));
+        assert!(CRYPTOCURRENCIES.windows(2).all(|w| w[0].code < w[1].code));
+    }
+
+    #[test]
+    fn test_crypto_assets_are_priced_and_distinct() {
+        for coin in CRYPTOCURRENCIES {
+            assert!(coin.coingecko_id.is_some(), "{}", coin.code);
+            assert!(!CURRENCIES.iter().any(|c| c.code == coin.code));
+        }
+        assert!(CURRENCIES.iter().all(|c| c.coingecko_id.is_none()));
+        assert_eq!(coingecko_id("avax"), Some("avalanche-2"));
+        assert_eq!(resolve("bitcoins").map(|c| c.code), Some("BTC"));
+        assert_eq!(by_symbol("₿").map(|c| c.code), Some("BTC"));
+        assert!(is_crypto("eth") && !is_crypto("EUR"));
+    }
+
+    #[test]
+    fn test_aliases_fit_word_limit() {
+        for alias in all().flat_map(|c| c.aliases) {
+            assert_eq!(alias, &alias.trim().to_lowercase());
+            assert!(alias.split(' ').count() <= MAX_ALIAS_WORDS, "{}", alias);
+        }
+    }
+
+    #[test]
+    fn test_resolve_codes_and_aliases() {
+        assert_eq!(resolve("usd").map(|c| c.code), Some("USD"));
+        assert_eq!(resolve("Dollars").map(|c| c.code), Some("USD"));
+        assert_eq!(resolve("yen").map(|c| c.code), Some("JPY"));
+        assert_eq!(resolve("peru").map(|c| c.code), Some("PEN"));
+        assert_eq!(resolve("EUo"), None);
+        assert_eq!(by_code("KWD").map(|c| c.minor_units), Some(3));
+        assert_eq!(by_symbol("€").map(|c| c.code), Some("EUR"));
+    }
+
+    #[test]
+    fn test_search() {
+        let codes: Vec<_> = search("peso").iter().map(|c| c.code).collect();
+        assert_eq!(codes.first(), Some(&"MXN"));
+        assert!(codes.contains(&"ARS") && codes.contains(&"CLP"));
+        assert!(search("zzz").is_empty());
+    }
+}
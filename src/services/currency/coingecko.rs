@@ -0,0 +1,361 @@
+// src/services/currency/coingecko.rs
+
+//! [CoinGecko](https://www.coingecko.com/en/api) — crypto prices against
+//! fiat currencies and other coins, with their 24-hour change. Works without
+//! a key; a free demo key (`COINGECKO_API_KEY`) raises the rate limit.
+
+use super::catalogue::coingecko_id as coin_id;
+use super::{CurrencyProvider, DailyRate, Quote, decimal};
+use crate::services::ServiceError;
+use async_trait::async_trait;
+use chrono::{DateTime, Days, NaiveDate, Utc};
+use reqwest::{Client, StatusCode};
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use std::collections::{BTreeMap, HashMap};
+
+const BASE_URL: &str = "https://api.coingecko.com/api/v3";
+
+/// Currency both sides are priced in when converting coin to coin.
+const CROSS_CURRENCY: &str = "usd";
+
+/// `/simple/price` response body: coin ID → `eur`, `eur_24h_change`,
+/// `last_updated_at`, … (fields may be `null`).
+type PriceResponse = HashMap<String, HashMap<String, Option<f64>>>;
+
+/// `/coins/<id>/market_chart/range` response body.
+#[derive(Debug, Deserialize)]
+struct ChartResponse {
+    /// `[unix_millis, price]` pairs in time order.
+    prices: Vec<(f64, f64)>,
+}
+
+pub struct CoinGecko {
+    client: Client,
+    base_url: String,
+    api_key: Option<String>,
+}
+
+impl CoinGecko {
+    pub fn new(client: Client, api_key: Option<String>) -> Self {
+        Self::with_base_url(client, BASE_URL, api_key)
+    }
+
+    /// Points the provider at another server, e.g. a local stub in tests.
+    pub fn with_base_url(client: Client, base_url: &str, api_key: Option<String>) -> Self {
+        Self {
+            client,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            api_key,
+        }
+    }
+
+    /// GETs `path`, mapping the API's 404 for unknown coins to
+    /// `ServiceError::NotFound`.
+    async fn get<T: DeserializeOwned>(&self, path: &str, pair: &str) -> Result<T, ServiceError> {
+        let url = format!("{}{}", self.base_url, path);
+        let mut request = self.client.get(&url);
+        if let Some(key) = &self.api_key {
+            request = request.header("x-cg-demo-api-key", key);
+        }
+        let response = request
+            .send()
+            .await
+            .map_err(|e| ServiceError::network(&url, e))?;
+
+        match response.status() {
+            StatusCode::OK => response
+                .json::<T>()
+                .await
+                .map_err(|e| ServiceError::parse(&url, e)),
+            StatusCode::NOT_FOUND => Err(ServiceError::not_found("Currency pair", pair)),
+            status => Err(ServiceError::unexpected_status(&url, status)),
+        }
+    }
+
+    /// Current prices of the coins `ids` in `vs`, in a single call.
+    async fn prices(
+        &self,
+        ids: &[&str],
+        vs: &str,
+        pair: &str,
+    ) -> Result<HashMap<String, Quote>, ServiceError> {
+        let vs = vs.to_lowercase();
+        let path = format!(
+            "/simple/price?ids={}&vs_currencies={}&include_24hr_change=true&include_last_updated_at=true",
+            ids.join(","),
+            vs
+        );
+        let data: PriceResponse = self.get(&path, pair).await?;
+        let change_key = format!("{}_24h_change", vs);
+
+        Ok(data
+            .into_iter()
+            .filter_map(|(id, fields)| {
+                let field = |name: &str| fields.get(name).copied().flatten();
+                let quote = Quote {
+                    rate: field(&vs).and_then(decimal)?,
+                    published_at: field("last_updated_at")
+                        .and_then(|ts| DateTime::from_timestamp(ts as i64, 0))
+                        .unwrap_or_else(Utc::now),
+                    change_24h: field(&change_key)
+                        .and_then(|c| Decimal::try_from(c).ok())
+                        .map(|c| c.round_dp(4)),
+                };
+                Some((id, quote))
+            })
+            .collect())
+    }
+
+    /// Closing price of coin `id` in `vs` for each day in `start..=end`.
+    async fn closes(
+        &self,
+        id: &str,
+        vs: &str,
+        start: NaiveDate,
+        end: NaiveDate,
+        pair: &str,
+    ) -> Result<BTreeMap<NaiveDate, Decimal>, ServiceError> {
+        let from = start.and_time(Default::default()).and_utc().timestamp();
+        let to = (end + Days::new(1))
+            .and_time(Default::default())
+            .and_utc()
+            .timestamp()
+            - 1;
+        let path = format!(
+            "/coins/{}/market_chart/range?vs_currency={}&from={}&to={}",
+            id,
+            vs.to_lowercase(),
+            from,
+            to
+        );
+        let data: ChartResponse = self.get(&path, pair).await?;
+
+        // Points are hourly or daily depending on the range; the last one of
+        // each day is its close.
+        let mut closes = BTreeMap::new();
+        for (millis, price) in data.prices {
+            let at = DateTime::from_timestamp_millis(millis as i64);
+            if let (Some(at), Some(price)) = (at, decimal(price)) {
+                closes.insert(at.date_naive(), price);
+            }
+        }
+        closes.retain(|date, _| (start..=end).contains(date));
+        Ok(closes)
+    }
+}
+
+#[async_trait]
+impl CurrencyProvider for CoinGecko {
+    fn id(&self) -> &'static str {
+        "coingecko"
+    }
+
+    fn name(&self) -> &'static str {
+        "CoinGecko"
+    }
+
+    fn covers(&self, from: &str, to: &str) -> bool {
+        coin_id(from).is_some() || coin_id(to).is_some()
+    }
+
+    async fn rate(&self, from: &str, to: &str) -> Result<Quote, ServiceError> {
+        let pair = format!("{}/{}", from, to);
+        let missing = || ServiceError::not_found("Currency pair", &pair);
+
+        match (coin_id(from), coin_id(to)) {
+            (Some(coin), None) => self
+                .prices(&[coin], to, &pair)
+                .await?
+                .remove(coin)
+                .ok_or_else(missing),
+            (None, Some(coin)) => self
+                .prices(&[coin], from, &pair)
+                .await?
+                .remove(coin)
+                .map(|quote| quote.inverse())
+                .ok_or_else(missing),
+            (Some(base), Some(quote)) => {
+                let mut prices = self.prices(&[base, quote], CROSS_CURRENCY, &pair).await?;
+                match (prices.remove(base), prices.remove(quote)) {
+                    (Some(base), Some(quote)) => Ok(base.cross(&quote)),
+                    _ => Err(missing()),
+                }
+            }
+            (None, None) => Err(missing()),
+        }
+    }
+
+    /// Prices several coins in one call when `to` is fiat.
+    async fn rates(&self, bases: &[&str], to: &str) -> Result<Vec<(String, Quote)>, ServiceError> {
+        let pair = format!("{}/{}", bases.join(","), to);
+        let ids: Option<Vec<&str>> = bases.iter().map(|base| coin_id(base)).collect();
+        let Some(ids) = ids.filter(|_| coin_id(to).is_none()) else {
+            return Err(ServiceError::not_found("Batched rates", &pair));
+        };
+
+        let mut prices = self.prices(&ids, to, &pair).await?;
+        Ok(bases
+            .iter()
+            .zip(ids)
+            .filter_map(|(base, id)| Some((base.to_string(), prices.remove(id)?)))
+            .collect())
+    }
+
+    async fn history(
+        &self,
+        from: &str,
+        to: &str,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<Vec<DailyRate>, ServiceError> {
+        let pair = format!("{}/{}", from, to);
+        let closes = match (coin_id(from), coin_id(to)) {
+            (Some(coin), None) => self.closes(coin, to, start, end, &pair).await?,
+            (None, Some(coin)) => {
+                let mut closes = self.closes(coin, from, start, end, &pair).await?;
+                closes
+                    .values_mut()
+                    .for_each(|rate| *rate = Decimal::ONE / *rate);
+                closes
+            }
+            (Some(base), Some(quote)) => {
+                let base = self.closes(base, CROSS_CURRENCY, start, end, &pair).await?;
+                let quote = self
+                    .closes(quote, CROSS_CURRENCY, start, end, &pair)
+                    .await?;
+                base.into_iter()
+                    .filter_map(|(date, rate)| Some((date, rate / quote.get(&date)?)))
+                    .collect()
+            }
+            (None, None) => return Err(ServiceError::not_found("Currency pair", &pair)),
+        };
+
+        Ok(closes
+            .into_iter()
+            .map(|(date, rate)| DailyRate { date, rate })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::{Matcher, Server};
+
+    fn stub(server: &Server) -> CoinGecko {
+        CoinGecko::with_base_url(Client::new(), &server.url(), None)
+    }
+
+    fn price_query(ids: &str, vs: &str) -> Matcher {
+        Matcher::AllOf(vec![
+            Matcher::UrlEncoded("ids".into(), ids.into()),
+            Matcher::UrlEncoded("vs_currencies".into(), vs.into()),
+        ])
+    }
+
+    #[tokio::test]
+    async fn test_coin_to_fiat_and_back() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("GET", "/simple/price")
+            .match_query(price_query("bitcoin", "eur"))
+            .with_body(
+                r#"{"bitcoin":{"eur":50000,"eur_24h_change":25.0,"last_updated_at":1738324800}}"#,
+            )
+            .expect(2)
+            .create_async()
+            .await;
+        let provider = stub(&server);
+
+        let quote = provider.rate("BTC", "EUR").await.unwrap();
+        assert_eq!(quote.rate, Decimal::from(50_000));
+        assert_eq!(quote.change_24h, Some(Decimal::from(25)));
+        assert_eq!(quote.published_at.to_rfc3339(), "2025-01-31T12:00:00+00:00");
+
+        let inverse = provider.rate("EUR", "BTC").await.unwrap();
+        assert_eq!(inverse.rate, Decimal::new(2, 5));
+        assert_eq!(inverse.change_24h, Some(Decimal::from(-20)));
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_coin_to_coin_uses_one_call() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("GET", "/simple/price")
+            .match_query(price_query("bitcoin,ethereum", "usd"))
+            .with_body(r#"{"bitcoin":{"usd":60000},"ethereum":{"usd":3000}}"#)
+            .create_async()
+            .await;
+
+        let quote = stub(&server).rate("BTC", "ETH").await.unwrap();
+        assert_eq!(quote.rate, Decimal::from(20));
+        assert_eq!(quote.change_24h, None);
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_several_coins_use_one_call() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("GET", "/simple/price")
+            .match_query(price_query("solana,bitcoin,tether", "eur"))
+            .with_body(r#"{"bitcoin":{"eur":50000},"solana":{"eur":150}}"#)
+            .create_async()
+            .await;
+
+        let rates = stub(&server)
+            .rates(&["SOL", "BTC", "USDT"], "EUR")
+            .await
+            .unwrap();
+        let rates: Vec<_> = rates
+            .iter()
+            .map(|(coin, quote)| (coin.as_str(), quote.rate.to_string()))
+            .collect();
+        assert_eq!(rates, [("SOL", "150".into()), ("BTC", "50000".into())]);
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_unsupported_currency_is_not_found() {
+        let mut server = Server::new_async().await;
+        server
+            .mock("GET", "/simple/price")
+            .match_query(Matcher::Any)
+            .with_body(r#"{"bitcoin":{}}"#)
+            .create_async()
+            .await;
+
+        let provider = stub(&server);
+        assert!(matches!(
+            provider.rate("BTC", "MNT").await,
+            Err(ServiceError::NotFound { .. })
+        ));
+        assert!(!provider.covers("USD", "EUR"));
+    }
+
+    #[tokio::test]
+    async fn test_history_keeps_daily_close() {
+        let mut server = Server::new_async().await;
+        // 2025-01-30 00:00, 2025-01-30 23:00 and 2025-01-31 12:00 UTC.
+        server
+            .mock("GET", "/coins/ethereum/market_chart/range")
+            .match_query(Matcher::UrlEncoded("vs_currency".into(), "usd".into()))
+            .with_body(
+                r#"{"prices":[[1738195200000,3100.0],[1738278000000,3200.0],[1738324800000,3300.5]]}"#,
+            )
+            .create_async()
+            .await;
+
+        let start = NaiveDate::from_ymd_opt(2025, 1, 30).unwrap();
+        let end = NaiveDate::from_ymd_opt(2025, 1, 31).unwrap();
+        let points = stub(&server)
+            .history("ETH", "USD", start, end)
+            .await
+            .unwrap();
+        let rates: Vec<_> = points.iter().map(|p| p.rate.to_string()).collect();
+        assert_eq!(rates, ["3200", "3300.5"]);
+    }
+}
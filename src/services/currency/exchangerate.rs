@@ -0,0 +1,102 @@
+// src/services/currency/exchangerate.rs
+
+//! [exchangerate.host](https://exchangerate.host) — paid plans, token required.
+
+use super::{CurrencyProvider, DailyRate, Quote, decimal};
+use crate::services::models::{ErrorData, ExchangerateResponse, TimeframeResponse};
+use crate::services::{ServiceError, fetch_json};
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, Utc};
+use reqwest::Client;
+
+/// Error code exchangerate.host returns once the monthly allowance is spent.
+const USAGE_LIMIT_REACHED: i32 = 104;
+
+pub struct ExchangerateHost {
+    client: Client,
+    token: String,
+}
+
+impl ExchangerateHost {
+    pub fn new(client: Client, token: String) -> Self {
+        Self { client, token }
+    }
+
+    /// Maps an unsuccessful response to a quota or API error.
+    fn failure(&self, url: &str, error: Option<&ErrorData>) -> ServiceError {
+        match error {
+            Some(e) if e.code == USAGE_LIMIT_REACHED => ServiceError::quota_exhausted(self.name()),
+            e => ServiceError::api_error(url, e.map(|e| e.info.as_str())),
+        }
+    }
+}
+
+#[async_trait]
+impl CurrencyProvider for ExchangerateHost {
+    fn id(&self) -> &'static str {
+        "exchangerate"
+    }
+
+    fn name(&self) -> &'static str {
+        "exchangerate.host"
+    }
+
+    async fn rate(&self, from: &str, to: &str) -> Result<Quote, ServiceError> {
+        let url = format!(
+            "https://api.exchangerate.host/convert?access_key={}&from={}&to={}&amount=1",
+            self.token, from, to
+        );
+
+        let data: ExchangerateResponse = fetch_json(&self.client, &url).await?;
+
+        if !data.success {
+            return Err(self.failure(&url, data.error.as_ref()));
+        }
+
+        let info = data.info.as_ref();
+        let rate = info
+            .and_then(|i| i.quote)
+            .or(data.result)
+            .and_then(decimal)
+            .ok_or_else(|| ServiceError::missing_field("info.quote", &url))?;
+
+        Ok(Quote {
+            rate,
+            published_at: info
+                .and_then(|i| i.timestamp)
+                .and_then(|ts| DateTime::from_timestamp(ts, 0))
+                .unwrap_or_else(Utc::now),
+            change_24h: None,
+        })
+    }
+
+    async fn history(
+        &self,
+        from: &str,
+        to: &str,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<Vec<DailyRate>, ServiceError> {
+        let url = format!(
+            "https://api.exchangerate.host/timeframe?access_key={}&start_date={}&end_date={}&source={}&currencies={}",
+            self.token, start, end, from, to
+        );
+
+        let data: TimeframeResponse = fetch_json(&self.client, &url).await?;
+
+        if !data.success {
+            return Err(self.failure(&url, data.error.as_ref()));
+        }
+
+        // Quotes are keyed by the concatenated pair, e.g. `USDEUR`.
+        let key = format!("{}{}", from, to);
+        Ok(data
+            .quotes
+            .into_iter()
+            .filter_map(|(date, quotes)| {
+                let rate = quotes.get(&key).copied().and_then(decimal)?;
+                Some(DailyRate { date, rate })
+            })
+            .collect())
+    }
+}
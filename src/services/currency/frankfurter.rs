@@ -0,0 +1,116 @@
+// src/services/currency/frankfurter.rs
+
+//! [Frankfurter](https://frankfurter.dev) — free, keyless reference rates
+//! published daily by the European Central Bank (about 30 currencies).
+
+use super::{CurrencyProvider, DailyRate, Quote, decimal};
+use crate::services::ServiceError;
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use reqwest::{Client, StatusCode};
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use std::collections::{BTreeMap, HashMap};
+
+const BASE_URL: &str = "https://api.frankfurter.dev/v1";
+
+/// `/latest` and `/<date>` response body.
+#[derive(Debug, Deserialize)]
+struct RatesResponse {
+    date: NaiveDate,
+    rates: HashMap<String, f64>,
+}
+
+/// `/<start>..<end>` response body.
+#[derive(Debug, Deserialize)]
+struct SeriesResponse {
+    rates: BTreeMap<NaiveDate, HashMap<String, f64>>,
+}
+
+pub struct Frankfurter {
+    client: Client,
+}
+
+impl Frankfurter {
+    pub fn new(client: Client) -> Self {
+        Self { client }
+    }
+
+    /// GETs `url`, mapping the API's 404/422 for unsupported currencies
+    /// to `ServiceError::NotFound`.
+    async fn get<T: DeserializeOwned>(&self, url: &str, pair: &str) -> Result<T, ServiceError> {
+        let response = self
+            .client
+            .get(url)
+            .send()
+            .await
+            .map_err(|e| ServiceError::network(url, e))?;
+
+        match response.status() {
+            StatusCode::OK => response
+                .json::<T>()
+                .await
+                .map_err(|e| ServiceError::parse(url, e)),
+            StatusCode::NOT_FOUND | StatusCode::UNPROCESSABLE_ENTITY => {
+                Err(ServiceError::not_found("Currency pair", pair))
+            }
+            status => Err(ServiceError::unexpected_status(url, status)),
+        }
+    }
+}
+
+#[async_trait]
+impl CurrencyProvider for Frankfurter {
+    fn id(&self) -> &'static str {
+        "frankfurter"
+    }
+
+    fn name(&self) -> &'static str {
+        "Frankfurter (ECB)"
+    }
+
+    async fn rate(&self, from: &str, to: &str) -> Result<Quote, ServiceError> {
+        let pair = format!("{}/{}", from, to);
+        let url = format!("{}/latest?base={}&symbols={}", BASE_URL, from, to);
+        let data: RatesResponse = self.get(&url, &pair).await?;
+
+        let rate = data
+            .rates
+            .get(to)
+            .copied()
+            .and_then(decimal)
+            .ok_or_else(|| ServiceError::not_found("Currency pair", &pair))?;
+
+        Ok(Quote {
+            rate,
+            published_at: data.date.and_time(Default::default()).and_utc(),
+            change_24h: None,
+        })
+    }
+
+    async fn history(
+        &self,
+        from: &str,
+        to: &str,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<Vec<DailyRate>, ServiceError> {
+        let pair = format!("{}/{}", from, to);
+        let url = format!(
+            "{}/{}..{}?base={}&symbols={}",
+            BASE_URL, start, end, from, to
+        );
+        let data: SeriesResponse = self.get(&url, &pair).await?;
+
+        // The series may start on the last business day before `start`.
+        Ok(data
+            .rates
+            .into_iter()
+            .filter(|(date, _)| (start..=end).contains(date))
+            .filter_map(|(date, rates)| {
+                let rate = rates.get(to).copied().and_then(decimal)?;
+                Some(DailyRate { date, rate })
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,657 @@
+// src/services/currency/mod.rs
+
+//! Exchange-rate providers and the fallback chain that picks between them.
+//!
+//! Each [`CurrencyProvider`] answers "how many `to` per one `from`". The
+//! [`ProviderChain`] asks them in the operator's order (`FX_PROVIDERS`),
+//! tracking a [`Health`] state per provider so a failing or exhausted
+//! source is skipped for a while instead of slowing every request down.
+//! Crypto assets are priced by CoinGecko; fiat-only providers are never
+//! asked about them.
+
+pub mod catalogue;
+mod coingecko;
+mod exchangerate;
+mod frankfurter;
+mod static_rates;
+
+pub use coingecko::CoinGecko;
+pub use exchangerate::ExchangerateHost;
+pub use frankfurter::Frankfurter;
+pub use static_rates::StaticRates;
+
+use super::ServiceError;
+use super::quota::{Budget, QuotaMeter};
+use async_trait::async_trait;
+use catalogue::is_crypto;
+use chrono::{DateTime, NaiveDate, Utc};
+use reqwest::Client;
+use rust_decimal::Decimal;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// First retry delay after a provider fails; doubled per consecutive failure.
+const BASE_BACKOFF: Duration = Duration::from_secs(30);
+/// Longest a failing provider is skipped.
+const MAX_BACKOFF: Duration = Duration::from_secs(30 * 60);
+/// How long a provider that reported an exhausted quota is skipped.
+const QUOTA_BACKOFF: Duration = Duration::from_secs(6 * 60 * 60);
+
+/// A rate for one currency pair.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Quote {
+    /// Units of `to` per unit of `from`.
+    pub rate: Decimal,
+    /// When the provider published the rate.
+    pub published_at: DateTime<Utc>,
+    /// Percentage change over the last 24 hours, for providers that report it.
+    pub change_24h: Option<Decimal>,
+}
+
+impl Quote {
+    /// The rate in the opposite direction (`to` per `from` becomes `from`
+    /// per `to`).
+    pub fn inverse(&self) -> Self {
+        Self {
+            rate: Decimal::ONE / self.rate,
+            published_at: self.published_at,
+            change_24h: self
+                .change_24h
+                .and_then(|c| (-c * Decimal::ONE_HUNDRED).checked_div(Decimal::ONE_HUNDRED + c)),
+        }
+    }
+
+    /// The cross rate between two quotes against the same currency, e.g.
+    /// BTC/ETH from BTC/USD and ETH/USD.
+    pub fn cross(&self, other: &Self) -> Self {
+        let change = self.change_24h.zip(other.change_24h).and_then(|(a, b)| {
+            let ratio = (Decimal::ONE_HUNDRED + a).checked_div(Decimal::ONE_HUNDRED + b)?;
+            Some((ratio - Decimal::ONE) * Decimal::ONE_HUNDRED)
+        });
+        Self {
+            rate: self.rate / other.rate,
+            published_at: self.published_at.min(other.published_at),
+            change_24h: change,
+        }
+    }
+}
+
+/// The closing rate of one day.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DailyRate {
+    pub date: NaiveDate,
+    /// Units of `to` per unit of `from`.
+    pub rate: Decimal,
+}
+
+type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// A source of exchange rates.
+#[async_trait]
+pub trait CurrencyProvider: Send + Sync {
+    /// Stable identifier used in `FX_PROVIDERS` and `API_QUOTAS`.
+    fn id(&self) -> &'static str;
+
+    /// Human-readable source name shown next to converted amounts.
+    fn name(&self) -> &'static str;
+
+    /// Whether calls count against an API quota. Local sources are free.
+    fn metered(&self) -> bool {
+        true
+    }
+
+    /// Whether the provider can price the pair at all. Pairs it can't are
+    /// skipped without spending a call; by default that is any crypto asset.
+    fn covers(&self, from: &str, to: &str) -> bool {
+        !is_crypto(from) && !is_crypto(to)
+    }
+
+    /// Current rate from `from` to `to` (uppercase ISO codes).
+    ///
+    /// # Errors
+    ///
+    /// `ServiceError::NotFound` when the provider doesn't cover the pair,
+    /// `ServiceError::QuotaExhausted` when its plan is used up, or any
+    /// transport error.
+    async fn rate(&self, from: &str, to: &str) -> Result<Quote, ServiceError>;
+
+    /// Current rates from each of `bases` to `to` in one request, for
+    /// providers that can batch them; pairs without a rate are left out.
+    ///
+    /// # Errors
+    ///
+    /// As for [`CurrencyProvider::rate`]. Providers that can't batch answer
+    /// `ServiceError::NotFound`, the default, so callers ask pair by pair.
+    async fn rates(&self, bases: &[&str], to: &str) -> Result<Vec<(String, Quote)>, ServiceError> {
+        Err(ServiceError::not_found(
+            "Batched rates",
+            &format!("{} to {} from {}", bases.join(","), to, self.name()),
+        ))
+    }
+
+    /// Daily rates from `from` to `to` for the days in `start..=end` on
+    /// which the provider published one (weekends and holidays are absent).
+    ///
+    /// # Errors
+    ///
+    /// As for [`CurrencyProvider::rate`]. Providers without history answer
+    /// `ServiceError::NotFound`, the default.
+    async fn history(
+        &self,
+        from: &str,
+        to: &str,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<Vec<DailyRate>, ServiceError> {
+        let _ = (start, end);
+        Err(ServiceError::not_found(
+            "Rate history",
+            &format!("{}/{} from {}", from, to, self.name()),
+        ))
+    }
+}
+
+/// Whether a provider is currently worth asking.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Health {
+    Healthy,
+    /// Recent consecutive failures; skipped until `retry_at`.
+    Failing {
+        failures: u32,
+        retry_at: Instant,
+    },
+    /// The provider reported its quota as used up; skipped until `retry_at`.
+    Exhausted {
+        retry_at: Instant,
+    },
+}
+
+impl Health {
+    fn is_available(&self, now: Instant) -> bool {
+        match *self {
+            Self::Healthy => true,
+            Self::Failing { retry_at, .. } | Self::Exhausted { retry_at } => now >= retry_at,
+        }
+    }
+
+    /// The state after `err`, or `None` when the error says nothing about
+    /// the provider's health (e.g. an unsupported currency).
+    fn after_error(&self, err: &ServiceError, now: Instant) -> Option<Self> {
+        match err {
+            ServiceError::NotFound { .. } => None,
+            ServiceError::QuotaExhausted { .. } => Some(Self::Exhausted {
+                retry_at: now + QUOTA_BACKOFF,
+            }),
+            _ => {
+                let failures = match *self {
+                    Self::Failing { failures, .. } => failures + 1,
+                    _ => 1,
+                };
+                let backoff = BASE_BACKOFF
+                    .saturating_mul(2u32.saturating_pow(failures - 1))
+                    .min(MAX_BACKOFF);
+                Some(Self::Failing {
+                    failures,
+                    retry_at: now + backoff,
+                })
+            }
+        }
+    }
+}
+
+/// Providers that can be named in `FX_PROVIDERS`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProviderKind {
+    Exchangerate,
+    Frankfurter,
+    CoinGecko,
+    Static,
+}
+
+impl ProviderKind {
+    fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "exchangerate" | "exchangerate.host" => Some(Self::Exchangerate),
+            "frankfurter" | "ecb" => Some(Self::Frankfurter),
+            "coingecko" | "crypto" => Some(Self::CoinGecko),
+            "static" | "file" => Some(Self::Static),
+            _ => None,
+        }
+    }
+}
+
+struct Entry {
+    provider: Box<dyn CurrencyProvider>,
+    health: Mutex<Health>,
+}
+
+/// Ordered providers with per-provider health tracking.
+pub struct ProviderChain {
+    entries: Vec<Entry>,
+}
+
+impl ProviderChain {
+    pub fn new(providers: Vec<Box<dyn CurrencyProvider>>) -> Self {
+        Self {
+            entries: providers
+                .into_iter()
+                .map(|provider| Entry {
+                    provider,
+                    health: Mutex::new(Health::Healthy),
+                })
+                .collect(),
+        }
+    }
+
+    /// Builds the chain from `FX_PROVIDERS` (comma-separated, in priority
+    /// order), `EXCHANGERATE_TOKEN`, `COINGECKO_API_KEY` and `FX_RATES_FILE`.
+    ///
+    /// Without `FX_PROVIDERS`, exchangerate.host is used when a token is set,
+    /// followed by Frankfurter, CoinGecko and, when configured, the static
+    /// rates file. Providers that lack their token or file are skipped with a
+    /// warning.
+    pub fn from_env(client: &Client) -> Self {
+        let token = std::env::var("EXCHANGERATE_TOKEN").ok();
+        let coingecko_key = std::env::var("COINGECKO_API_KEY").ok();
+        let rates_file = std::env::var("FX_RATES_FILE").ok();
+
+        let kinds: Vec<ProviderKind> = match std::env::var("FX_PROVIDERS") {
+            Ok(list) => list
+                .split(',')
+                .filter(|name| !name.trim().is_empty())
+                .filter_map(|name| {
+                    let kind = ProviderKind::parse(name);
+                    if kind.is_none() {
+                        log::warn!("Ignoring unknown FX_PROVIDERS entry: {}", name);
+                    }
+                    kind
+                })
+                .collect(),
+            Err(_) => [
+                token.is_some().then_some(ProviderKind::Exchangerate),
+                Some(ProviderKind::Frankfurter),
+                Some(ProviderKind::CoinGecko),
+                rates_file.is_some().then_some(ProviderKind::Static),
+            ]
+            .into_iter()
+            .flatten()
+            .collect(),
+        };
+
+        let mut providers: Vec<Box<dyn CurrencyProvider>> = Vec::new();
+        for kind in kinds {
+            match kind {
+                ProviderKind::Exchangerate => match &token {
+                    Some(token) => providers.push(Box::new(ExchangerateHost::new(
+                        client.clone(),
+                        token.clone(),
+                    ))),
+                    None => log::warn!("exchangerate.host needs EXCHANGERATE_TOKEN; skipping"),
+                },
+                ProviderKind::Frankfurter => {
+                    providers.push(Box::new(Frankfurter::new(client.clone())))
+                }
+                ProviderKind::CoinGecko => providers.push(Box::new(CoinGecko::new(
+                    client.clone(),
+                    coingecko_key.clone(),
+                ))),
+                ProviderKind::Static => match &rates_file {
+                    Some(path) => match StaticRates::load(path) {
+                        Ok(rates) => providers.push(Box::new(rates)),
+                        Err(err) => log::warn!("Cannot load FX_RATES_FILE {}: {}", path, err),
+                    },
+                    None => log::warn!("Static rates need FX_RATES_FILE; skipping"),
+                },
+            }
+        }
+
+        let names: Vec<_> = providers.iter().map(|p| p.name()).collect();
+        log::info!("Currency providers: {}", names.join(" → "));
+        Self::new(providers)
+    }
+
+    /// Asks each available provider in order until one returns a rate.
+    ///
+    /// Providers past their hard limit in `quota` are skipped, and every
+    /// call to a metered provider is counted. Returns the quote together
+    /// with the name of the provider that supplied it.
+    ///
+    /// # Errors
+    ///
+    /// `ServiceError::NotFound` if every provider asked lacks the pair,
+    /// otherwise the last provider error, or `ServiceError::NoProvider`
+    /// when none is available.
+    pub async fn rate(
+        &self,
+        from: &str,
+        to: &str,
+        quota: &QuotaMeter,
+    ) -> Result<(Quote, &'static str), ServiceError> {
+        let label = format!("{}/{}", from, to);
+        self.ask(quota, |p| p.covers(from, to), &label, |p| p.rate(from, to))
+            .await
+    }
+
+    /// Rates from each of `bases` to `to` from the first provider that
+    /// batches them, e.g. CoinGecko for several coins. Quota and health are
+    /// handled as in [`Self::rate`].
+    ///
+    /// # Errors
+    ///
+    /// As for [`Self::rate`]; `ServiceError::NotFound` also means no
+    /// provider can batch these pairs.
+    pub async fn rates(
+        &self,
+        bases: &[&str],
+        to: &str,
+        quota: &QuotaMeter,
+    ) -> Result<(Vec<(String, Quote)>, &'static str), ServiceError> {
+        let label = format!("{}/{}", bases.join(","), to);
+        self.ask(
+            quota,
+            |p| bases.iter().all(|base| p.covers(base, to)),
+            &label,
+            |p| p.rates(bases, to),
+        )
+        .await
+    }
+
+    /// Daily rates between `start` and `end`, from the first provider that
+    /// keeps history. Quota and health are handled as in [`Self::rate`].
+    ///
+    /// # Errors
+    ///
+    /// As for [`Self::rate`].
+    pub async fn history(
+        &self,
+        from: &str,
+        to: &str,
+        start: NaiveDate,
+        end: NaiveDate,
+        quota: &QuotaMeter,
+    ) -> Result<(Vec<DailyRate>, &'static str), ServiceError> {
+        let label = format!("{}/{} {}..{}", from, to, start, end);
+        self.ask(
+            quota,
+            |p| p.covers(from, to),
+            &label,
+            |p| p.history(from, to, start, end),
+        )
+        .await
+    }
+
+    /// Runs `call` against each available provider that `covers` the
+    /// request until one succeeds.
+    async fn ask<'a, T>(
+        &'a self,
+        quota: &QuotaMeter,
+        covers: impl Fn(&dyn CurrencyProvider) -> bool,
+        label: &str,
+        call: impl Fn(&'a dyn CurrencyProvider) -> BoxFuture<'a, Result<T, ServiceError>>,
+    ) -> Result<(T, &'static str), ServiceError> {
+        let mut last_error = None;
+        let mut not_found = None;
+
+        for entry in &self.entries {
+            let provider = entry.provider.as_ref();
+            if !covers(provider) {
+                continue;
+            }
+            let health = *entry.health.lock().expect("health lock poisoned");
+            if !health.is_available(Instant::now()) {
+                continue;
+            }
+            if provider.metered() && !quota.reserve(provider.id(), provider.name()).await {
+                last_error = Some(ServiceError::quota_exhausted(provider.name()));
+                continue;
+            }
+
+            let result = call(provider).await;
+
+            match result {
+                Ok(answer) => {
+                    *entry.health.lock().expect("health lock poisoned") = Health::Healthy;
+                    return Ok((answer, provider.name()));
+                }
+                Err(err) => {
+                    log::warn!("{} failed for {}: {}", provider.name(), label, err);
+                    let mut health = entry.health.lock().expect("health lock poisoned");
+                    if let Some(next) = health.after_error(&err, Instant::now()) {
+                        *health = next;
+                    }
+                    match err {
+                        ServiceError::NotFound { .. } => not_found = Some(err),
+                        err => last_error = Some(err),
+                    }
+                }
+            }
+        }
+
+        Err(not_found.or(last_error).unwrap_or(ServiceError::NoProvider))
+    }
+
+    /// Whether the provider that would serve the pair is past its soft
+    /// limit, in which case callers should prefer older cached rates over
+    /// new calls. Providers past their hard limit are skipped, as in
+    /// [`Self::rate`], so the one after them decides.
+    pub async fn conserving(&self, from: &str, to: &str, quota: &QuotaMeter) -> bool {
+        for entry in &self.entries {
+            let provider = entry.provider.as_ref();
+            let health = *entry.health.lock().expect("health lock poisoned");
+            if !provider.covers(from, to) || !health.is_available(Instant::now()) {
+                continue;
+            }
+            if !provider.metered() {
+                return false;
+            }
+            match quota.budget(provider.id()).await {
+                Budget::Available => return false,
+                Budget::Conserve => return true,
+                Budget::Exhausted => continue,
+            }
+        }
+        false
+    }
+
+    /// Each provider with its current health, in chain order.
+    pub fn providers(&self) -> Vec<(&dyn CurrencyProvider, Health)> {
+        self.entries
+            .iter()
+            .map(|e| {
+                let health = *e.health.lock().expect("health lock poisoned");
+                (e.provider.as_ref(), health)
+            })
+            .collect()
+    }
+}
+
+/// Parses a JSON number from an API into an exact decimal.
+fn decimal(value: f64) -> Option<Decimal> {
+    Decimal::try_from(value).ok().filter(|d| *d > Decimal::ZERO)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::quota::Limits;
+    use crate::storage::Storage;
+    use std::collections::HashMap;
+    use std::str::FromStr;
+    use std::sync::Arc;
+    use tempfile::TempDir;
+    use tokio::sync::mpsc::UnboundedReceiver;
+
+    type Outcome = fn() -> Result<Quote, ServiceError>;
+
+    struct Fixed {
+        name: &'static str,
+        result: Outcome,
+    }
+
+    #[async_trait]
+    impl CurrencyProvider for Fixed {
+        fn id(&self) -> &'static str {
+            self.name
+        }
+
+        fn name(&self) -> &'static str {
+            self.name
+        }
+
+        async fn rate(&self, _from: &str, _to: &str) -> Result<Quote, ServiceError> {
+            (self.result)()
+        }
+    }
+
+    fn quote() -> Result<Quote, ServiceError> {
+        Ok(Quote {
+            rate: Decimal::from_str("0.92").unwrap(),
+            published_at: DateTime::UNIX_EPOCH,
+            change_24h: None,
+        })
+    }
+
+    fn down() -> Result<Quote, ServiceError> {
+        Err(ServiceError::api_error("http://down", None))
+    }
+
+    fn exhausted() -> Result<Quote, ServiceError> {
+        Err(ServiceError::quota_exhausted("paid"))
+    }
+
+    fn chain(providers: &[(&'static str, Outcome)]) -> ProviderChain {
+        ProviderChain::new(
+            providers
+                .iter()
+                .map(|&(name, result)| {
+                    Box::new(Fixed { name, result }) as Box<dyn CurrencyProvider>
+                })
+                .collect(),
+        )
+    }
+
+    fn quota_meter(hard: u32) -> (QuotaMeter, UnboundedReceiver<String>, TempDir) {
+        quota_meter_with(None, Some(hard))
+    }
+
+    fn quota_meter_with(
+        soft: Option<u32>,
+        hard: Option<u32>,
+    ) -> (QuotaMeter, UnboundedReceiver<String>, TempDir) {
+        let dir = tempfile::tempdir().unwrap();
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        let limits = HashMap::from([("paid".to_string(), Limits { soft, hard })]);
+        (
+            QuotaMeter::new(Arc::new(Storage::open(dir.path()).unwrap()), limits, tx),
+            rx,
+            dir,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_falls_back_and_names_source() {
+        let (meter, _, _dir) = quota_meter(100);
+        let chain = chain(&[("paid", exhausted), ("flaky", down), ("backup", quote)]);
+
+        let (quote, source) = chain.rate("USD", "EUR", &meter).await.unwrap();
+        assert_eq!(source, "backup");
+        assert_eq!(quote.rate.to_string(), "0.92");
+
+        let health: Vec<_> = chain
+            .entries
+            .iter()
+            .map(|e| *e.health.lock().unwrap())
+            .collect();
+        assert!(matches!(health[0], Health::Exhausted { .. }));
+        assert!(matches!(health[1], Health::Failing { failures: 1, .. }));
+        assert_eq!(health[2], Health::Healthy);
+    }
+
+    #[tokio::test]
+    async fn test_unhealthy_providers_are_skipped() {
+        let (meter, _, _dir) = quota_meter(100);
+        let chain = chain(&[("flaky", down)]);
+        assert!(matches!(
+            chain.rate("USD", "EUR", &meter).await,
+            Err(ServiceError::ApiError { .. })
+        ));
+        assert!(matches!(
+            chain.rate("USD", "EUR", &meter).await,
+            Err(ServiceError::NoProvider)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_hard_limit_switches_provider() {
+        let (meter, mut notices, _dir) = quota_meter(1);
+        let chain = chain(&[("paid", quote), ("free", quote)]);
+
+        assert_eq!(chain.rate("USD", "EUR", &meter).await.unwrap().1, "paid");
+        assert!(notices.try_recv().unwrap().contains("hard limit"));
+        assert_eq!(chain.rate("USD", "EUR", &meter).await.unwrap().1, "free");
+    }
+
+    #[tokio::test]
+    async fn test_fiat_providers_skip_crypto() {
+        let (meter, _, _dir) = quota_meter(1);
+        let chain = chain(&[("paid", quote)]);
+
+        assert!(matches!(
+            chain.rate("BTC", "USD", &meter).await,
+            Err(ServiceError::NoProvider)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_conserving_follows_the_serving_provider() {
+        let (meter, _, _dir) = quota_meter_with(Some(1), Some(10));
+        let paid_first = chain(&[("paid", quote), ("free", quote)]);
+        let free_first = chain(&[("free", quote), ("paid", quote)]);
+        assert!(!paid_first.conserving("USD", "EUR", &meter).await);
+
+        assert!(meter.reserve("paid", "paid").await);
+        assert!(paid_first.conserving("USD", "EUR", &meter).await);
+        // Pairs the paid provider doesn't serve keep their usual freshness.
+        assert!(!free_first.conserving("USD", "EUR", &meter).await);
+        assert!(!paid_first.conserving("BTC", "USD", &meter).await);
+    }
+
+    #[test]
+    fn test_inverse_and_cross_quotes() {
+        let quote = |rate: i64, change: i64| Quote {
+            rate: Decimal::from(rate),
+            published_at: DateTime::UNIX_EPOCH,
+            change_24h: Some(Decimal::from(change)),
+        };
+        let inverse = quote(4, 100).inverse();
+        assert_eq!(inverse.rate, Decimal::new(25, 2));
+        assert_eq!(inverse.change_24h, Some(Decimal::from(-50)));
+
+        let cross = quote(60_000, 20).cross(&quote(3_000, -20));
+        assert_eq!(cross.rate, Decimal::from(20));
+        assert_eq!(cross.change_24h, Some(Decimal::from(50)));
+    }
+
+    #[test]
+    fn test_backoff_grows_and_caps() {
+        let now = Instant::now();
+        let err = ServiceError::api_error("http://down", None);
+        let mut health = Health::Healthy;
+        for _ in 0..10 {
+            health = health.after_error(&err, now).unwrap();
+        }
+        assert_eq!(
+            health,
+            Health::Failing {
+                failures: 10,
+                retry_at: now + MAX_BACKOFF
+            }
+        );
+        assert_eq!(
+            health.after_error(&ServiceError::not_found("Currency", "XYZ"), now),
+            None
+        );
+    }
+}
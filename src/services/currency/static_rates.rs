@@ -0,0 +1,94 @@
+// src/services/currency/static_rates.rs
+
+//! Rates read once from a local JSON file, for offline use or as a last
+//! resort. The file uses Frankfurter's shape:
+//!
+//! ```json
+//! { "base": "EUR", "date": "2025-01-31", "rates": { "USD": 1.0393, "JPY": 160.31 } }
+//! ```
+
+use super::{CurrencyProvider, Quote};
+use crate::services::ServiceError;
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::Path;
+
+#[derive(Debug, Deserialize)]
+pub struct StaticRates {
+    base: String,
+    date: NaiveDate,
+    /// Units of each currency per one `base`.
+    rates: HashMap<String, Decimal>,
+}
+
+impl StaticRates {
+    /// Reads and validates a rates file.
+    pub fn load(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        let raw = std::fs::read_to_string(path)?;
+        serde_json::from_str(&raw).map_err(std::io::Error::other)
+    }
+
+    /// Units of `code` per one `base`.
+    fn per_base(&self, code: &str) -> Option<Decimal> {
+        if code.eq_ignore_ascii_case(&self.base) {
+            return Some(Decimal::ONE);
+        }
+        self.rates.get(code).copied().filter(|r| *r > Decimal::ZERO)
+    }
+}
+
+#[async_trait]
+impl CurrencyProvider for StaticRates {
+    fn id(&self) -> &'static str {
+        "static"
+    }
+
+    fn name(&self) -> &'static str {
+        "static rates file"
+    }
+
+    fn metered(&self) -> bool {
+        false
+    }
+
+    async fn rate(&self, from: &str, to: &str) -> Result<Quote, ServiceError> {
+        let rate = self
+            .per_base(to)
+            .zip(self.per_base(from))
+            .and_then(|(to, from)| to.checked_div(from))
+            .ok_or_else(|| ServiceError::not_found("Currency pair", &format!("{}/{}", from, to)))?;
+
+        Ok(Quote {
+            rate,
+            published_at: self.date.and_time(Default::default()).and_utc(),
+            change_24h: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_cross_rates() {
+        let rates: StaticRates = serde_json::from_str(
+            r#"{ "base": "EUR", "date": "2025-01-31", "rates": { "USD": 1.25, "GBP": 0.8 } }"#,
+        )
+        .unwrap();
+
+        let quote = rates.rate("USD", "GBP").await.unwrap();
+        assert_eq!(quote.rate.to_string(), "0.64");
+        assert_eq!(
+            rates.rate("EUR", "USD").await.unwrap().rate.to_string(),
+            "1.25"
+        );
+        assert!(matches!(
+            rates.rate("USD", "PEN").await,
+            Err(ServiceError::NotFound { .. })
+        ));
+    }
+}
@@ -8,6 +8,9 @@ use std::fmt;
 ///
 /// Replaces string-based error propagation with structured variants
 /// that enable type-safe error handling in command handlers.
+///
+/// Errors are shown to users, so URLs are stored without their query
+/// string, which may carry an API key.
 #[derive(Debug)]
 pub enum ServiceError {
     /// Network transport failure (connection, timeout, DNS).
@@ -17,10 +20,10 @@ pub enum ServiceError {
     Parse { url: String, source: String },
 
     /// Resource not found (404-equivalent).
-    NotFound { resource: String, identifier: String },
-
-    /// Missing required API credentials.
-    MissingToken { token_name: String },
+    NotFound {
+        resource: String,
+        identifier: String,
+    },
 
     /// Upstream API returned an error response.
     ApiError { url: String, message: String },
@@ -30,20 +33,26 @@ pub enum ServiceError {
 
     /// Expected field missing from API response.
     MissingField { field: String, url: String },
+
+    /// The provider's plan allowance is used up.
+    QuotaExhausted { provider: String },
+
+    /// Every configured provider is unavailable.
+    NoProvider,
 }
 
 impl ServiceError {
     pub fn network(url: &str, err: reqwest::Error) -> Self {
         Self::Network {
-            url: url.to_string(),
-            source: err.to_string(),
+            url: redact(url),
+            source: err.without_url().to_string(),
         }
     }
 
     pub fn parse(url: &str, err: reqwest::Error) -> Self {
         Self::Parse {
-            url: url.to_string(),
-            source: err.to_string(),
+            url: redact(url),
+            source: err.without_url().to_string(),
         }
     }
 
@@ -54,22 +63,16 @@ impl ServiceError {
         }
     }
 
-    pub fn missing_token(token_name: &str) -> Self {
-        Self::MissingToken {
-            token_name: token_name.to_string(),
-        }
-    }
-
     pub fn api_error(url: &str, message: Option<&str>) -> Self {
         Self::ApiError {
-            url: url.to_string(),
+            url: redact(url),
             message: message.unwrap_or("Unknown API error").to_string(),
         }
     }
 
     pub fn unexpected_status(url: &str, status: reqwest::StatusCode) -> Self {
         Self::UnexpectedStatus {
-            url: url.to_string(),
+            url: redact(url),
             status: status.as_u16(),
         }
     }
@@ -77,9 +80,22 @@ impl ServiceError {
     pub fn missing_field(field: &str, url: &str) -> Self {
         Self::MissingField {
             field: field.to_string(),
-            url: url.to_string(),
+            url: redact(url),
         }
     }
+
+    pub fn quota_exhausted(provider: &str) -> Self {
+        Self::QuotaExhausted {
+            provider: provider.to_string(),
+        }
+    }
+}
+
+/// `url` without its query string.
+fn redact(url: &str) -> String {
+    url.split_once('?')
+        .map_or(url, |(path, _)| path)
+        .to_string()
 }
 
 impl fmt::Display for ServiceError {
@@ -87,12 +103,12 @@ impl fmt::Display for ServiceError {
         match self {
             Self::Network { url, source } => write!(f, "Network error for {}: {}", url, source),
             Self::Parse { url, source } => write!(f, "Parse error for {}: {}", url, source),
-            Self::NotFound { resource, identifier } => {
+            Self::NotFound {
+                resource,
+                identifier,
+            } => {
                 write!(f, "{} not found: {}", resource, identifier)
             }
-            Self::MissingToken { token_name } => {
-                write!(f, "Missing required token: {}", token_name)
-            }
             Self::ApiError { url, message } => write!(f, "API error from {}: {}", url, message),
             Self::UnexpectedStatus { url, status } => {
                 write!(f, "Unexpected status {} from {}", status, url)
@@ -100,8 +116,27 @@ impl fmt::Display for ServiceError {
             Self::MissingField { field, url } => {
                 write!(f, "Missing field '{}' in response from {}", field, url)
             }
+            Self::QuotaExhausted { provider } => write!(f, "{} quota exhausted", provider),
+            Self::NoProvider => write!(f, "No provider is available right now"),
         }
     }
 }
 
-impl std::error::Error for ServiceError {}
\ No newline at end of file
+impl std::error::Error for ServiceError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_urls_are_redacted() {
+        let url = "https://api.exchangerate.host/convert?access_key=secret&from=USD";
+        let err = ServiceError::api_error(url, Some("Invalid currency"));
+        assert_eq!(
+            err.to_string(),
+            "API error from https://api.exchangerate.host/convert: Invalid currency"
+        );
+        let err = ServiceError::missing_field("info.quote", url);
+        assert!(!err.to_string().contains("secret"));
+    }
+}
@@ -0,0 +1,160 @@
+// src/services/joke.rs
+
+//! JokeAPI request options: category, language and content filters.
+
+use crate::storage::{ChatJokePrefs, JokeFlag};
+
+const JOKE_URL: &str = "https://v2.jokeapi.dev/joke";
+
+/// JokeAPI error code for filters no joke matches.
+pub(crate) const NO_MATCHING_JOKE: u16 = 106;
+
+/// Languages JokeAPI has jokes in.
+pub const LANGUAGES: &[&str] = &["en", "cs", "de", "es", "fr", "pt"];
+
+/// JokeAPI joke categories.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JokeCategory {
+    Any,
+    Programming,
+    Misc,
+    Pun,
+    Spooky,
+    Christmas,
+    Dark,
+}
+
+impl JokeCategory {
+    pub const ALL: [Self; 7] = [
+        Self::Any,
+        Self::Programming,
+        Self::Misc,
+        Self::Pun,
+        Self::Spooky,
+        Self::Christmas,
+        Self::Dark,
+    ];
+
+    /// Lowercase names as typed after `/joke`, in [`Self::ALL`] order.
+    pub const KEYS: &[&str] = &[
+        "any",
+        "programming",
+        "misc",
+        "pun",
+        "spooky",
+        "christmas",
+        "dark",
+    ];
+
+    /// The lowercase name used in commands and callback data.
+    pub fn key(self) -> &'static str {
+        Self::KEYS[self as usize]
+    }
+
+    /// The name JokeAPI knows the category by.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Any => "Any",
+            Self::Programming => "Programming",
+            Self::Misc => "Misc",
+            Self::Pun => "Pun",
+            Self::Spooky => "Spooky",
+            Self::Christmas => "Christmas",
+            Self::Dark => "Dark",
+        }
+    }
+
+    pub fn emoji(self) -> &'static str {
+        match self {
+            Self::Any => "🎲",
+            Self::Programming => "💻",
+            Self::Misc => "🃏",
+            Self::Pun => "🥁",
+            Self::Spooky => "👻",
+            Self::Christmas => "🎄",
+            Self::Dark => "🖤",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|category| category.key().eq_ignore_ascii_case(name.trim()))
+    }
+
+    /// Whether JokeAPI never serves the category in safe mode.
+    pub fn is_unsafe(self) -> bool {
+        self == Self::Dark
+    }
+}
+
+/// What kind of joke to ask JokeAPI for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JokeRequest {
+    pub category: JokeCategory,
+    pub language: String,
+    pub blacklist: Vec<JokeFlag>,
+    pub safe_mode: bool,
+}
+
+impl Default for JokeRequest {
+    /// Any safe joke in English.
+    fn default() -> Self {
+        Self::new(JokeCategory::Any, &ChatJokePrefs::default())
+    }
+}
+
+impl JokeRequest {
+    /// A joke from `category` filtered by a chat's settings.
+    pub fn new(category: JokeCategory, prefs: &ChatJokePrefs) -> Self {
+        Self {
+            category,
+            language: prefs.language.clone(),
+            blacklist: prefs.blacklist.iter().copied().collect(),
+            safe_mode: prefs.safe_mode,
+        }
+    }
+
+    pub(crate) fn url(&self) -> String {
+        let mut url = format!(
+            "{}/{}?type=single,twopart&lang={}",
+            JOKE_URL,
+            self.category.label(),
+            self.language
+        );
+        if !self.blacklist.is_empty() {
+            let flags: Vec<_> = self.blacklist.iter().map(|f| f.name()).collect();
+            url.push_str(&format!("&blacklistFlags={}", flags.join(",")));
+        }
+        if self.safe_mode {
+            url.push_str("&safe-mode");
+        }
+        url
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_url() {
+        assert_eq!(
+            JokeRequest::default().url(),
+            "https://v2.jokeapi.dev/joke/Any?type=single,twopart&lang=en&safe-mode"
+        );
+
+        let prefs = ChatJokePrefs {
+            language: "de".to_string(),
+            blacklist: [JokeFlag::Religious, JokeFlag::Nsfw].into(),
+            safe_mode: false,
+        };
+        assert_eq!(
+            JokeRequest::new(JokeCategory::Programming, &prefs).url(),
+            "https://v2.jokeapi.dev/joke/Programming?type=single,twopart&lang=de\
+             &blacklistFlags=nsfw,religious"
+        );
+        assert_eq!(JokeCategory::parse("PUN"), Some(JokeCategory::Pun));
+        assert_eq!(JokeCategory::Christmas.key(), "christmas");
+    }
+}
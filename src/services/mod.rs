@@ -5,138 +5,467 @@
 //! Provides type-safe, ergonomic interfaces to third-party APIs while abstracting
 //! transport concerns from command handlers.
 
+pub mod cache;
+pub mod currency;
 mod error;
+pub mod joke;
 mod models;
+pub mod quota;
+pub mod weather;
 
 pub use error::ServiceError;
-pub use models::{ExchangerateResponse, JokeResponse, WeatherData};
+pub use models::JokeResponse;
+pub use weather::{Condition, CurrentConditions, DailyForecast, HourlyForecast, WeatherData};
 
-use reqwest::{Client, StatusCode};
+use crate::storage::Storage;
+use cache::TtlCache;
+use chrono::{DateTime, NaiveDate, Utc};
+use currency::{DailyRate, Health, ProviderChain, Quote};
+use joke::JokeRequest;
+use quota::{QuotaMeter, Usage};
+use reqwest::Client;
+use rust_decimal::Decimal;
 use serde::Deserialize;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc::UnboundedSender;
+use weather::{Place, WeatherChain};
 
-/// Shared HTTP client and API credentials container.
+/// How long a fetched rate is reused before asking a provider again.
+const RATE_TTL: Duration = Duration::from_secs(60 * 60);
+/// Crypto prices move faster, so they are refreshed more often.
+const CRYPTO_RATE_TTL: Duration = Duration::from_secs(5 * 60);
+/// How old a cached rate may be once the paid provider serving it is past
+/// its soft limit.
+const CONSERVE_RATE_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+/// Past days' rates don't change; they are kept for a week.
+const DAILY_RATE_TTL: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+/// How long a city's weather is reused by `/weather` and `/forecast`.
+const WEATHER_TTL: Duration = Duration::from_secs(10 * 60);
+/// Days searched backwards for a historical rate, to skip weekends and holidays.
+const HISTORY_LOOKBACK_DAYS: u64 = 7;
+
+/// Cached daily rate: `None` records a day without a published rate.
+type DailyEntry = Option<(Decimal, &'static str)>;
+
+/// Result of converting an amount between two currencies.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Conversion {
+    pub amount: Decimal,
+    pub from: String,
+    pub to: String,
+    /// `amount × rate`, unrounded; callers round to the target's minor units.
+    pub result: Decimal,
+    /// Units of `to` per unit of `from`.
+    pub rate: Decimal,
+    /// When the provider published the rate.
+    pub fetched_at: DateTime<Utc>,
+    /// Percentage change of the rate over the last 24 hours, when known.
+    pub change_24h: Option<Decimal>,
+    /// Name of the provider that supplied the rate.
+    pub source: &'static str,
+}
+
+impl Conversion {
+    fn from_quote(
+        amount: Decimal,
+        from: String,
+        to: String,
+        (quote, source): (Quote, &'static str),
+    ) -> Self {
+        Self {
+            amount,
+            result: amount * quote.rate,
+            rate: quote.rate,
+            fetched_at: quote.published_at,
+            change_24h: quote.change_24h,
+            from,
+            to,
+            source,
+        }
+    }
+}
+
+/// Daily rates for a currency pair over a period.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RateHistory {
+    pub from: String,
+    pub to: String,
+    /// Published rates in date order.
+    pub points: Vec<DailyRate>,
+    /// Name of the provider that supplied the rates.
+    pub source: &'static str,
+}
+
+/// A currency provider's health and, when metered, its quota usage.
+#[derive(Debug, Clone)]
+pub struct ProviderStatus {
+    pub name: &'static str,
+    pub health: Health,
+    pub usage: Option<Usage>,
+}
+
+/// Generic HTTP GET with JSON deserialization.
+///
+/// Centralizes request/response error handling with structured logging.
+pub(crate) async fn fetch_json<T>(client: &Client, url: &str) -> Result<T, ServiceError>
+where
+    T: for<'de> Deserialize<'de>,
+{
+    client
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| ServiceError::network(url, e))?
+        .json::<T>()
+        .await
+        .map_err(|e| ServiceError::parse(url, e))
+}
+
+/// Shared HTTP client and external data providers.
 ///
 /// Encapsulates all external API interactions with connection pooling
 /// and timeout management.
 pub struct ApiService {
     client: Client,
-    exchange_token: Option<String>,
+    currency: ProviderChain,
+    quota: QuotaMeter,
+    rates: TtlCache<(String, String), (Quote, &'static str)>,
+    daily: TtlCache<(String, String, NaiveDate), DailyEntry>,
+    weather_providers: WeatherChain,
+    weather: TtlCache<String, WeatherData>,
 }
 
 impl ApiService {
-    /// Creates a new `ApiService` with an optional exchangerate API token.
+    /// Creates an `ApiService` whose currency and weather providers and
+    /// quotas are configured from the environment (see
+    /// [`ProviderChain::from_env`], [`WeatherChain::from_env`] and
+    /// [`QuotaMeter::from_env`]). Usage is persisted in `storage`, and quota
+    /// warnings for admins are sent through `notices`.
     ///
     /// # Panics
     ///
     /// Panics if the HTTP client cannot be constructed (rare - indicates
     /// invalid TLS configuration or system resource exhaustion).
-    pub fn new(exchange_token: Option<String>) -> Self {
+    pub fn from_env(storage: Arc<Storage>, notices: UnboundedSender<String>) -> Self {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .expect("Failed to build HTTP client");
         Self {
-            client: Client::builder()
-                .timeout(std::time::Duration::from_secs(10))
-                .build()
-                .expect("Failed to build HTTP client"),
-            exchange_token,
+            currency: ProviderChain::from_env(&client),
+            weather_providers: WeatherChain::from_env(&client),
+            quota: QuotaMeter::from_env(storage, notices),
+            rates: TtlCache::new(CONSERVE_RATE_TTL),
+            daily: TtlCache::new(DAILY_RATE_TTL),
+            weather: TtlCache::new(WEATHER_TTL),
+            client,
         }
     }
 
-    /// Generic HTTP GET with JSON deserialization.
-    ///
-    /// Centralizes request/response error handling with structured logging.
-    async fn fetch_json<T>(&self, url: &str) -> Result<T, ServiceError>
-    where
-        T: for<'de> Deserialize<'de>,
-    {
-        self.client
-            .get(url)
-            .send()
-            .await
-            .map_err(|e| ServiceError::network(url, e))?
-            .json::<T>()
-            .await
-            .map_err(|e| ServiceError::parse(url, e))
+    /// Retrieves current conditions and the three-day forecast for a city
+    /// from the first weather provider that knows it.
+    ///
+    /// Responses are cached per city for ten minutes, so `/weather` and
+    /// `/forecast` for the same place share one upstream request.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ServiceError::NotFound` if the city doesn't exist or has no
+    /// current observation, or propagates network/parse errors.
+    pub async fn get_weather(&self, city: &str) -> Result<WeatherData, ServiceError> {
+        self.fetch_weather(Place::Name(city)).await
+    }
+
+    /// Like [`Self::get_weather`], for coordinates such as a shared
+    /// Telegram location. They are rounded to about a kilometre so nearby
+    /// requests share a cache entry.
+    ///
+    /// # Errors
+    ///
+    /// As for [`Self::get_weather`].
+    pub async fn get_weather_at(
+        &self,
+        latitude: f64,
+        longitude: f64,
+    ) -> Result<WeatherData, ServiceError> {
+        self.fetch_weather(Place::Coordinates {
+            latitude,
+            longitude,
+        })
+        .await
     }
 
-    /// Retrieves current weather conditions for a given city.
+    /// Cached lookup of a place name or coordinates.
+    async fn fetch_weather(&self, place: Place<'_>) -> Result<WeatherData, ServiceError> {
+        let key = place.to_string().to_lowercase();
+        if let Some(cached) = self.weather.get(&key, WEATHER_TTL) {
+            return Ok(cached);
+        }
+
+        let (data, source) = self.weather_providers.weather(place).await?;
+        log::debug!("Weather for {} from {}", place, source);
+        self.weather.insert(key, data.clone());
+        Ok(data)
+    }
+
+    /// Fetches a random joke from JokeAPI matching `request`.
     ///
     /// # Errors
     ///
-    /// Returns `ServiceError::NotFound` if the city doesn't exist,
-    /// or propagates network/parse errors from `fetch_json`.
-    pub async fn get_weather(&self, city: &str) -> Result<WeatherData, ServiceError> {
-        let url = format!(
-            "https://wttr.in/{}?format=j1",
-            urlencoding::encode(city)
-        );
-
-        let response = self
-            .client
-            .get(&url)
-            .send()
-            .await
-            .map_err(|e| ServiceError::network(&url, e))?;
-
-        match response.status() {
-            StatusCode::NOT_FOUND => Err(ServiceError::not_found("City", city)),
-            StatusCode::OK => response
-                .json::<WeatherData>()
-                .await
-                .map_err(|e| ServiceError::parse(&url, e)),
-            status => Err(ServiceError::unexpected_status(&url, status)),
+    /// Returns `ServiceError::NotFound` if no joke matches the filters,
+    /// `ServiceError::ApiError` for other JokeAPI errors, or propagates
+    /// network or parsing errors.
+    pub async fn get_joke(&self, request: &JokeRequest) -> Result<String, ServiceError> {
+        let url = request.url();
+        let joke = fetch_json::<JokeResponse>(&self.client, &url).await?;
+
+        match joke {
+            JokeResponse::Single { joke } => Ok(joke),
+            JokeResponse::TwoPart { setup, delivery } => Ok(format!("{}\n{}", setup, delivery)),
+            JokeResponse::Error { code, message } => Err(match code {
+                joke::NO_MATCHING_JOKE => ServiceError::not_found("Joke", request.category.label()),
+                _ => ServiceError::api_error(&url, Some(&message)),
+            }),
+        }
+    }
+
+    /// Converts `amount` using the first currency provider that has a rate.
+    ///
+    /// Rates are cached for an hour (five minutes for crypto assets), or up
+    /// to a day once a paid provider is past its soft limit. The result is
+    /// computed locally as `amount × rate` in fixed-point arithmetic, and the
+    /// returned [`Conversion`] names the rate's source.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ServiceError::NotFound` if no provider covers the pair, or
+    /// the last provider error when all of them fail.
+    pub async fn convert_currency(
+        &self,
+        amount: Decimal,
+        from: &str,
+        to: &str,
+    ) -> Result<Conversion, ServiceError> {
+        let (from, to) = (from.to_uppercase(), to.to_uppercase());
+        let rate = self.rate(&from, &to).await?;
+        Ok(Conversion::from_quote(amount, from, to, rate))
+    }
+
+    /// The price of one unit of each of `coins` in `quote`, in order; `None`
+    /// where no provider has one.
+    ///
+    /// Prices missing from the cache are fetched in a single batched call
+    /// where a provider supports it, and pair by pair otherwise.
+    ///
+    /// # Errors
+    ///
+    /// Returns the provider error when nothing could be priced.
+    pub async fn prices(
+        &self,
+        coins: &[String],
+        quote: &str,
+    ) -> Result<Vec<Option<Conversion>>, ServiceError> {
+        let quote = quote.to_uppercase();
+        let mut rates = Vec::with_capacity(coins.len());
+        for coin in coins {
+            let coin = coin.to_uppercase();
+            let max_age = self.rate_ttl(&coin, &quote).await;
+            let cached = self.rates.get(&(coin.clone(), quote.clone()), max_age);
+            rates.push((coin, cached));
         }
+
+        let missing: Vec<&str> = rates
+            .iter()
+            .filter(|(_, rate)| rate.is_none())
+            .map(|(coin, _)| coin.as_str())
+            .collect();
+        if !missing.is_empty() {
+            let fetched = match self.currency.rates(&missing, &quote, &self.quota).await {
+                Ok((fetched, source)) => fetched
+                    .into_iter()
+                    .map(|(coin, rate)| (coin, (rate, source)))
+                    .collect(),
+                // No provider batches these pairs.
+                Err(ServiceError::NotFound { .. } | ServiceError::NoProvider) => {
+                    let mut fetched = Vec::new();
+                    for coin in missing {
+                        match self.rate(coin, &quote).await {
+                            Ok(rate) => fetched.push((coin.to_string(), rate)),
+                            Err(err) => log::warn!("No {}/{} rate: {}", coin, quote, err),
+                        }
+                    }
+                    fetched
+                }
+                Err(err) if rates.iter().all(|(_, rate)| rate.is_none()) => return Err(err),
+                Err(err) => {
+                    log::warn!("Serving cached prices only: {}", err);
+                    Vec::new()
+                }
+            };
+            for (coin, rate) in fetched {
+                self.rates.insert((coin.clone(), quote.clone()), rate.clone());
+                if let Some((_, slot)) = rates.iter_mut().find(|(c, _)| *c == coin) {
+                    *slot = Some(rate);
+                }
+            }
+        }
+
+        Ok(rates
+            .into_iter()
+            .map(|(coin, rate)| {
+                rate.map(|rate| Conversion::from_quote(Decimal::ONE, coin, quote.clone(), rate))
+            })
+            .collect())
     }
 
-    /// Fetches a random joke from JokeAPI.
+    /// Converts `amount` at the rate published on `date`, or the closest
+    /// earlier business day. Today's and future dates use the latest rate.
     ///
     /// # Errors
     ///
-    /// Propagates network or parsing errors as `ServiceError`.
-    pub async fn get_joke(&self) -> Result<String, ServiceError> {
-        const JOKE_URL: &str = "https://v2.jokeapi.dev/joke/Any?safe-mode&type=single,twopart";
+    /// As for [`Self::rate_history`].
+    pub async fn convert_currency_at(
+        &self,
+        amount: Decimal,
+        from: &str,
+        to: &str,
+        date: NaiveDate,
+    ) -> Result<Conversion, ServiceError> {
+        if date >= Utc::now().date_naive() {
+            return self.convert_currency(amount, from, to).await;
+        }
 
-        let joke = self.fetch_json::<JokeResponse>(JOKE_URL).await?;
+        let start = date - chrono::Days::new(HISTORY_LOOKBACK_DAYS);
+        let history = self.rate_history(from, to, start, date).await?;
+        let point = history.points.last().ok_or_else(|| {
+            ServiceError::not_found("Rate", &format!("{}/{} on {}", from, to, date))
+        })?;
 
-        Ok(match joke {
-            JokeResponse::Single { joke } => joke,
-            JokeResponse::TwoPart { setup, delivery } => format!("{}\n{}", setup, delivery),
+        Ok(Conversion {
+            amount,
+            result: amount * point.rate,
+            rate: point.rate,
+            fetched_at: point.date.and_time(Default::default()).and_utc(),
+            change_24h: None,
+            from: history.from,
+            to: history.to,
+            source: history.source,
         })
     }
 
-    /// Converts currency using exchangerate.host API.
+    /// Daily rates for `start..=end`, served from the cache when every day
+    /// in the range is known.
     ///
     /// # Errors
     ///
-    /// Returns `ServiceError::MissingToken` if API key is not configured,
-    /// `ServiceError::Api` for upstream errors, or propagates network/parse errors.
-    pub async fn convert_currency(
+    /// Returns `ServiceError::NotFound` if no provider has history for the
+    /// pair or none was published in the range, otherwise the last
+    /// provider error.
+    pub async fn rate_history(
         &self,
-        amount: f64,
         from: &str,
         to: &str,
-    ) -> Result<f64, ServiceError> {
-        let token = self
-            .exchange_token
-            .as_ref()
-            .ok_or_else(|| ServiceError::missing_token("EXCHANGERATE_TOKEN"))?;
-
-        let url = format!(
-            "https://api.exchangerate.host/convert?access_key={}&from={}&to={}&amount={}",
-            token,
-            from.to_uppercase(),
-            to.to_uppercase(),
-            amount
-        );
-
-        let data = self.fetch_json::<ExchangerateResponse>(&url).await?;
-
-        if data.success {
-            data.result
-                .ok_or_else(|| ServiceError::missing_field("result", &url))
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<RateHistory, ServiceError> {
+        let (from, to) = (from.to_uppercase(), to.to_uppercase());
+        let today = Utc::now().date_naive();
+        let days: Vec<NaiveDate> = start.iter_days().take_while(|d| *d <= end).collect();
+        let key = |day: NaiveDate| (from.clone(), to.clone(), day);
+        // Today's rate may still be published, so it is kept like a live rate.
+        let max_age = |day: NaiveDate| {
+            if day < today {
+                DAILY_RATE_TTL
+            } else {
+                RATE_TTL
+            }
+        };
+
+        let cached: Option<Vec<DailyEntry>> = days
+            .iter()
+            .map(|&day| self.daily.get(&key(day), max_age(day)))
+            .collect();
+
+        let (points, source) = match cached {
+            Some(entries) => {
+                let source = entries.iter().flatten().map(|(_, s)| *s).next();
+                let points = days
+                    .iter()
+                    .zip(entries)
+                    .filter_map(|(&date, entry)| entry.map(|(rate, _)| DailyRate { date, rate }))
+                    .collect();
+                (points, source.unwrap_or("cache"))
+            }
+            None => {
+                let (points, source) = self
+                    .currency
+                    .history(&from, &to, start, end, &self.quota)
+                    .await?;
+                for &day in &days {
+                    let entry = points
+                        .iter()
+                        .find(|p| p.date == day)
+                        .map(|p| (p.rate, source));
+                    self.daily.insert(key(day), entry);
+                }
+                (points, source)
+            }
+        };
+
+        if points.is_empty() {
+            return Err(ServiceError::not_found(
+                "Rates",
+                &format!("{}/{} between {} and {}", from, to, start, end),
+            ));
+        }
+
+        Ok(RateHistory {
+            from,
+            to,
+            points,
+            source,
+        })
+    }
+
+    /// Cached or freshly fetched rate for an uppercase currency pair.
+    async fn rate(&self, from: &str, to: &str) -> Result<(Quote, &'static str), ServiceError> {
+        let key = (from.to_string(), to.to_string());
+        let max_age = self.rate_ttl(from, to).await;
+        if let Some(cached) = self.rates.get(&key, max_age) {
+            return Ok(cached);
+        }
+
+        let fetched = self.currency.rate(from, to, &self.quota).await?;
+        self.rates.insert(key, fetched.clone());
+        Ok(fetched)
+    }
+
+    /// How old a cached rate for the pair may be.
+    async fn rate_ttl(&self, from: &str, to: &str) -> Duration {
+        if self.currency.conserving(from, to, &self.quota).await {
+            CONSERVE_RATE_TTL
+        } else if currency::catalogue::is_crypto(from) || currency::catalogue::is_crypto(to) {
+            CRYPTO_RATE_TTL
         } else {
-            Err(ServiceError::api_error(
-                &url,
-                data.error.as_ref().map(|e| e.info.as_str()),
-            ))
+            RATE_TTL
         }
     }
-}
\ No newline at end of file
+
+    /// Health and current-period usage of every currency provider.
+    pub async fn provider_status(&self) -> Vec<ProviderStatus> {
+        let mut statuses = Vec::new();
+        for (provider, health) in self.currency.providers() {
+            let usage = match provider.metered() {
+                true => Some(self.quota.usage(provider.id()).await),
+                false => None,
+            };
+            statuses.push(ProviderStatus {
+                name: provider.name(),
+                health,
+                usage,
+            });
+        }
+        statuses
+    }
+}
@@ -2,36 +2,26 @@
 
 //! Data transfer objects for third-party API responses.
 
+use chrono::NaiveDate;
 use serde::Deserialize;
-
-/// Top-level weather data from wttr.in API.
-#[derive(Debug, Deserialize, Clone)]
-pub struct WeatherData {
-    pub current_condition: Vec<CurrentCondition>,
-}
-
-/// Current weather snapshot.
-#[derive(Debug, Deserialize, Clone)]
-pub struct CurrentCondition {
-    #[serde(rename = "temp_C")]
-    pub temp_c: String,
-
-    #[serde(rename = "weatherDesc")]
-    pub weather_desc: Vec<WeatherDesc>,
-}
-
-/// Weather description wrapper.
-#[derive(Debug, Deserialize, Clone)]
-pub struct WeatherDesc {
-    pub value: String,
-}
+use std::collections::{BTreeMap, HashMap};
 
 /// JokeAPI response variants.
 #[derive(Debug, Deserialize)]
 #[serde(untagged)]
 pub enum JokeResponse {
-    Single { joke: String },
-    TwoPart { setup: String, delivery: String },
+    Single {
+        joke: String,
+    },
+    TwoPart {
+        setup: String,
+        delivery: String,
+    },
+    /// Sent with `"error": true`, e.g. when no joke matches the filters.
+    Error {
+        code: u16,
+        message: String,
+    },
 }
 
 /// exchangerate.host API response.
@@ -39,6 +29,27 @@ pub enum JokeResponse {
 pub struct ExchangerateResponse {
     pub success: bool,
     pub result: Option<f64>,
+    pub info: Option<ConvertInfo>,
+    pub error: Option<ErrorData>,
+}
+
+/// Rate metadata attached to a successful exchangerate.host conversion.
+#[derive(Debug, Deserialize)]
+pub struct ConvertInfo {
+    /// Unix timestamp of the rate snapshot.
+    pub timestamp: Option<i64>,
+
+    /// Exchange rate applied to the amount.
+    pub quote: Option<f64>,
+}
+
+/// exchangerate.host `/timeframe` response.
+#[derive(Debug, Deserialize)]
+pub struct TimeframeResponse {
+    pub success: bool,
+    /// Rates per day, keyed by concatenated pair (`USDEUR`).
+    #[serde(default)]
+    pub quotes: BTreeMap<NaiveDate, HashMap<String, f64>>,
     pub error: Option<ErrorData>,
 }
 
@@ -46,9 +57,8 @@ pub struct ExchangerateResponse {
 #[derive(Debug, Deserialize)]
 pub struct ErrorData {
     /// Numeric error code from the API.
-    #[allow(dead_code)]
     pub code: i32,
-    
+
     /// Human-readable error description.
     pub info: String,
-}
\ No newline at end of file
+}
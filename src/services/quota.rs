@@ -0,0 +1,300 @@
+// src/services/quota.rs
+
+//! Per-provider call budgets for paid APIs.
+//!
+//! Every call to a metered provider is counted against the current billing
+//! period (a calendar month, UTC) and persisted, so restarts don't reset
+//! the meter. Limits come from `API_QUOTAS`, e.g.
+//! `exchangerate=80/100,frankfurter=/5000`, where each entry is
+//! `<provider>=<soft>/<hard>` and either bound may be left out.
+
+use crate::storage::{PeriodUsage, Storage};
+use chrono::Utc;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::mpsc::UnboundedSender;
+
+/// exchangerate.host's free plan (100 requests a month) and CoinGecko's
+/// demo plan (10,000).
+const DEFAULT_QUOTAS: &str = "exchangerate=80/100,coingecko=8000/10000";
+
+/// Soft and hard call limits for one provider per billing period.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Limits {
+    /// Past this, admins are warned and cached rates are preferred.
+    pub soft: Option<u32>,
+    /// Past this, calls are refused until the next period.
+    pub hard: Option<u32>,
+}
+
+impl Limits {
+    fn parse(spec: &str) -> Option<Self> {
+        let bound = |s: &str| -> Option<Option<u32>> {
+            let s = s.trim();
+            if s.is_empty() {
+                Some(None)
+            } else {
+                s.parse().ok().map(Some)
+            }
+        };
+        match spec.split_once('/') {
+            Some((soft, hard)) => Some(Self {
+                soft: bound(soft)?,
+                hard: bound(hard)?,
+            }),
+            None => Some(Self {
+                soft: None,
+                hard: bound(spec)?,
+            }),
+        }
+    }
+
+    pub fn budget(&self, calls: u32) -> Budget {
+        if self.hard.is_some_and(|hard| calls >= hard) {
+            Budget::Exhausted
+        } else if self.soft.is_some_and(|soft| calls >= soft) {
+            Budget::Conserve
+        } else {
+            Budget::Available
+        }
+    }
+}
+
+/// What a provider's usage allows right now.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Budget {
+    Available,
+    /// Past the soft limit: serve cached data where possible.
+    Conserve,
+    /// Past the hard limit: no calls until the next period.
+    Exhausted,
+}
+
+/// One provider's usage in the current period.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Usage {
+    pub period: String,
+    pub calls: u32,
+    pub limits: Limits,
+}
+
+/// Persistent call counter with limits and admin notices.
+pub struct QuotaMeter {
+    storage: Arc<Storage>,
+    limits: HashMap<String, Limits>,
+    notices: UnboundedSender<String>,
+}
+
+impl QuotaMeter {
+    /// Reads limits from `API_QUOTAS`, defaulting to exchangerate.host's
+    /// free plan. Warnings are sent through `notices`.
+    pub fn from_env(storage: Arc<Storage>, notices: UnboundedSender<String>) -> Self {
+        let spec = std::env::var("API_QUOTAS").unwrap_or_else(|_| DEFAULT_QUOTAS.to_string());
+        Self::new(storage, parse_quotas(&spec), notices)
+    }
+
+    pub fn new(
+        storage: Arc<Storage>,
+        limits: HashMap<String, Limits>,
+        notices: UnboundedSender<String>,
+    ) -> Self {
+        Self {
+            storage,
+            limits,
+            notices,
+        }
+    }
+
+    pub fn limits(&self, provider: &str) -> Limits {
+        self.limits.get(provider).copied().unwrap_or_default()
+    }
+
+    pub async fn usage(&self, provider: &str) -> Usage {
+        let period = current_period();
+        let calls = self
+            .storage
+            .api_usage
+            .read(|usage| usage.calls(provider, &period))
+            .await;
+        Usage {
+            period,
+            calls,
+            limits: self.limits(provider),
+        }
+    }
+
+    pub async fn budget(&self, provider: &str) -> Budget {
+        let usage = self.usage(provider).await;
+        usage.limits.budget(usage.calls)
+    }
+
+    /// Reserves one call to `provider`, or returns `false` once it's past
+    /// its hard limit. The check and the count happen in a single update,
+    /// so concurrent callers can't overshoot the limit together.
+    ///
+    /// Admins are notified once per period when the soft or hard limit is
+    /// reached.
+    pub async fn reserve(&self, provider: &str, name: &str) -> bool {
+        let period = current_period();
+        let limits = self.limits(provider);
+        let mut outcome = (true, None);
+        let saved = self
+            .storage
+            .api_usage
+            .update(|usage| {
+                let usage = usage.current(provider, &period);
+                if limits.budget(usage.calls) == Budget::Exhausted {
+                    outcome = (false, None);
+                    return;
+                }
+                usage.calls += 1;
+                outcome = (true, notice(usage, limits, name));
+            })
+            .await;
+        if let Err(err) = saved {
+            // The reservation still stands in memory.
+            log::error!("Failed to persist API usage for {}: {}", provider, err);
+        }
+
+        let (reserved, notice) = outcome;
+        if let Some(notice) = notice {
+            log::warn!("{}", notice);
+            let _ = self.notices.send(notice);
+        }
+        reserved
+    }
+}
+
+/// The admin notice due now that `usage` grew, marking it as sent.
+fn notice(usage: &mut PeriodUsage, limits: Limits, name: &str) -> Option<String> {
+    let calls = usage.calls;
+    if limits.hard.is_some_and(|hard| calls >= hard) && !usage.hard_notified {
+        usage.hard_notified = true;
+        Some(format!(
+            "⛔ {} reached its hard limit of {} calls for {}. Further calls are refused until next period.",
+            name, calls, usage.period
+        ))
+    } else if limits.soft.is_some_and(|soft| calls >= soft) && !usage.soft_notified {
+        usage.soft_notified = true;
+        Some(format!(
+            "⚠️ {} has used {} calls in {}{}. Cached rates are preferred from now on.",
+            name,
+            calls,
+            usage.period,
+            limits
+                .hard
+                .map(|h| format!(" (hard limit {})", h))
+                .unwrap_or_default()
+        ))
+    } else {
+        None
+    }
+}
+
+/// Billing period label for the current calendar month, e.g. `2025-01`.
+pub fn current_period() -> String {
+    Utc::now().format("%Y-%m").to_string()
+}
+
+/// Parses `provider=soft/hard` entries, skipping malformed ones.
+fn parse_quotas(spec: &str) -> HashMap<String, Limits> {
+    spec.split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .filter_map(|entry| {
+            let parsed = entry.split_once('=').and_then(|(name, limits)| {
+                Some((name.trim().to_lowercase(), Limits::parse(limits)?))
+            });
+            if parsed.is_none() {
+                log::warn!("Ignoring invalid API_QUOTAS entry: {}", entry);
+            }
+            parsed
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+    use tokio::sync::mpsc::UnboundedReceiver;
+
+    #[test]
+    fn test_parse_quotas() {
+        let quotas = parse_quotas("exchangerate=80/100, frankfurter=/5000, crypto=50, bad");
+        assert_eq!(
+            quotas["exchangerate"],
+            Limits {
+                soft: Some(80),
+                hard: Some(100)
+            }
+        );
+        assert_eq!(quotas["frankfurter"].soft, None);
+        assert_eq!(quotas["crypto"].hard, Some(50));
+        assert!(!quotas.contains_key("bad"));
+    }
+
+    #[test]
+    fn test_budget() {
+        let limits = Limits {
+            soft: Some(80),
+            hard: Some(100),
+        };
+        assert_eq!(limits.budget(79), Budget::Available);
+        assert_eq!(limits.budget(80), Budget::Conserve);
+        assert_eq!(limits.budget(100), Budget::Exhausted);
+        assert_eq!(Limits::default().budget(u32::MAX), Budget::Available);
+    }
+
+    fn meter(soft: u32, hard: u32) -> (Arc<QuotaMeter>, UnboundedReceiver<String>, TempDir) {
+        let dir = tempfile::tempdir().unwrap();
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        let limits = Limits {
+            soft: Some(soft),
+            hard: Some(hard),
+        };
+        let meter = QuotaMeter::new(
+            Arc::new(Storage::open(dir.path()).unwrap()),
+            HashMap::from([("paid".to_string(), limits)]),
+            tx,
+        );
+        (Arc::new(meter), rx, dir)
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_reservations_respect_hard_limit() {
+        let (meter, _notices, _dir) = meter(2, 3);
+        let tasks: Vec<_> = (0..10)
+            .map(|_| {
+                let meter = meter.clone();
+                tokio::spawn(async move { meter.reserve("paid", "Paid").await })
+            })
+            .collect();
+        let mut reserved = 0;
+        for task in tasks {
+            reserved += usize::from(task.await.unwrap());
+        }
+        assert_eq!(reserved, 3);
+        assert_eq!(meter.usage("paid").await.calls, 3);
+    }
+
+    #[tokio::test]
+    async fn test_each_notice_is_sent_once() {
+        let (meter, mut notices, _dir) = meter(1, 3);
+        // Usage carried over from before the limits were lowered.
+        let period = current_period();
+        meter
+            .storage
+            .api_usage
+            .update(|u| u.current("paid", &period).calls = 1)
+            .await
+            .unwrap();
+
+        assert!(meter.reserve("paid", "Paid").await);
+        assert!(notices.try_recv().unwrap().contains("has used 2 calls"));
+        assert!(meter.reserve("paid", "Paid").await);
+        assert!(notices.try_recv().unwrap().contains("hard limit of 3"));
+        assert!(!meter.reserve("paid", "Paid").await);
+        assert!(notices.try_recv().is_err());
+    }
+}
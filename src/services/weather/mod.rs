@@ -0,0 +1,233 @@
+// src/services/weather/mod.rs
+
+//! Weather providers and the fallback chain that picks between them.
+//!
+//! Every [`WeatherProvider`] answers with the same [`WeatherData`] report,
+//! whatever its upstream format, so commands never know which source
+//! answered. The [`WeatherChain`] asks them in the operator's order
+//! (`WEATHER_PROVIDERS`) until one has the place.
+
+mod model;
+mod open_meteo;
+mod wttr;
+
+pub use model::{Condition, CurrentConditions, DailyForecast, HourlyForecast, WeatherData};
+pub use open_meteo::OpenMeteo;
+pub use wttr::Wttr;
+
+use super::ServiceError;
+use async_trait::async_trait;
+use reqwest::Client;
+use std::fmt;
+
+/// What a weather report is asked for.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Place<'a> {
+    /// A place name as typed, e.g. `Lima` or `Paris, France`.
+    Name(&'a str),
+    /// A point such as a shared Telegram location.
+    Coordinates { latitude: f64, longitude: f64 },
+}
+
+impl Place<'_> {
+    /// What the place is called in "not found" errors.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Name(_) => "City",
+            Self::Coordinates { .. } => "Location",
+        }
+    }
+}
+
+impl fmt::Display for Place<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Name(name) => write!(f, "{}", name.trim()),
+            Self::Coordinates {
+                latitude,
+                longitude,
+            } => write!(f, "{:.2},{:.2}", latitude, longitude),
+        }
+    }
+}
+
+/// A source of current conditions and a three-day forecast.
+#[async_trait]
+pub trait WeatherProvider: Send + Sync {
+    /// Display name.
+    fn name(&self) -> &'static str;
+
+    /// Current conditions and forecast for `place`.
+    ///
+    /// # Errors
+    ///
+    /// `ServiceError::NotFound` if the provider doesn't know the place,
+    /// otherwise network, status or parse errors.
+    async fn weather(&self, place: Place<'_>) -> Result<WeatherData, ServiceError>;
+}
+
+/// Weather sources an operator can enable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WeatherProviderKind {
+    Wttr,
+    OpenMeteo,
+}
+
+impl WeatherProviderKind {
+    fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "wttr" | "wttr.in" => Some(Self::Wttr),
+            "open-meteo" | "openmeteo" => Some(Self::OpenMeteo),
+            _ => None,
+        }
+    }
+}
+
+/// Ordered weather providers, each used when the ones before it fail.
+pub struct WeatherChain {
+    providers: Vec<Box<dyn WeatherProvider>>,
+}
+
+impl WeatherChain {
+    pub fn new(providers: Vec<Box<dyn WeatherProvider>>) -> Self {
+        Self { providers }
+    }
+
+    /// Builds the chain named in `WEATHER_PROVIDERS` (comma-separated),
+    /// defaulting to wttr.in with Open-Meteo as its fallback.
+    pub fn from_env(client: &Client) -> Self {
+        let kinds: Vec<WeatherProviderKind> = match std::env::var("WEATHER_PROVIDERS") {
+            Ok(list) => list
+                .split(',')
+                .filter(|name| !name.trim().is_empty())
+                .filter_map(|name| {
+                    let kind = WeatherProviderKind::parse(name);
+                    if kind.is_none() {
+                        log::warn!("Ignoring unknown WEATHER_PROVIDERS entry: {}", name);
+                    }
+                    kind
+                })
+                .collect(),
+            Err(_) => vec![WeatherProviderKind::Wttr, WeatherProviderKind::OpenMeteo],
+        };
+
+        let providers: Vec<Box<dyn WeatherProvider>> = kinds
+            .into_iter()
+            .map(|kind| -> Box<dyn WeatherProvider> {
+                match kind {
+                    WeatherProviderKind::Wttr => Box::new(Wttr::new(client.clone())),
+                    WeatherProviderKind::OpenMeteo => Box::new(OpenMeteo::new(client.clone())),
+                }
+            })
+            .collect();
+
+        let names: Vec<_> = providers.iter().map(|p| p.name()).collect();
+        log::info!("Weather providers: {}", names.join(" → "));
+        Self::new(providers)
+    }
+
+    /// Asks each provider in order until one has current conditions for
+    /// `place`. Returns the report with the name of the provider behind it.
+    ///
+    /// # Errors
+    ///
+    /// `ServiceError::NotFound` if every provider lacks the place,
+    /// otherwise the last provider error, or `ServiceError::NoProvider`
+    /// when none is configured.
+    pub async fn weather(
+        &self,
+        place: Place<'_>,
+    ) -> Result<(WeatherData, &'static str), ServiceError> {
+        let mut last_error = None;
+        let mut not_found = None;
+
+        for provider in &self.providers {
+            let result = provider
+                .weather(place)
+                .await
+                .and_then(|data| match data.current {
+                    Some(_) => Ok(data),
+                    None => Err(ServiceError::not_found(
+                        "Current weather",
+                        &place.to_string(),
+                    )),
+                });
+            match result {
+                Ok(data) => return Ok((data, provider.name())),
+                Err(err) => {
+                    log::warn!("{} failed for {}: {}", provider.name(), place, err);
+                    match err {
+                        ServiceError::NotFound { .. } => not_found = Some(err),
+                        err => last_error = Some(err),
+                    }
+                }
+            }
+        }
+
+        Err(not_found.or(last_error).unwrap_or(ServiceError::NoProvider))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Fixed(Result<Option<f64>, fn() -> ServiceError>);
+
+    #[async_trait]
+    impl WeatherProvider for Fixed {
+        fn name(&self) -> &'static str {
+            "Fixed"
+        }
+
+        async fn weather(&self, _place: Place<'_>) -> Result<WeatherData, ServiceError> {
+            match self.0 {
+                Ok(temperature) => Ok(WeatherData {
+                    current: temperature.map(|temperature| CurrentConditions {
+                        temperature: Some(temperature),
+                        ..CurrentConditions::default()
+                    }),
+                    ..WeatherData::default()
+                }),
+                Err(error) => Err(error()),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_falls_back_and_prefers_not_found() {
+        let down = || ServiceError::NoProvider;
+        let unknown = || ServiceError::not_found("City", "Atlantis");
+
+        let chain = WeatherChain::new(vec![
+            Box::new(Fixed(Err(down))),
+            Box::new(Fixed(Ok(Some(20.0)))),
+        ]);
+        let (data, _) = chain.weather(Place::Name("Lima")).await.unwrap();
+        assert_eq!(data.current.and_then(|c| c.temperature), Some(20.0));
+
+        let chain = WeatherChain::new(vec![
+            Box::new(Fixed(Err(unknown))),
+            Box::new(Fixed(Ok(None))),
+            Box::new(Fixed(Err(down))),
+        ]);
+        assert!(matches!(
+            chain.weather(Place::Name("Atlantis")).await,
+            Err(ServiceError::NotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn test_place_display() {
+        let point = Place::Coordinates {
+            latitude: -12.04641,
+            longitude: -77.04282,
+        };
+        assert_eq!(point.to_string(), "-12.05,-77.04");
+        assert_eq!(Place::Name(" Lima ").to_string(), "Lima");
+        assert_eq!(
+            WeatherProviderKind::parse("Open-Meteo"),
+            Some(WeatherProviderKind::OpenMeteo)
+        );
+    }
+}
@@ -0,0 +1,156 @@
+// src/services/weather/model.rs
+
+//! The weather report every provider maps its response into.
+//!
+//! Readings are metric numbers (°C, km/h, hPa, km, percent) and times are
+//! the place's local clock, with its UTC offset alongside. Commands convert
+//! units and format text; providers only parse.
+
+use chrono::{FixedOffset, NaiveDate, NaiveDateTime, Timelike};
+
+/// Current conditions and forecast for one place.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct WeatherData {
+    /// Resolved place name such as `Paris, France`.
+    pub location: Option<String>,
+    /// The place's offset from UTC when the report was made.
+    pub utc_offset: Option<FixedOffset>,
+    /// The latest observation.
+    pub current: Option<CurrentConditions>,
+    /// Forecast for today and the following days.
+    pub days: Vec<DailyForecast>,
+}
+
+/// A weather observation.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct CurrentConditions {
+    /// Local time of the observation.
+    pub observed_at: Option<NaiveDateTime>,
+    pub condition: Condition,
+    /// The provider's wording of the condition, e.g. `Patchy rain nearby`.
+    pub description: Option<String>,
+    /// °C.
+    pub temperature: Option<f64>,
+    /// °C.
+    pub feels_like: Option<f64>,
+    /// Relative humidity in percent.
+    pub humidity: Option<f64>,
+    /// km/h.
+    pub wind_speed: Option<f64>,
+    /// Degrees the wind blows from.
+    pub wind_direction: Option<f64>,
+    /// Sea-level pressure in hPa.
+    pub pressure: Option<f64>,
+    pub uv_index: Option<f64>,
+    /// km.
+    pub visibility: Option<f64>,
+}
+
+impl CurrentConditions {
+    /// Sixteen-point compass direction the wind blows from, e.g. `NNW`.
+    pub fn wind_compass(&self) -> Option<&'static str> {
+        const POINTS: [&str; 16] = [
+            "N", "NNE", "NE", "ENE", "E", "ESE", "SE", "SSE", "S", "SSW", "SW", "WSW", "W", "WNW",
+            "NW", "NNW",
+        ];
+        self.wind_direction
+            .map(|degrees| POINTS[((degrees.rem_euclid(360.0) / 22.5).round() as usize) % 16])
+    }
+}
+
+/// One day of the forecast.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DailyForecast {
+    pub date: NaiveDate,
+    /// °C.
+    pub max_temperature: Option<f64>,
+    /// °C.
+    pub min_temperature: Option<f64>,
+    /// Three-hourly slots from midnight.
+    pub hourly: Vec<HourlyForecast>,
+}
+
+impl DailyForecast {
+    /// Highest chance of rain across the day's slots, in percent.
+    pub fn chance_of_rain(&self) -> Option<f64> {
+        self.hourly
+            .iter()
+            .filter_map(|h| h.chance_of_rain)
+            .max_by(f64::total_cmp)
+    }
+
+    /// The slot closest to noon, representative of the day's weather.
+    pub fn midday(&self) -> Option<&HourlyForecast> {
+        self.hourly
+            .iter()
+            .min_by_key(|h| h.start.hour().abs_diff(12))
+    }
+}
+
+/// A three-hour forecast slot.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HourlyForecast {
+    /// Local time the slot starts at.
+    pub start: NaiveDateTime,
+    pub condition: Condition,
+    pub description: Option<String>,
+    /// °C.
+    pub temperature: Option<f64>,
+    /// Percent.
+    pub chance_of_rain: Option<f64>,
+    /// km/h.
+    pub wind_speed: Option<f64>,
+    /// km/h.
+    pub wind_gust: Option<f64>,
+}
+
+/// The kind of weather, coarse enough for every provider to agree on.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Condition {
+    Clear,
+    PartlyCloudy,
+    Cloudy,
+    Fog,
+    Drizzle,
+    Rain,
+    /// Freezing rain or drizzle, sleet.
+    FreezingRain,
+    Snow,
+    Thunderstorm,
+    #[default]
+    Unknown,
+}
+
+impl Condition {
+    /// Plain description for providers that don't word it themselves.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Clear => "Clear",
+            Self::PartlyCloudy => "Partly cloudy",
+            Self::Cloudy => "Cloudy",
+            Self::Fog => "Fog",
+            Self::Drizzle => "Drizzle",
+            Self::Rain => "Rain",
+            Self::FreezingRain => "Freezing rain",
+            Self::Snow => "Snow",
+            Self::Thunderstorm => "Thunderstorm",
+            Self::Unknown => "Unknown",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wind_compass() {
+        let wind = |degrees| CurrentConditions {
+            wind_direction: Some(degrees),
+            ..CurrentConditions::default()
+        };
+        assert_eq!(wind(315.0).wind_compass(), Some("NW"));
+        assert_eq!(wind(-10.0).wind_compass(), Some("N"));
+        assert_eq!(CurrentConditions::default().wind_compass(), None);
+    }
+}
@@ -0,0 +1,385 @@
+// src/services/weather/open_meteo.rs
+
+//! [Open-Meteo](https://open-meteo.com) — keyless forecasts from national
+//! weather services. Place names are resolved with its geocoding API first;
+//! the forecast is mapped onto [`WeatherData`].
+
+use super::{Condition, CurrentConditions, DailyForecast, HourlyForecast, Place, WeatherProvider};
+use crate::services::{ServiceError, WeatherData};
+use async_trait::async_trait;
+use chrono::{FixedOffset, NaiveDate, NaiveDateTime, Timelike};
+use reqwest::{Client, StatusCode};
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+
+const GEOCODING_URL: &str = "https://geocoding-api.open-meteo.com/v1";
+const FORECAST_URL: &str = "https://api.open-meteo.com/v1";
+
+/// Matches considered when the query names a country, e.g. `Paris, France`.
+const GEOCODING_CANDIDATES: usize = 10;
+
+const CURRENT_FIELDS: &str = "temperature_2m,apparent_temperature,relative_humidity_2m,weather_code,wind_speed_10m,wind_direction_10m,pressure_msl,visibility,uv_index";
+const HOURLY_FIELDS: &str =
+    "temperature_2m,precipitation_probability,wind_speed_10m,wind_gusts_10m,weather_code";
+const DAILY_FIELDS: &str = "temperature_2m_max,temperature_2m_min";
+
+/// Open-Meteo's local timestamps, e.g. `2025-01-31T09:15`.
+const TIME_FORMAT: &str = "%Y-%m-%dT%H:%M";
+
+/// `/search` response body; `results` is absent when nothing matched.
+#[derive(Debug, Deserialize)]
+struct GeocodingResponse {
+    #[serde(default)]
+    results: Vec<GeocodingResult>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GeocodingResult {
+    name: String,
+    latitude: f64,
+    longitude: f64,
+    #[serde(default)]
+    country: Option<String>,
+}
+
+/// `/forecast` response body. Readings may be `null` where a model has no
+/// data.
+#[derive(Debug, Deserialize)]
+struct ForecastResponse {
+    #[serde(default)]
+    utc_offset_seconds: i32,
+    current: Current,
+    #[serde(default)]
+    hourly: Hourly,
+    #[serde(default)]
+    daily: Daily,
+}
+
+#[derive(Debug, Deserialize)]
+struct Current {
+    time: String,
+    temperature_2m: Option<f64>,
+    apparent_temperature: Option<f64>,
+    relative_humidity_2m: Option<f64>,
+    weather_code: Option<u8>,
+    /// km/h.
+    wind_speed_10m: Option<f64>,
+    /// Degrees the wind blows from.
+    wind_direction_10m: Option<f64>,
+    /// Sea-level pressure in hPa.
+    pressure_msl: Option<f64>,
+    /// Metres.
+    visibility: Option<f64>,
+    uv_index: Option<f64>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct Hourly {
+    time: Vec<String>,
+    temperature_2m: Vec<Option<f64>>,
+    precipitation_probability: Vec<Option<f64>>,
+    wind_speed_10m: Vec<Option<f64>>,
+    wind_gusts_10m: Vec<Option<f64>>,
+    weather_code: Vec<Option<u8>>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct Daily {
+    time: Vec<NaiveDate>,
+    temperature_2m_max: Vec<Option<f64>>,
+    temperature_2m_min: Vec<Option<f64>>,
+}
+
+pub struct OpenMeteo {
+    client: Client,
+    geocoding_url: String,
+    forecast_url: String,
+}
+
+impl OpenMeteo {
+    pub fn new(client: Client) -> Self {
+        Self::with_base_urls(client, GEOCODING_URL, FORECAST_URL)
+    }
+
+    /// Points the provider at other servers, e.g. a local stub in tests.
+    pub fn with_base_urls(client: Client, geocoding_url: &str, forecast_url: &str) -> Self {
+        Self {
+            client,
+            geocoding_url: geocoding_url.trim_end_matches('/').to_string(),
+            forecast_url: forecast_url.trim_end_matches('/').to_string(),
+        }
+    }
+
+    async fn get<T: DeserializeOwned>(&self, url: &str) -> Result<T, ServiceError> {
+        let response = self
+            .client
+            .get(url)
+            .send()
+            .await
+            .map_err(|e| ServiceError::network(url, e))?;
+
+        match response.status() {
+            StatusCode::OK => response
+                .json::<T>()
+                .await
+                .map_err(|e| ServiceError::parse(url, e)),
+            status => Err(ServiceError::unexpected_status(url, status)),
+        }
+    }
+
+    /// The best match for `query`. A trailing `, Country` picks among
+    /// same-named places; the top match is used if none is in that country.
+    async fn geocode(&self, query: &str) -> Result<GeocodingResult, ServiceError> {
+        let (name, country) = match query.split_once(',') {
+            Some((name, rest)) => (name.trim(), Some(rest.trim())),
+            None => (query.trim(), None),
+        };
+        let url = format!(
+            "{}/search?name={}&count={}&language=en&format=json",
+            self.geocoding_url,
+            urlencoding::encode(name),
+            GEOCODING_CANDIDATES
+        );
+        let mut results = self.get::<GeocodingResponse>(&url).await?.results;
+
+        let preferred = country.and_then(|country| {
+            results.iter().position(|r| {
+                r.country
+                    .as_deref()
+                    .is_some_and(|c| c.eq_ignore_ascii_case(country))
+            })
+        });
+        match preferred.or((!results.is_empty()).then_some(0)) {
+            Some(index) => Ok(results.swap_remove(index)),
+            None => Err(ServiceError::not_found("City", query)),
+        }
+    }
+}
+
+#[async_trait]
+impl WeatherProvider for OpenMeteo {
+    fn name(&self) -> &'static str {
+        "Open-Meteo"
+    }
+
+    async fn weather(&self, place: Place<'_>) -> Result<WeatherData, ServiceError> {
+        let (latitude, longitude, location) = match place {
+            Place::Name(query) => {
+                let found = self.geocode(query).await?;
+                let location = match found.country {
+                    Some(country) => format!("{}, {}", found.name, country),
+                    None => found.name,
+                };
+                (found.latitude, found.longitude, Some(location))
+            }
+            Place::Coordinates {
+                latitude,
+                longitude,
+            } => (latitude, longitude, None),
+        };
+
+        let url = format!(
+            "{}/forecast?latitude={:.4}&longitude={:.4}&current={}&hourly={}&daily={}&timezone=auto&forecast_days=3",
+            self.forecast_url, latitude, longitude, CURRENT_FIELDS, HOURLY_FIELDS, DAILY_FIELDS
+        );
+        let forecast: ForecastResponse = self.get(&url).await?;
+        Ok(convert(forecast, location))
+    }
+}
+
+/// Maps an Open-Meteo forecast onto the common report.
+fn convert(forecast: ForecastResponse, location: Option<String>) -> WeatherData {
+    let now = &forecast.current;
+    let (condition, description) = describe(now.weather_code);
+    let current = CurrentConditions {
+        observed_at: NaiveDateTime::parse_from_str(&now.time, TIME_FORMAT).ok(),
+        condition,
+        description,
+        temperature: now.temperature_2m,
+        feels_like: now.apparent_temperature,
+        humidity: now.relative_humidity_2m,
+        wind_speed: now.wind_speed_10m,
+        wind_direction: now.wind_direction_10m,
+        pressure: now.pressure_msl,
+        uv_index: now.uv_index,
+        visibility: now.visibility.map(|m| m / 1000.0),
+    };
+
+    let hourly = &forecast.hourly;
+    let at = |values: &Vec<Option<f64>>, i: usize| values.get(i).copied().flatten();
+    let mut slots: Vec<HourlyForecast> = Vec::new();
+    for (i, time) in hourly.time.iter().enumerate() {
+        let Ok(start) = NaiveDateTime::parse_from_str(time, TIME_FORMAT) else {
+            continue;
+        };
+        // wttr.in reports three-hourly slots; keep the same granularity.
+        if start.hour() % 3 != 0 {
+            continue;
+        }
+        let (condition, description) = describe(hourly.weather_code.get(i).copied().flatten());
+        slots.push(HourlyForecast {
+            start,
+            condition,
+            description,
+            temperature: at(&hourly.temperature_2m, i),
+            chance_of_rain: at(&hourly.precipitation_probability, i),
+            wind_speed: at(&hourly.wind_speed_10m, i),
+            wind_gust: at(&hourly.wind_gusts_10m, i),
+        });
+    }
+
+    let daily = &forecast.daily;
+    let days = daily
+        .time
+        .iter()
+        .enumerate()
+        .map(|(i, &date)| DailyForecast {
+            date,
+            max_temperature: at(&daily.temperature_2m_max, i),
+            min_temperature: at(&daily.temperature_2m_min, i),
+            hourly: slots
+                .iter()
+                .filter(|slot| slot.start.date() == date)
+                .cloned()
+                .collect(),
+        })
+        .collect();
+
+    WeatherData {
+        location,
+        utc_offset: FixedOffset::east_opt(forecast.utc_offset_seconds),
+        current: Some(current),
+        days,
+    }
+}
+
+/// The condition and a description for a WMO weather code.
+fn describe(wmo: Option<u8>) -> (Condition, Option<String>) {
+    let (condition, description) = match wmo {
+        Some(0) => (Condition::Clear, "Clear sky"),
+        Some(1) => (Condition::PartlyCloudy, "Mainly clear"),
+        Some(2) => (Condition::PartlyCloudy, "Partly cloudy"),
+        Some(3) => (Condition::Cloudy, "Overcast"),
+        Some(45 | 48) => (Condition::Fog, "Fog"),
+        Some(51 | 53 | 55) => (Condition::Drizzle, "Drizzle"),
+        Some(56 | 57) => (Condition::FreezingRain, "Freezing drizzle"),
+        Some(61) => (Condition::Rain, "Light rain"),
+        Some(63) => (Condition::Rain, "Moderate rain"),
+        Some(65) => (Condition::Rain, "Heavy rain"),
+        Some(66 | 67) => (Condition::FreezingRain, "Freezing rain"),
+        Some(71) => (Condition::Snow, "Light snow"),
+        Some(73) => (Condition::Snow, "Moderate snow"),
+        Some(75 | 77) => (Condition::Snow, "Heavy snow"),
+        Some(80) => (Condition::Rain, "Light rain shower"),
+        Some(81) => (Condition::Rain, "Rain shower"),
+        Some(82) => (Condition::Rain, "Torrential rain shower"),
+        Some(85) => (Condition::Snow, "Light snow showers"),
+        Some(86) => (Condition::Snow, "Heavy snow showers"),
+        Some(95) => (Condition::Thunderstorm, "Thunderstorm"),
+        Some(96 | 99) => (Condition::Thunderstorm, "Thunderstorm with hail"),
+        _ => return (Condition::Unknown, None),
+    };
+    (condition, Some(description.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::{Matcher, Server};
+
+    const FORECAST: &str = r#"{
+        "utc_offset_seconds": -18000,
+        "current": {"time": "2025-01-31T09:15", "temperature_2m": 18.4,
+            "apparent_temperature": 17.2, "relative_humidity_2m": 62, "weather_code": 2,
+            "wind_speed_10m": 15.3, "wind_direction_10m": 315, "pressure_msl": 1015.2,
+            "visibility": 10000, "uv_index": 3.1},
+        "hourly": {
+            "time": ["2025-01-31T00:00", "2025-01-31T01:00", "2025-01-31T03:00", "2025-02-01T00:00"],
+            "temperature_2m": [20.1, 19.8, -0.3, 19.0],
+            "precipitation_probability": [10, 10, null, 80],
+            "wind_speed_10m": [10, 11, 12, 50],
+            "wind_gusts_10m": [20, 22, 24, 90],
+            "weather_code": [0, 0, 61, 95]
+        },
+        "daily": {"time": ["2025-01-31", "2025-02-01"],
+                  "temperature_2m_max": [27.3, 26.0], "temperature_2m_min": [19.6, 19.0]}
+    }"#;
+
+    #[tokio::test]
+    async fn test_geocodes_and_converts_forecast() {
+        let mut server = Server::new_async().await;
+        server
+            .mock("GET", "/search")
+            .match_query(Matcher::UrlEncoded("name".into(), "Paris".into()))
+            .with_body(
+                r#"{"results": [
+                    {"name": "Paris", "latitude": 33.66, "longitude": -95.55, "country": "United States"},
+                    {"name": "Paris", "latitude": 48.85, "longitude": 2.35, "country": "France"}
+                ]}"#,
+            )
+            .create_async()
+            .await;
+        let forecast = server
+            .mock("GET", "/forecast")
+            .match_query(Matcher::UrlEncoded("latitude".into(), "48.8500".into()))
+            .with_body(FORECAST)
+            .create_async()
+            .await;
+
+        let provider = OpenMeteo::with_base_urls(Client::new(), &server.url(), &server.url());
+        let data = provider
+            .weather(Place::Name("Paris, France"))
+            .await
+            .unwrap();
+        forecast.assert_async().await;
+
+        assert_eq!(data.location.as_deref(), Some("Paris, France"));
+        assert_eq!(
+            data.utc_offset.map(|o| o.local_minus_utc()),
+            Some(-5 * 3600)
+        );
+        let now = data.current.unwrap();
+        assert_eq!(
+            (now.temperature, now.wind_compass(), now.visibility),
+            (Some(18.4), Some("NW"), Some(10.0))
+        );
+        assert_eq!(
+            (now.condition, now.description.as_deref()),
+            (Condition::PartlyCloudy, Some("Partly cloudy"))
+        );
+        assert_eq!(now.observed_at.unwrap().to_string(), "2025-01-31 09:15:00");
+
+        assert_eq!(data.days.len(), 2);
+        let today = &data.days[0];
+        assert_eq!(
+            (today.max_temperature, today.min_temperature),
+            (Some(27.3), Some(19.6))
+        );
+        let hours: Vec<_> = today.hourly.iter().map(|h| h.start.hour()).collect();
+        assert_eq!(hours, [0, 3]);
+        assert_eq!(today.hourly[1].condition, Condition::Rain);
+        assert_eq!(today.hourly[1].chance_of_rain, None);
+        let tomorrow = &data.days[1].hourly[0];
+        assert_eq!(
+            (tomorrow.condition, tomorrow.wind_gust),
+            (Condition::Thunderstorm, Some(90.0))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_unknown_city_is_not_found() {
+        let mut server = Server::new_async().await;
+        server
+            .mock("GET", "/search")
+            .match_query(Matcher::Any)
+            .with_body(r#"{"generationtime_ms": 0.5}"#)
+            .create_async()
+            .await;
+
+        let provider = OpenMeteo::with_base_urls(Client::new(), &server.url(), &server.url());
+        assert!(matches!(
+            provider.weather(Place::Name("Atlantis")).await,
+            Err(ServiceError::NotFound { .. })
+        ));
+    }
+}
@@ -0,0 +1,363 @@
+// src/services/weather/wttr.rs
+
+//! [wttr.in](https://wttr.in) — keyless weather for place names and
+//! coordinates. Its `format=j1` response, with every number sent as a
+//! string, is mapped onto [`WeatherData`].
+
+use super::{Condition, CurrentConditions, DailyForecast, HourlyForecast, Place, WeatherProvider};
+use crate::services::{ServiceError, WeatherData};
+use async_trait::async_trait;
+use chrono::{FixedOffset, NaiveDate, NaiveDateTime, NaiveTime};
+use reqwest::{Client, StatusCode};
+use serde::Deserialize;
+
+const BASE_URL: &str = "https://wttr.in";
+
+/// `format=j1` response body.
+#[derive(Debug, Deserialize)]
+struct Report {
+    current_condition: Vec<Current>,
+    /// Places the query was resolved to, closest first.
+    #[serde(default)]
+    nearest_area: Vec<Area>,
+    /// Today and the next two days.
+    #[serde(default)]
+    weather: Vec<Day>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Current {
+    #[serde(rename = "temp_C", default)]
+    temp_c: String,
+    #[serde(rename = "FeelsLikeC", default)]
+    feels_like_c: String,
+    #[serde(default)]
+    humidity: String,
+    #[serde(rename = "windspeedKmph", default)]
+    wind_speed_kmph: String,
+    #[serde(rename = "winddirDegree", default)]
+    wind_degree: String,
+    /// hPa.
+    #[serde(default)]
+    pressure: String,
+    #[serde(rename = "uvIndex", default)]
+    uv_index: String,
+    /// Kilometres.
+    #[serde(default)]
+    visibility: String,
+    /// WWO condition code, e.g. `113` for clear skies.
+    #[serde(rename = "weatherCode", default)]
+    weather_code: String,
+    #[serde(rename = "weatherDesc", default)]
+    weather_desc: Vec<Text>,
+    /// UTC clock time of the observation, e.g. `02:15 PM`.
+    #[serde(default)]
+    observation_time: String,
+    /// Local date and time of the observation, e.g. `2025-01-31 09:15 AM`.
+    #[serde(rename = "localObsDateTime", default)]
+    local_obs_date_time: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Day {
+    date: NaiveDate,
+    #[serde(rename = "maxtempC", default)]
+    max_temp_c: String,
+    #[serde(rename = "mintempC", default)]
+    min_temp_c: String,
+    #[serde(default)]
+    hourly: Vec<Slot>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Slot {
+    /// Local time as `HMM` without a separator: `0`, `300`, …, `2100`.
+    time: String,
+    #[serde(rename = "tempC", default)]
+    temp_c: String,
+    #[serde(rename = "chanceofrain", default)]
+    chance_of_rain: String,
+    #[serde(rename = "windspeedKmph", default)]
+    wind_speed_kmph: String,
+    #[serde(rename = "WindGustKmph", default)]
+    wind_gust_kmph: String,
+    #[serde(rename = "weatherCode", default)]
+    weather_code: String,
+    #[serde(rename = "weatherDesc", default)]
+    weather_desc: Vec<Text>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Area {
+    #[serde(rename = "areaName", default)]
+    area_name: Vec<Text>,
+    #[serde(default)]
+    country: Vec<Text>,
+}
+
+/// wttr.in's `[{"value": ...}]` text wrapper.
+#[derive(Debug, Deserialize)]
+struct Text {
+    value: String,
+}
+
+pub struct Wttr {
+    client: Client,
+    base_url: String,
+}
+
+impl Wttr {
+    pub fn new(client: Client) -> Self {
+        Self::with_base_url(client, BASE_URL)
+    }
+
+    /// Points the provider at another server, e.g. a local stub in tests.
+    pub fn with_base_url(client: Client, base_url: &str) -> Self {
+        Self {
+            client,
+            base_url: base_url.trim_end_matches('/').to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl WeatherProvider for Wttr {
+    fn name(&self) -> &'static str {
+        "wttr.in"
+    }
+
+    async fn weather(&self, place: Place<'_>) -> Result<WeatherData, ServiceError> {
+        let query = place.to_string();
+        let url = format!(
+            "{}/{}?format=j1",
+            self.base_url,
+            urlencoding::encode(&query)
+        );
+
+        let response = self
+            .client
+            .get(&url)
+            .send()
+            .await
+            .map_err(|e| ServiceError::network(&url, e))?;
+
+        match response.status() {
+            StatusCode::NOT_FOUND => Err(ServiceError::not_found(place.kind(), &query)),
+            StatusCode::OK => response
+                .json::<Report>()
+                .await
+                .map(convert)
+                .map_err(|e| ServiceError::parse(&url, e)),
+            status => Err(ServiceError::unexpected_status(&url, status)),
+        }
+    }
+}
+
+/// Maps a wttr.in report onto the common one.
+fn convert(report: Report) -> WeatherData {
+    let location = report.nearest_area.first().and_then(|area| {
+        let parts: Vec<&str> = [&area.area_name, &area.country]
+            .into_iter()
+            .filter_map(|field| first_text(field))
+            .collect();
+        (!parts.is_empty()).then(|| parts.join(", "))
+    });
+    let now = report.current_condition.first();
+
+    let current = now.map(|now| CurrentConditions {
+        observed_at: observed_at(now),
+        condition: condition(&now.weather_code),
+        description: first_text(&now.weather_desc).map(str::to_string),
+        temperature: number(&now.temp_c),
+        feels_like: number(&now.feels_like_c),
+        humidity: number(&now.humidity),
+        wind_speed: number(&now.wind_speed_kmph),
+        wind_direction: number(&now.wind_degree),
+        pressure: number(&now.pressure),
+        uv_index: number(&now.uv_index),
+        visibility: number(&now.visibility),
+    });
+
+    let days = report
+        .weather
+        .into_iter()
+        .map(|day| DailyForecast {
+            date: day.date,
+            max_temperature: number(&day.max_temp_c),
+            min_temperature: number(&day.min_temp_c),
+            hourly: day
+                .hourly
+                .iter()
+                .filter_map(|slot| {
+                    let hour = slot.time.trim().parse::<u32>().ok()? / 100;
+                    Some(HourlyForecast {
+                        start: day.date.and_time(NaiveTime::from_hms_opt(hour, 0, 0)?),
+                        condition: condition(&slot.weather_code),
+                        description: first_text(&slot.weather_desc).map(str::to_string),
+                        temperature: number(&slot.temp_c),
+                        chance_of_rain: number(&slot.chance_of_rain),
+                        wind_speed: number(&slot.wind_speed_kmph),
+                        wind_gust: number(&slot.wind_gust_kmph),
+                    })
+                })
+                .collect(),
+        })
+        .collect();
+
+    WeatherData {
+        location,
+        utc_offset: now.and_then(utc_offset),
+        current,
+        days,
+    }
+}
+
+fn observed_at(now: &Current) -> Option<NaiveDateTime> {
+    NaiveDateTime::parse_from_str(now.local_obs_date_time.trim(), "%Y-%m-%d %I:%M %p").ok()
+}
+
+/// The place's UTC offset, worked out from the observation's local and UTC
+/// clock times and rounded to the nearest quarter hour.
+fn utc_offset(now: &Current) -> Option<FixedOffset> {
+    let local = observed_at(now)?;
+    let utc = NaiveTime::parse_from_str(now.observation_time.trim(), "%I:%M %p").ok()?;
+
+    // Only clock times are compared, so fold the difference into the range
+    // real offsets span (UTC-12 to UTC+14).
+    let mut minutes = (local.time() - utc).num_minutes();
+    if minutes < -12 * 60 {
+        minutes += 24 * 60;
+    } else if minutes > 14 * 60 {
+        minutes -= 24 * 60;
+    }
+    let minutes = (minutes as f64 / 15.0).round() as i32 * 15;
+    FixedOffset::east_opt(minutes * 60)
+}
+
+/// The condition behind a WWO weather code.
+fn condition(code: &str) -> Condition {
+    match code.trim().parse::<u16>().unwrap_or_default() {
+        113 => Condition::Clear,
+        116 => Condition::PartlyCloudy,
+        119 | 122 => Condition::Cloudy,
+        143 | 248 | 260 => Condition::Fog,
+        200 | 386 | 389 | 392 | 395 => Condition::Thunderstorm,
+        263 | 266 => Condition::Drizzle,
+        176 | 293..=308 | 353..=359 => Condition::Rain,
+        281 | 284 | 311 | 314 | 317 | 320 | 350 | 362..=365 | 374 | 377 => Condition::FreezingRain,
+        179 | 182 | 185 | 227 | 230 | 323..=338 | 368 | 371 => Condition::Snow,
+        _ => Condition::Unknown,
+    }
+}
+
+/// A reading; wttr.in leaves missing ones empty.
+fn number(value: &str) -> Option<f64> {
+    value.trim().parse().ok()
+}
+
+fn first_text(field: &[Text]) -> Option<&str> {
+    field
+        .first()
+        .map(|t| t.value.trim())
+        .filter(|v| !v.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::Server;
+
+    const PARIS: &str = r#"{
+        "current_condition": [{
+            "FeelsLikeC": "17", "humidity": "62", "pressure": "1015",
+            "temp_C": "18", "uvIndex": "3", "visibility": "10",
+            "weatherCode": "116", "weatherDesc": [{"value": "Partly cloudy"}],
+            "winddir16Point": "NW", "winddirDegree": "315", "windspeedKmph": "15",
+            "observation_time": "08:15 AM", "localObsDateTime": "2025-01-31 09:15 AM"
+        }],
+        "nearest_area": [{
+            "areaName": [{"value": "Paris"}],
+            "country": [{"value": "France"}],
+            "region": [{"value": "Ile-de-France"}]
+        }],
+        "weather": [{
+            "date": "2025-01-31", "maxtempC": "9", "mintempC": "2",
+            "hourly": [
+                {"time": "0", "tempC": "3", "chanceofrain": "0", "weatherCode": "113"},
+                {"time": "1200", "tempC": "8", "chanceofrain": "70", "WindGustKmph": "40",
+                 "weatherCode": "296", "weatherDesc": [{"value": "Light rain"}]}
+            ]
+        }]
+    }"#;
+
+    #[tokio::test]
+    async fn test_converts_report() {
+        let mut server = Server::new_async().await;
+        server
+            .mock("GET", "/Paris?format=j1")
+            .with_body(PARIS)
+            .create_async()
+            .await;
+
+        let provider = Wttr::with_base_url(Client::new(), &server.url());
+        let data = provider.weather(Place::Name("Paris")).await.unwrap();
+        assert_eq!(data.location.as_deref(), Some("Paris, France"));
+        assert_eq!(data.utc_offset.map(|o| o.local_minus_utc()), Some(3600));
+
+        let now = data.current.unwrap();
+        assert_eq!(now.condition, Condition::PartlyCloudy);
+        assert_eq!(now.description.as_deref(), Some("Partly cloudy"));
+        assert_eq!((now.temperature, now.visibility), (Some(18.0), Some(10.0)));
+        assert_eq!(now.wind_compass(), Some("NW"));
+        assert_eq!(now.observed_at.unwrap().to_string(), "2025-01-31 09:15:00");
+
+        let today = &data.days[0];
+        assert_eq!(today.max_temperature, Some(9.0));
+        let noon = &today.hourly[1];
+        assert_eq!(noon.start.to_string(), "2025-01-31 12:00:00");
+        assert_eq!(
+            (
+                noon.condition,
+                noon.chance_of_rain,
+                noon.wind_gust,
+                noon.wind_speed
+            ),
+            (Condition::Rain, Some(70.0), Some(40.0), None)
+        );
+    }
+
+    #[test]
+    fn test_utc_offset() {
+        let offset = |utc: &str, local: &str| {
+            let now: Current = serde_json::from_value(serde_json::json!({
+                "observation_time": utc,
+                "localObsDateTime": local,
+            }))
+            .unwrap();
+            utc_offset(&now).map(|o| o.local_minus_utc())
+        };
+        assert_eq!(offset("02:15 PM", "2025-01-31 09:15 AM"), Some(-5 * 3600));
+        assert_eq!(
+            offset("11:40 PM", "2025-02-01 05:12 AM"),
+            Some(5 * 3600 + 30 * 60)
+        );
+        assert_eq!(offset("10:00 AM", "2025-01-31 11:00 PM"), Some(13 * 3600));
+        assert_eq!(offset("", "2025-01-31 11:00 PM"), None);
+    }
+
+    #[tokio::test]
+    async fn test_unknown_place_is_not_found() {
+        let mut server = Server::new_async().await;
+        server
+            .mock("GET", "/Atlantis?format=j1")
+            .with_status(404)
+            .create_async()
+            .await;
+
+        let provider = Wttr::with_base_url(Client::new(), &server.url());
+        assert!(matches!(
+            provider.weather(Place::Name("Atlantis")).await,
+            Err(ServiceError::NotFound { .. })
+        ));
+    }
+}
@@ -0,0 +1,152 @@
+// src/storage/alerts.rs
+
+//! Storage shared by every kind of chat alert: IDs, ownership and the
+//! per-chat listing behind the cancel buttons.
+
+use serde::{Deserialize, Serialize};
+
+/// Fields every alert has, whatever it watches.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AlertOwner {
+    pub id: u64,
+    pub chat_id: i64,
+    /// Who created the alert; they (or an admin) may cancel it.
+    pub user_id: Option<u64>,
+}
+
+impl AlertOwner {
+    /// Owner details for a new alert; the ID is assigned when it is stored.
+    pub fn new(chat_id: i64, user_id: Option<u64>) -> Self {
+        Self {
+            id: 0,
+            chat_id,
+            user_id,
+        }
+    }
+}
+
+/// An alert kept in an [`AlertStore`].
+pub trait Alert: Clone {
+    fn owner(&self) -> &AlertOwner;
+    fn owner_mut(&mut self) -> &mut AlertOwner;
+}
+
+/// All alerts of one kind across chats.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AlertStore<A> {
+    next_id: u64,
+    alerts: Vec<A>,
+}
+
+impl<A> Default for AlertStore<A> {
+    fn default() -> Self {
+        Self {
+            next_id: 0,
+            alerts: Vec::new(),
+        }
+    }
+}
+
+impl<A: Alert> AlertStore<A> {
+    /// Stores a new alert, assigning its ID, unless its chat already has
+    /// `max_per_chat` alerts.
+    pub fn add(&mut self, mut alert: A, max_per_chat: usize) -> Option<u64> {
+        let chat_id = alert.owner().chat_id;
+        if self.in_chat(chat_id).len() >= max_per_chat {
+            return None;
+        }
+        self.next_id += 1;
+        alert.owner_mut().id = self.next_id;
+        self.alerts.push(alert);
+        Some(self.next_id)
+    }
+
+    pub fn remove(&mut self, id: u64) -> Option<A> {
+        let index = self.alerts.iter().position(|a| a.owner().id == id)?;
+        Some(self.alerts.remove(index))
+    }
+
+    /// Removes alert `id` from `chat_id` if `allowed` accepts it. Returns
+    /// `None` if the chat has no such alert, otherwise whether it was
+    /// removed.
+    pub fn cancel(
+        &mut self,
+        id: u64,
+        chat_id: i64,
+        allowed: impl FnOnce(&AlertOwner) -> bool,
+    ) -> Option<bool> {
+        let owner = self
+            .get(id)
+            .map(Alert::owner)
+            .filter(|o| o.chat_id == chat_id)?;
+        Some(allowed(owner) && self.remove(id).is_some())
+    }
+
+    pub fn get(&self, id: u64) -> Option<&A> {
+        self.alerts.iter().find(|a| a.owner().id == id)
+    }
+
+    pub fn get_mut(&mut self, id: u64) -> Option<&mut A> {
+        self.alerts.iter_mut().find(|a| a.owner().id == id)
+    }
+
+    pub fn in_chat(&self, chat_id: i64) -> Vec<A> {
+        self.alerts
+            .iter()
+            .filter(|a| a.owner().chat_id == chat_id)
+            .cloned()
+            .collect()
+    }
+
+    pub fn all(&self) -> &[A] {
+        &self.alerts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Clone)]
+    struct TestAlert(AlertOwner);
+
+    impl Alert for TestAlert {
+        fn owner(&self) -> &AlertOwner {
+            &self.0
+        }
+
+        fn owner_mut(&mut self) -> &mut AlertOwner {
+            &mut self.0
+        }
+    }
+
+    fn alert(chat_id: i64) -> TestAlert {
+        TestAlert(AlertOwner::new(chat_id, Some(1)))
+    }
+
+    #[test]
+    fn test_add_remove_and_list() {
+        let mut alerts = AlertStore::default();
+        let first = alerts.add(alert(10), 2).unwrap();
+        let second = alerts.add(alert(20), 2).unwrap();
+        assert_ne!(first, second);
+        alerts.add(alert(10), 2);
+        assert_eq!(alerts.add(alert(10), 2), None);
+        assert_eq!(alerts.in_chat(10).len(), 2);
+
+        assert_eq!(alerts.remove(first).map(|a| a.0.chat_id), Some(10));
+        assert!(alerts.remove(first).is_none());
+        assert_eq!(alerts.all().len(), 2);
+    }
+
+    #[test]
+    fn test_cancel_checks_chat_and_owner() {
+        let mut alerts = AlertStore::default();
+        let id = alerts.add(alert(10), 5).unwrap();
+
+        assert_eq!(alerts.cancel(id, 20, |_| true), None);
+        assert_eq!(alerts.cancel(id, 10, |o| o.user_id == Some(2)), Some(false));
+        assert_eq!(alerts.cancel(id, 10, |o| o.user_id == Some(1)), Some(true));
+        assert_eq!(alerts.cancel(id, 10, |_| true), None);
+    }
+}
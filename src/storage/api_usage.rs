@@ -0,0 +1,66 @@
+// src/storage/api_usage.rs
+
+//! Calls made to each external provider in the current billing period.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Usage counters keyed by provider ID.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ApiUsage {
+    providers: HashMap<String, PeriodUsage>,
+}
+
+/// Calls made by one provider during `period` (e.g. `2025-01`).
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PeriodUsage {
+    pub period: String,
+    pub calls: u32,
+    /// Whether admins were told about the soft limit this period.
+    #[serde(default)]
+    pub soft_notified: bool,
+    /// Whether admins were told about the hard limit this period.
+    #[serde(default)]
+    pub hard_notified: bool,
+}
+
+impl ApiUsage {
+    /// Calls made by `provider` in `period`; earlier periods count as zero.
+    pub fn calls(&self, provider: &str, period: &str) -> u32 {
+        self.providers
+            .get(provider)
+            .filter(|usage| usage.period == period)
+            .map_or(0, |usage| usage.calls)
+    }
+
+    /// `provider`'s counter for `period`, started afresh when the period
+    /// changed.
+    pub fn current(&mut self, provider: &str, period: &str) -> &mut PeriodUsage {
+        let usage = self.providers.entry(provider.to_string()).or_default();
+        if usage.period != period {
+            *usage = PeriodUsage {
+                period: period.to_string(),
+                ..PeriodUsage::default()
+            };
+        }
+        usage
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counter_resets_each_period() {
+        let mut usage = ApiUsage::default();
+        usage.current("exchangerate", "2025-01").calls += 2;
+        usage.current("exchangerate", "2025-01").soft_notified = true;
+        assert_eq!(usage.calls("exchangerate", "2025-01"), 2);
+
+        assert_eq!(usage.calls("exchangerate", "2025-02"), 0);
+        let fresh = usage.current("exchangerate", "2025-02");
+        assert_eq!((fresh.calls, fresh.soft_notified), (0, false));
+        assert_eq!(usage.calls("frankfurter", "2025-02"), 0);
+    }
+}
@@ -0,0 +1,87 @@
+// src/storage/fx_alerts.rs
+
+//! Exchange-rate alerts waiting for their threshold to be crossed.
+
+use super::alerts::{Alert, AlertOwner, AlertStore};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+/// Which side of the threshold fires the alert.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Direction {
+    Above,
+    Below,
+}
+
+impl Direction {
+    pub fn symbol(self) -> &'static str {
+        match self {
+            Self::Above => ">",
+            Self::Below => "<",
+        }
+    }
+}
+
+/// A one-shot alert on a currency pair.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FxAlert {
+    #[serde(flatten)]
+    pub owner: AlertOwner,
+    pub from: String,
+    pub to: String,
+    pub direction: Direction,
+    pub threshold: Decimal,
+}
+
+impl FxAlert {
+    /// Whether `rate` is on the firing side of the threshold.
+    pub fn is_triggered(&self, rate: Decimal) -> bool {
+        match self.direction {
+            Direction::Above => rate > self.threshold,
+            Direction::Below => rate < self.threshold,
+        }
+    }
+}
+
+impl Alert for FxAlert {
+    fn owner(&self) -> &AlertOwner {
+        &self.owner
+    }
+
+    fn owner_mut(&mut self) -> &mut AlertOwner {
+        &mut self.owner
+    }
+}
+
+/// All pending FX alerts across chats.
+pub type FxAlerts = AlertStore<FxAlert>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trigger_direction() {
+        let alert = |direction| FxAlert {
+            owner: AlertOwner::new(1, None),
+            from: "USD".to_string(),
+            to: "PEN".to_string(),
+            direction,
+            threshold: Decimal::from(4),
+        };
+        let above = alert(Direction::Above);
+        assert!(above.is_triggered(Decimal::new(401, 2)));
+        assert!(!above.is_triggered(Decimal::from(4)));
+
+        let below = alert(Direction::Below);
+        assert!(below.is_triggered(Decimal::new(399, 2)));
+    }
+
+    #[test]
+    fn test_owner_is_stored_inline() {
+        let json = r#"{"id":3,"chat_id":10,"user_id":1,"from":"USD","to":"PEN","direction":"Above","threshold":"4"}"#;
+        let alert: FxAlert = serde_json::from_str(json).unwrap();
+        assert_eq!(alert.owner.id, 3);
+        assert_eq!(serde_json::to_string(&alert).unwrap(), json);
+    }
+}
@@ -0,0 +1,142 @@
+// src/storage/joke_prefs.rs
+
+//! Per-chat joke settings: language, excluded content and safe mode.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+
+/// JokeAPI content flags a chat can exclude.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JokeFlag {
+    Nsfw,
+    Religious,
+    Political,
+    Racist,
+    Sexist,
+    Explicit,
+}
+
+impl JokeFlag {
+    pub const ALL: [Self; 6] = [
+        Self::Nsfw,
+        Self::Religious,
+        Self::Political,
+        Self::Racist,
+        Self::Sexist,
+        Self::Explicit,
+    ];
+
+    /// The name JokeAPI and users know the flag by.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Nsfw => "nsfw",
+            Self::Religious => "religious",
+            Self::Political => "political",
+            Self::Racist => "racist",
+            Self::Sexist => "sexist",
+            Self::Explicit => "explicit",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|flag| flag.name().eq_ignore_ascii_case(name.trim()))
+    }
+}
+
+/// One chat's joke settings.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChatJokePrefs {
+    /// JokeAPI language code, e.g. `en` or `de`.
+    #[serde(default = "default_language")]
+    pub language: String,
+    /// Flags whose jokes are never requested.
+    #[serde(default)]
+    pub blacklist: BTreeSet<JokeFlag>,
+    /// Whether only jokes JokeAPI considers safe for everyone are told.
+    #[serde(default = "enabled")]
+    pub safe_mode: bool,
+}
+
+impl Default for ChatJokePrefs {
+    fn default() -> Self {
+        Self {
+            language: default_language(),
+            blacklist: BTreeSet::new(),
+            safe_mode: true,
+        }
+    }
+}
+
+fn default_language() -> String {
+    "en".to_string()
+}
+
+fn enabled() -> bool {
+    true
+}
+
+/// Settings of every chat that changed the defaults, keyed by chat ID.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct JokePrefs {
+    chats: HashMap<i64, ChatJokePrefs>,
+}
+
+impl JokePrefs {
+    /// The chat's settings, or the defaults if it never saved any.
+    pub fn get(&self, chat_id: i64) -> ChatJokePrefs {
+        self.chats.get(&chat_id).cloned().unwrap_or_default()
+    }
+
+    pub fn set_language(&mut self, chat_id: i64, language: &str) {
+        self.chats.entry(chat_id).or_default().language = language.to_string();
+    }
+
+    /// Adds `flags` to the chat's blacklist, or removes them when `blocked`
+    /// is false.
+    pub fn set_blocked(&mut self, chat_id: i64, flags: &[JokeFlag], blocked: bool) {
+        let blacklist = &mut self.chats.entry(chat_id).or_default().blacklist;
+        for flag in flags {
+            if blocked {
+                blacklist.insert(*flag);
+            } else {
+                blacklist.remove(flag);
+            }
+        }
+    }
+
+    pub fn set_safe_mode(&mut self, chat_id: i64, enabled: bool) {
+        self.chats.entry(chat_id).or_default().safe_mode = enabled;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_defaults_and_updates() {
+        let mut prefs = JokePrefs::default();
+        assert_eq!(prefs.get(-100), ChatJokePrefs::default());
+        assert!(prefs.get(-100).safe_mode);
+
+        prefs.set_language(-100, "de");
+        prefs.set_blocked(-100, &[JokeFlag::Political, JokeFlag::Nsfw], true);
+        prefs.set_blocked(-100, &[JokeFlag::Nsfw], false);
+        prefs.set_safe_mode(-100, false);
+        let chat = prefs.get(-100);
+        assert_eq!(chat.language, "de");
+        assert_eq!(
+            chat.blacklist.into_iter().collect::<Vec<_>>(),
+            [JokeFlag::Political]
+        );
+        assert!(!chat.safe_mode);
+
+        let stored: ChatJokePrefs = serde_json::from_str(r#"{"blacklist": ["nsfw"]}"#).unwrap();
+        assert_eq!(stored.language, "en");
+        assert!(stored.safe_mode);
+        assert_eq!(JokeFlag::parse(" NSFW"), Some(JokeFlag::Nsfw));
+    }
+}
@@ -0,0 +1,208 @@
+// src/storage/jokes.rs
+
+//! The local joke collection: bundled classics plus user submissions,
+//! moderated by admins and voted on by everyone.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Jokes shipped with the bot, one string per joke.
+const BUNDLED: &str = include_str!("../../assets/jokes.json");
+
+/// Where a joke is in moderation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JokeStatus {
+    Pending,
+    Approved,
+    Rejected,
+}
+
+/// Who sent a joke in, so they can be told the verdict.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Submitter {
+    pub user_id: u64,
+    pub name: String,
+}
+
+/// A joke in the local collection.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StoredJoke {
+    pub id: u64,
+    pub text: String,
+    /// `None` for bundled jokes.
+    pub submitter: Option<Submitter>,
+    pub status: JokeStatus,
+    /// +1 or -1 per voter, keyed by user ID.
+    #[serde(default)]
+    pub votes: HashMap<u64, i8>,
+}
+
+impl StoredJoke {
+    pub fn upvotes(&self) -> usize {
+        self.votes.values().filter(|v| **v > 0).count()
+    }
+
+    pub fn downvotes(&self) -> usize {
+        self.votes.values().filter(|v| **v < 0).count()
+    }
+
+    pub fn score(&self) -> i64 {
+        self.votes.values().map(|v| i64::from(*v)).sum()
+    }
+}
+
+/// Every local joke, whatever its status.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct JokeStore {
+    next_id: u64,
+    jokes: Vec<StoredJoke>,
+}
+
+impl JokeStore {
+    /// Adds the bundled jokes that aren't in the collection yet, approved.
+    pub fn seed(&mut self) {
+        let bundled: Vec<String> = match serde_json::from_str(BUNDLED) {
+            Ok(bundled) => bundled,
+            Err(err) => {
+                log::error!("Ignoring unreadable bundled jokes: {}", err);
+                return;
+            }
+        };
+        for text in bundled {
+            if !self.jokes.iter().any(|j| j.text == text) {
+                self.insert(text, None, JokeStatus::Approved);
+            }
+        }
+    }
+
+    /// Queues a user's joke for moderation and returns its ID.
+    pub fn submit(&mut self, text: String, submitter: Submitter) -> u64 {
+        self.insert(text, Some(submitter), JokeStatus::Pending)
+    }
+
+    fn insert(&mut self, text: String, submitter: Option<Submitter>, status: JokeStatus) -> u64 {
+        self.next_id += 1;
+        self.jokes.push(StoredJoke {
+            id: self.next_id,
+            text,
+            submitter,
+            status,
+            votes: HashMap::new(),
+        });
+        self.next_id
+    }
+
+    pub fn get(&self, id: u64) -> Option<&StoredJoke> {
+        self.jokes.iter().find(|j| j.id == id)
+    }
+
+    /// Approves or rejects a pending joke. Returns it, or `None` if it isn't
+    /// pending (e.g. another admin was quicker).
+    pub fn moderate(&mut self, id: u64, approve: bool) -> Option<StoredJoke> {
+        let joke = self
+            .jokes
+            .iter_mut()
+            .find(|j| j.id == id && j.status == JokeStatus::Pending)?;
+        joke.status = if approve {
+            JokeStatus::Approved
+        } else {
+            JokeStatus::Rejected
+        };
+        Some(joke.clone())
+    }
+
+    /// Pending jokes, oldest first.
+    pub fn pending(&self) -> Vec<StoredJoke> {
+        self.with_status(JokeStatus::Pending).cloned().collect()
+    }
+
+    /// How many of `user_id`'s jokes await moderation.
+    pub fn pending_from(&self, user_id: u64) -> usize {
+        self.with_status(JokeStatus::Pending)
+            .filter(|j| j.submitter.as_ref().is_some_and(|s| s.user_id == user_id))
+            .count()
+    }
+
+    pub fn approved(&self) -> Vec<&StoredJoke> {
+        self.with_status(JokeStatus::Approved).collect()
+    }
+
+    /// Records `user_id`'s vote on an approved joke; voting the same way
+    /// twice withdraws the vote. Returns the joke as it stands.
+    pub fn vote(&mut self, id: u64, user_id: u64, up: bool) -> Option<StoredJoke> {
+        let joke = self
+            .jokes
+            .iter_mut()
+            .find(|j| j.id == id && j.status == JokeStatus::Approved)?;
+        let value = if up { 1 } else { -1 };
+        if joke.votes.get(&user_id) == Some(&value) {
+            joke.votes.remove(&user_id);
+        } else {
+            joke.votes.insert(user_id, value);
+        }
+        Some(joke.clone())
+    }
+
+    /// The `limit` best-scored approved jokes with at least one upvote,
+    /// most upvoted first among equal scores.
+    pub fn top(&self, limit: usize) -> Vec<StoredJoke> {
+        let mut ranked: Vec<_> = self
+            .with_status(JokeStatus::Approved)
+            .filter(|j| j.upvotes() > 0)
+            .collect();
+        ranked.sort_by_key(|j| (std::cmp::Reverse(j.score()), std::cmp::Reverse(j.upvotes())));
+        ranked.into_iter().take(limit).cloned().collect()
+    }
+
+    fn with_status(&self, status: JokeStatus) -> impl Iterator<Item = &StoredJoke> {
+        self.jokes.iter().filter(move |j| j.status == status)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn alice() -> Submitter {
+        Submitter {
+            user_id: 1,
+            name: "Alice".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_seed_is_idempotent() {
+        let mut store = JokeStore::default();
+        store.seed();
+        let seeded = store.approved().len();
+        assert!(seeded > 0);
+        store.seed();
+        assert_eq!(store.approved().len(), seeded);
+    }
+
+    #[test]
+    fn test_moderation_and_votes() {
+        let mut store = JokeStore::default();
+        let first = store.submit("Knock knock.".to_string(), alice());
+        let second = store.submit("Who's there?".to_string(), alice());
+        assert_eq!(store.pending_from(1), 2);
+        assert!(store.vote(first, 2, true).is_none());
+
+        assert!(store.moderate(first, true).is_some());
+        assert!(store.moderate(first, false).is_none());
+        assert!(store.moderate(second, true).is_some());
+        assert_eq!(store.pending().len(), 0);
+
+        store.vote(first, 2, true);
+        store.vote(first, 3, false);
+        store.vote(second, 2, true);
+        store.vote(second, 3, true);
+        let joke = store.vote(second, 3, true).unwrap();
+        assert_eq!((joke.upvotes(), joke.downvotes()), (1, 0));
+        store.vote(second, 4, true);
+
+        let top: Vec<_> = store.top(5).iter().map(|j| j.id).collect();
+        assert_eq!(top, [second, first]);
+    }
+}
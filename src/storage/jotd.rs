@@ -0,0 +1,171 @@
+// src/storage/jotd.rs
+
+//! Joke-of-the-day subscriptions and the daily jokes already told.
+
+use chrono::{DateTime, Duration, NaiveDate, NaiveTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// How late a joke of the day may still go out, e.g. after a restart.
+const GRACE: Duration = Duration::hours(1);
+
+/// Days a joke of the day is remembered, and so can't be told again.
+pub const REPEAT_WINDOW_DAYS: u64 = 30;
+
+/// A chat's daily joke.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JotdSubscription {
+    pub chat_id: i64,
+    /// Delivery time in UTC.
+    pub time: NaiveTime,
+    /// UTC date the last joke sent was scheduled for.
+    #[serde(default)]
+    pub last_sent: Option<NaiveDate>,
+}
+
+impl JotdSubscription {
+    /// The date of the joke that's due, if any: its time passed within the
+    /// last hour, yesterday's when that was just before midnight, and it
+    /// hasn't been sent yet.
+    pub fn due_date(&self, now: DateTime<Utc>) -> Option<NaiveDate> {
+        let now = now.naive_utc();
+        [Some(now.date()), now.date().pred_opt()]
+            .into_iter()
+            .flatten()
+            .find(|date| {
+                let scheduled = date.and_time(self.time);
+                now >= scheduled && now - scheduled < GRACE
+            })
+            .filter(|&date| self.last_sent.is_none_or(|sent| sent < date))
+    }
+}
+
+/// The joke told on one day.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DailyJoke {
+    pub date: NaiveDate,
+    pub text: String,
+}
+
+/// Every subscription and the recent jokes of the day.
+///
+/// All chats get the same joke each day, so keeping the shared history free
+/// of repeats keeps every chat's free of them too.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct JokeOfTheDay {
+    subscriptions: Vec<JotdSubscription>,
+    history: Vec<DailyJoke>,
+}
+
+impl JokeOfTheDay {
+    /// Subscribes the chat, or moves its delivery time. Returns whether it
+    /// was already subscribed.
+    pub fn subscribe(&mut self, subscription: JotdSubscription) -> bool {
+        let replaced = self.unsubscribe(subscription.chat_id);
+        self.subscriptions.push(subscription);
+        replaced
+    }
+
+    /// Returns whether the chat was subscribed.
+    pub fn unsubscribe(&mut self, chat_id: i64) -> bool {
+        let before = self.subscriptions.len();
+        self.subscriptions.retain(|s| s.chat_id != chat_id);
+        self.subscriptions.len() != before
+    }
+
+    pub fn get(&self, chat_id: i64) -> Option<&JotdSubscription> {
+        self.subscriptions.iter().find(|s| s.chat_id == chat_id)
+    }
+
+    /// Subscriptions with a joke due, each with the date of that joke.
+    pub fn due(&self, now: DateTime<Utc>) -> Vec<(JotdSubscription, NaiveDate)> {
+        self.subscriptions
+            .iter()
+            .filter_map(|s| Some((s.clone(), s.due_date(now)?)))
+            .collect()
+    }
+
+    pub fn mark_sent(&mut self, chat_id: i64, date: NaiveDate) {
+        if let Some(subscription) = self.subscriptions.iter_mut().find(|s| s.chat_id == chat_id) {
+            subscription.last_sent = Some(date);
+        }
+    }
+
+    /// The joke picked for `date`, if any yet.
+    pub fn joke_on(&self, date: NaiveDate) -> Option<&str> {
+        self.history
+            .iter()
+            .find(|j| j.date == date)
+            .map(|j| j.text.as_str())
+    }
+
+    /// Whether `text` was the joke of the day within the repeat window
+    /// before `date`.
+    pub fn is_recent(&self, text: &str, date: NaiveDate) -> bool {
+        let since = date - chrono::Days::new(REPEAT_WINDOW_DAYS);
+        self.history
+            .iter()
+            .any(|j| j.date >= since && j.text.trim().eq_ignore_ascii_case(text.trim()))
+    }
+
+    /// Stores `text` as the joke for `date` and forgets jokes older than the
+    /// repeat window.
+    pub fn record(&mut self, date: NaiveDate, text: String) {
+        let since = date - chrono::Days::new(REPEAT_WINDOW_DAYS);
+        self.history.retain(|j| j.date >= since && j.date != date);
+        self.history.push(DailyJoke { date, text });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 3, day).unwrap()
+    }
+
+    #[test]
+    fn test_due_once_per_day() {
+        let mut subscription = JotdSubscription {
+            chat_id: 10,
+            time: NaiveTime::from_hms_opt(9, 0, 0).unwrap(),
+            last_sent: None,
+        };
+        let at = |h, m| date(5).and_hms_opt(h, m, 0).unwrap().and_utc();
+        assert_eq!(subscription.due_date(at(8, 59)), None);
+        assert_eq!(subscription.due_date(at(9, 30)), Some(date(5)));
+        assert_eq!(subscription.due_date(at(10, 30)), None);
+        subscription.last_sent = Some(date(5));
+        assert_eq!(subscription.due_date(at(9, 30)), None);
+    }
+
+    #[test]
+    fn test_due_across_midnight() {
+        let mut subscription = JotdSubscription {
+            chat_id: 10,
+            time: NaiveTime::from_hms_opt(23, 30, 0).unwrap(),
+            last_sent: Some(date(4)),
+        };
+        let at = |day, h, m| date(day).and_hms_opt(h, m, 0).unwrap().and_utc();
+        assert_eq!(subscription.due_date(at(5, 23, 45)), Some(date(5)));
+        assert_eq!(subscription.due_date(at(6, 0, 20)), Some(date(5)));
+        assert_eq!(subscription.due_date(at(6, 0, 40)), None);
+        subscription.last_sent = Some(date(5));
+        assert_eq!(subscription.due_date(at(6, 0, 20)), None);
+    }
+
+    #[test]
+    fn test_history_window() {
+        let mut jotd = JokeOfTheDay::default();
+        jotd.record(date(1), "Old joke".to_string());
+        jotd.record(date(2), "New joke".to_string());
+        assert_eq!(jotd.joke_on(date(2)), Some("New joke"));
+        assert!(jotd.is_recent("old joke ", date(20)));
+
+        let later = date(1) + chrono::Days::new(REPEAT_WINDOW_DAYS + 1);
+        assert!(!jotd.is_recent("Old joke", later));
+        jotd.record(later, "Newest joke".to_string());
+        assert_eq!(jotd.joke_on(date(1)), None);
+        assert_eq!(jotd.joke_on(date(2)), Some("New joke"));
+    }
+}
@@ -0,0 +1,194 @@
+// src/storage/leaderboard.rs
+
+//! Per-chat scores for the dice games.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use teloxide::types::User;
+
+/// Scores of every player, grouped by chat.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Leaderboards {
+    chats: HashMap<i64, HashMap<String, PlayerStats>>,
+}
+
+/// Lifetime game statistics for one player in one chat.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlayerStats {
+    /// Display name at the time of the last game.
+    pub name: String,
+    /// Lowercase Telegram username, used to match `@mentions`.
+    pub username: Option<String>,
+    pub duels_won: u32,
+    pub duels_lost: u32,
+    pub current_streak: u32,
+    pub best_streak: u32,
+    pub spins: u32,
+    pub jackpots: u32,
+}
+
+/// Identifies a player for scoring purposes.
+///
+/// Players are keyed by user ID when known. Opponents named only by
+/// `@username` are keyed by that name until they play themselves.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Player {
+    key: String,
+    pub name: String,
+    username: Option<String>,
+}
+
+impl Player {
+    pub fn from_user(user: &User) -> Self {
+        let username = user.username.as_ref().map(|u| u.to_lowercase());
+        Self {
+            key: user.id.0.to_string(),
+            name: user
+                .username
+                .as_ref()
+                .map(|u| format!("@{}", u))
+                .unwrap_or_else(|| user.first_name.clone()),
+            username,
+        }
+    }
+
+    pub fn from_username(username: &str) -> Self {
+        let username = username.trim_start_matches('@');
+        Self {
+            key: format!("@{}", username.to_lowercase()),
+            name: format!("@{}", username),
+            username: Some(username.to_lowercase()),
+        }
+    }
+
+    /// Two players are the same if they share a key or a username.
+    pub fn is_same(&self, other: &Player) -> bool {
+        self.key == other.key || (self.username.is_some() && self.username == other.username)
+    }
+}
+
+impl Leaderboards {
+    /// Credits a duel win to `winner` and a loss to `loser`.
+    pub fn record_duel(&mut self, chat_id: i64, winner: &Player, loser: &Player) {
+        let stats = self.entry(chat_id, winner);
+        stats.duels_won += 1;
+        stats.current_streak += 1;
+        stats.best_streak = stats.best_streak.max(stats.current_streak);
+
+        let stats = self.entry(chat_id, loser);
+        stats.duels_lost += 1;
+        stats.current_streak = 0;
+    }
+
+    /// Records a 🎰 spin and whether it hit the jackpot.
+    pub fn record_spin(&mut self, chat_id: i64, player: &Player, jackpot: bool) {
+        let stats = self.entry(chat_id, player);
+        stats.spins += 1;
+        if jackpot {
+            stats.jackpots += 1;
+        }
+    }
+
+    /// Players of a chat ordered by duel wins, then fewest losses, then jackpots.
+    pub fn ranking(&self, chat_id: i64) -> Vec<PlayerStats> {
+        let mut players: Vec<PlayerStats> = self
+            .chats
+            .get(&chat_id)
+            .map(|board| board.values().cloned().collect())
+            .unwrap_or_default();
+
+        players.sort_by(|a, b| {
+            b.duels_won
+                .cmp(&a.duels_won)
+                .then(a.duels_lost.cmp(&b.duels_lost))
+                .then(b.jackpots.cmp(&a.jackpots))
+                .then(a.name.cmp(&b.name))
+        });
+        players
+    }
+
+    /// Returns the stats for `player`, folding in any entry that was
+    /// recorded under their `@username` before their user ID was known.
+    fn entry(&mut self, chat_id: i64, player: &Player) -> &mut PlayerStats {
+        let board = self.chats.entry(chat_id).or_default();
+
+        if let Some(username) = &player.username {
+            let alias = format!("@{}", username);
+            if alias != player.key {
+                if let Some(previous) = board.remove(&alias) {
+                    let stats = board.entry(player.key.clone()).or_default();
+                    merge(stats, previous);
+                }
+            }
+        }
+
+        let stats = board.entry(player.key.clone()).or_default();
+        stats.name = player.name.clone();
+        stats.username = player.username.clone();
+        stats
+    }
+}
+
+fn merge(into: &mut PlayerStats, from: PlayerStats) {
+    into.duels_won += from.duels_won;
+    into.duels_lost += from.duels_lost;
+    into.best_streak = into.best_streak.max(from.best_streak);
+    into.spins += from.spins;
+    into.jackpots += from.jackpots;
+    if into.current_streak == 0 {
+        into.current_streak = from.current_streak;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use teloxide::types::UserId;
+
+    fn user(id: u64, username: &str) -> User {
+        User {
+            id: UserId(id),
+            is_bot: false,
+            first_name: username.to_string(),
+            last_name: None,
+            username: Some(username.to_string()),
+            language_code: None,
+            is_premium: false,
+            added_to_attachment_menu: false,
+        }
+    }
+
+    #[test]
+    fn test_streaks_and_ranking() {
+        let mut boards = Leaderboards::default();
+        let alice = Player::from_user(&user(1, "alice"));
+        let bob = Player::from_user(&user(2, "bob"));
+
+        boards.record_duel(10, &alice, &bob);
+        boards.record_duel(10, &alice, &bob);
+        boards.record_duel(10, &bob, &alice);
+
+        let ranking = boards.ranking(10);
+        assert_eq!(ranking[0].name, "@alice");
+        assert_eq!(ranking[0].duels_won, 2);
+        assert_eq!(ranking[0].best_streak, 2);
+        assert_eq!(ranking[0].current_streak, 0);
+        assert_eq!(ranking[1].current_streak, 1);
+        assert!(boards.ranking(11).is_empty());
+    }
+
+    #[test]
+    fn test_mentioned_player_merges_into_user() {
+        let mut boards = Leaderboards::default();
+        let alice = Player::from_user(&user(1, "alice"));
+        let mentioned_bob = Player::from_username("@Bob");
+
+        boards.record_duel(10, &mentioned_bob, &alice);
+        boards.record_spin(10, &Player::from_user(&user(2, "bob")), true);
+
+        let ranking = boards.ranking(10);
+        assert_eq!(ranking.len(), 2);
+        let bob = ranking.iter().find(|p| p.name == "@bob").unwrap();
+        assert_eq!((bob.duels_won, bob.spins, bob.jackpots), (1, 1, 1));
+    }
+}
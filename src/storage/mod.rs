@@ -0,0 +1,219 @@
+// src/storage/mod.rs
+
+//! File-backed persistence for state that must survive restarts.
+//!
+//! Each store is a small JSON document guarded by an async mutex. Writes go to
+//! a temporary file first and are renamed into place, so a crash mid-write
+//! never leaves a truncated document behind.
+
+mod alerts;
+mod api_usage;
+mod fx_alerts;
+mod joke_prefs;
+mod jokes;
+mod jotd;
+mod leaderboard;
+mod weather_alerts;
+mod weather_prefs;
+mod weather_subs;
+
+pub use alerts::{Alert, AlertOwner, AlertStore};
+pub use api_usage::{ApiUsage, PeriodUsage};
+pub use fx_alerts::{Direction, FxAlert, FxAlerts};
+pub use joke_prefs::{ChatJokePrefs, JokeFlag, JokePrefs};
+pub use jokes::{JokeStore, StoredJoke, Submitter};
+pub use jotd::{JokeOfTheDay, JotdSubscription, REPEAT_WINDOW_DAYS};
+pub use leaderboard::{Leaderboards, Player};
+pub use weather_alerts::{Metric, WeatherAlert, WeatherAlerts, WeatherCondition};
+pub use weather_prefs::{Units, UserPrefs, WeatherPrefs};
+pub use weather_subs::{WeatherSubscription, WeatherSubscriptions};
+
+use chrono::Utc;
+use log::warn;
+use serde::{Serialize, de::DeserializeOwned};
+use std::collections::HashSet;
+use std::io;
+use std::path::{Path, PathBuf};
+use tokio::sync::Mutex;
+
+/// A single JSON document loaded into memory and persisted on every update.
+pub struct JsonStore<T> {
+    path: PathBuf,
+    data: Mutex<T>,
+}
+
+impl<T> JsonStore<T>
+where
+    T: Serialize + DeserializeOwned + Default,
+{
+    /// Loads the document at `path`, starting empty if it doesn't exist yet.
+    ///
+    /// A corrupt document is moved aside to `*.corrupt-<timestamp>` and
+    /// replaced by an empty one, so the next update can't overwrite the
+    /// original data.
+    ///
+    /// # Errors
+    ///
+    /// Returns the I/O error if the document exists but can't be read
+    /// (permissions, a directory in its place), or is corrupt and can't be
+    /// moved aside, rather than starting empty and replacing it on the next
+    /// update.
+    pub fn open(path: impl Into<PathBuf>) -> io::Result<Self> {
+        Self::open_with(path, |_| {})
+    }
+
+    /// Like [`Self::open`], then lets `prepare` adjust the document, e.g. to
+    /// seed defaults. The change is written out with the next update.
+    pub fn open_with(path: impl Into<PathBuf>, prepare: impl FnOnce(&mut T)) -> io::Result<Self> {
+        let path = path.into();
+        let context = |what: &str, err: io::Error| {
+            io::Error::new(
+                err.kind(),
+                format!("{} store {}: {}", what, path.display(), err),
+            )
+        };
+        let mut data = match std::fs::read(&path) {
+            Ok(bytes) => match serde_json::from_slice(&bytes) {
+                Ok(data) => data,
+                Err(err) => {
+                    let stamp = Utc::now().format("%Y%m%d-%H%M%S%3f");
+                    let aside = path.with_extension(format!("json.corrupt-{}", stamp));
+                    std::fs::rename(&path, &aside)
+                        .map_err(|e| context("Failed to move aside corrupt", e))?;
+                    warn!(
+                        "Moved unreadable store {} to {}: {}",
+                        path.display(),
+                        aside.display(),
+                        err
+                    );
+                    T::default()
+                }
+            },
+            Err(err) if err.kind() == io::ErrorKind::NotFound => T::default(),
+            Err(err) => return Err(context("Failed to read", err)),
+        };
+
+        prepare(&mut data);
+
+        Ok(Self {
+            path,
+            data: Mutex::new(data),
+        })
+    }
+
+    /// Runs `f` against a shared view of the document.
+    pub async fn read<R>(&self, f: impl FnOnce(&T) -> R) -> R {
+        f(&*self.data.lock().await)
+    }
+
+    /// Mutates the document with `f` and writes it back to disk.
+    ///
+    /// # Errors
+    ///
+    /// Returns the I/O error if the document could not be persisted. The
+    /// in-memory change is kept so the bot keeps working on a read-only disk.
+    pub async fn update<R>(&self, f: impl FnOnce(&mut T) -> R) -> io::Result<R> {
+        let mut data = self.data.lock().await;
+        let result = f(&mut data);
+        let bytes = serde_json::to_vec_pretty(&*data).map_err(io::Error::other)?;
+        write_atomically(&self.path, &bytes).await?;
+        Ok(result)
+    }
+}
+
+async fn write_atomically(path: &Path, bytes: &[u8]) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    let tmp = path.with_extension("json.tmp");
+    tokio::fs::write(&tmp, bytes).await?;
+    tokio::fs::rename(&tmp, path).await
+}
+
+/// All persistent stores used by command handlers and services.
+pub struct Storage {
+    pub leaderboards: JsonStore<Leaderboards>,
+    pub api_usage: JsonStore<ApiUsage>,
+    pub fx_alerts: JsonStore<FxAlerts>,
+    pub weather_prefs: JsonStore<WeatherPrefs>,
+    pub weather_subs: JsonStore<WeatherSubscriptions>,
+    pub weather_alerts: JsonStore<WeatherAlerts>,
+    pub joke_prefs: JsonStore<JokePrefs>,
+    pub jokes: JsonStore<JokeStore>,
+    pub jotd: JsonStore<JokeOfTheDay>,
+    /// Held while the day's joke is being picked, so concurrent callers
+    /// don't each fetch and record a different one.
+    pub jotd_picking: Mutex<()>,
+    /// Duel challenges still waiting for an answer, by chat and message ID.
+    /// Answering one takes it out first, so two taps can't both settle it.
+    pub pending_duels: Mutex<HashSet<(i64, i32)>>,
+}
+
+impl Storage {
+    /// Opens every store under `dir`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first store that fails to open; see [`JsonStore::open`].
+    pub fn open(dir: impl AsRef<Path>) -> io::Result<Self> {
+        let dir = dir.as_ref();
+        Ok(Self {
+            leaderboards: JsonStore::open(dir.join("leaderboards.json"))?,
+            api_usage: JsonStore::open(dir.join("api_usage.json"))?,
+            fx_alerts: JsonStore::open(dir.join("fx_alerts.json"))?,
+            weather_prefs: JsonStore::open(dir.join("weather_prefs.json"))?,
+            weather_subs: JsonStore::open(dir.join("weather_subs.json"))?,
+            weather_alerts: JsonStore::open(dir.join("weather_alerts.json"))?,
+            joke_prefs: JsonStore::open(dir.join("joke_prefs.json"))?,
+            jokes: JsonStore::open_with(dir.join("jokes.json"), JokeStore::seed)?,
+            jotd: JsonStore::open(dir.join("jotd.json"))?,
+            jotd_picking: Mutex::new(()),
+            pending_duels: Mutex::new(HashSet::new()),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_corrupt_store_is_moved_aside() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("leaderboards.json");
+        let corrupt = |text: &str| {
+            std::fs::write(&path, text).unwrap();
+            // Backups are named to the millisecond.
+            std::thread::sleep(std::time::Duration::from_millis(5));
+            JsonStore::<Leaderboards>::open(&path).unwrap()
+        };
+
+        corrupt("{ not json");
+        let store = corrupt("{ still not json");
+        store.update(|_| ()).await.unwrap();
+
+        let mut backups: Vec<String> = std::fs::read_dir(dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .filter(|name| name.starts_with("leaderboards.json.corrupt-"))
+            .collect();
+        backups.sort();
+        let contents: Vec<String> = backups
+            .iter()
+            .map(|name| std::fs::read_to_string(dir.path().join(name)).unwrap())
+            .collect();
+        assert_eq!(contents, ["{ not json", "{ still not json"]);
+        assert!(serde_json::from_slice::<Leaderboards>(&std::fs::read(&path).unwrap()).is_ok());
+    }
+
+    #[test]
+    fn test_unreadable_store_is_not_replaced() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("leaderboards.json");
+        std::fs::create_dir(&path).unwrap();
+
+        let err = JsonStore::<Leaderboards>::open(&path).err().unwrap();
+        assert!(err.to_string().starts_with("Failed to read store"));
+        assert!(path.is_dir());
+    }
+}
@@ -0,0 +1,140 @@
+// src/storage/weather_alerts.rs
+
+//! Severe-weather alerts watching a city's forecast for chats.
+
+use super::Units;
+use super::alerts::{Alert, AlertOwner, AlertStore};
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+/// Forecast reading an alert condition watches.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Metric {
+    /// Sustained wind speed.
+    Wind,
+    /// Wind gusts.
+    Gust,
+    /// Chance of rain in percent.
+    Rain,
+}
+
+impl Metric {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Wind => "wind",
+            Self::Gust => "gust",
+            Self::Rain => "rain",
+        }
+    }
+}
+
+/// A single `metric > threshold` rule, e.g. `wind>50`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WeatherCondition {
+    pub metric: Metric,
+    pub threshold: u32,
+}
+
+impl WeatherCondition {
+    pub fn is_met(&self, reading: u32) -> bool {
+        reading > self.threshold
+    }
+}
+
+/// A standing alert on a city's forecast. It stays active until cancelled
+/// and reports each event once, however long it lasts.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WeatherAlert {
+    #[serde(flatten)]
+    pub owner: AlertOwner,
+    pub city: String,
+    /// Units wind thresholds are given in, from the creator's settings.
+    #[serde(default)]
+    pub units: Units,
+    pub conditions: Vec<WeatherCondition>,
+    /// Events already reported and the local time each is forecast to end.
+    #[serde(default)]
+    pub reported: Vec<(Metric, NaiveDateTime)>,
+}
+
+impl WeatherAlert {
+    /// Whether an event of `metric` starting at `from` continues one that
+    /// was already reported.
+    pub fn is_reported(&self, metric: Metric, from: NaiveDateTime) -> bool {
+        self.reported
+            .iter()
+            .any(|&(m, until)| m == metric && from <= until)
+    }
+
+    /// Records the events in the current forecast, as `(metric, from,
+    /// until)`, extending those already reported, and forgets events that
+    /// ended by `now` so the next one is reported afresh.
+    pub fn record(
+        &mut self,
+        events: &[(Metric, NaiveDateTime, NaiveDateTime)],
+        now: NaiveDateTime,
+    ) {
+        for &(metric, from, until) in events {
+            match self
+                .reported
+                .iter_mut()
+                .find(|(m, end)| *m == metric && from <= *end)
+            {
+                Some((_, end)) => *end = (*end).max(until),
+                None => self.reported.push((metric, until)),
+            }
+        }
+        self.reported.retain(|&(_, until)| until > now);
+    }
+}
+
+impl Alert for WeatherAlert {
+    fn owner(&self) -> &AlertOwner {
+        &self.owner
+    }
+
+    fn owner_mut(&mut self) -> &mut AlertOwner {
+        &mut self.owner
+    }
+}
+
+/// All severe-weather alerts across chats.
+pub type WeatherAlerts = AlertStore<WeatherAlert>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn test_events_are_reported_until_they_end() {
+        let mut alert = WeatherAlert {
+            owner: AlertOwner::new(10, Some(1)),
+            city: "Lima".to_string(),
+            units: Units::Metric,
+            conditions: Vec::new(),
+            reported: Vec::new(),
+        };
+        let at = |d, h| {
+            NaiveDate::from_ymd_opt(2025, 1, d)
+                .unwrap()
+                .and_hms_opt(h, 0, 0)
+                .unwrap()
+        };
+
+        // A storm from 21:00 to 03:00 the next day.
+        alert.record(&[(Metric::Wind, at(30, 21), at(31, 3))], at(30, 20));
+        assert!(alert.is_reported(Metric::Wind, at(30, 21)));
+        assert!(!alert.is_reported(Metric::Rain, at(30, 21)));
+
+        // Past midnight the forecast extends it; still the same event.
+        assert!(alert.is_reported(Metric::Wind, at(31, 0)));
+        alert.record(&[(Metric::Wind, at(31, 0), at(31, 6))], at(31, 1));
+        assert!(alert.is_reported(Metric::Wind, at(31, 6)));
+
+        // Once it has ended, a new one is reported again.
+        alert.record(&[], at(31, 7));
+        assert!(!alert.is_reported(Metric::Wind, at(31, 12)));
+    }
+}
@@ -0,0 +1,72 @@
+// src/storage/weather_prefs.rs
+
+//! Per-user weather settings: a default city and the preferred units.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Measurement system used when showing weather.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Units {
+    /// °C, km/h, hPa and km.
+    #[default]
+    Metric,
+    /// °F, mph, inHg and miles.
+    Imperial,
+}
+
+/// One user's saved settings.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UserPrefs {
+    /// City used when `/weather` or `/forecast` is sent without one.
+    pub city: Option<String>,
+    #[serde(default)]
+    pub units: Units,
+}
+
+/// Settings of every user who changed the defaults, keyed by user ID.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct WeatherPrefs {
+    users: HashMap<u64, UserPrefs>,
+}
+
+impl WeatherPrefs {
+    /// The user's settings, or the defaults if they never saved any.
+    pub fn get(&self, user_id: u64) -> UserPrefs {
+        self.users.get(&user_id).cloned().unwrap_or_default()
+    }
+
+    pub fn set_city(&mut self, user_id: u64, city: Option<String>) {
+        self.users.entry(user_id).or_default().city = city;
+    }
+
+    pub fn set_units(&mut self, user_id: u64, units: Units) {
+        self.users.entry(user_id).or_default().units = units;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_defaults_and_updates() {
+        let mut prefs = WeatherPrefs::default();
+        assert_eq!(prefs.get(1), UserPrefs::default());
+
+        prefs.set_city(1, Some("Lima".to_string()));
+        prefs.set_units(1, Units::Imperial);
+        assert_eq!(
+            prefs.get(1),
+            UserPrefs {
+                city: Some("Lima".to_string()),
+                units: Units::Imperial
+            }
+        );
+        assert_eq!(prefs.get(2).units, Units::Metric);
+
+        let json = serde_json::to_string(&prefs).unwrap();
+        assert!(json.contains(r#""units":"imperial""#));
+    }
+}
@@ -0,0 +1,202 @@
+// src/storage/weather_subs.rs
+
+//! Daily weather digests chats have subscribed to.
+
+use chrono::{DateTime, Duration, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// How late a digest may still go out, e.g. after a restart.
+const GRACE: Duration = Duration::hours(1);
+
+/// A chat's daily summary for one city.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WeatherSubscription {
+    pub chat_id: i64,
+    /// Who subscribed; the digest uses their units.
+    pub user_id: Option<u64>,
+    pub city: String,
+    /// Delivery time in the city's local time.
+    pub time: NaiveTime,
+    /// The city's offset from UTC in minutes, refreshed with every digest.
+    pub utc_offset: i32,
+    /// Local date of the last digest sent.
+    #[serde(default)]
+    pub last_sent: Option<NaiveDate>,
+}
+
+impl WeatherSubscription {
+    /// Current date and time in the subscribed city.
+    pub fn local_now(&self, now: DateTime<Utc>) -> NaiveDateTime {
+        let offset = FixedOffset::east_opt(self.utc_offset * 60)
+            .unwrap_or_else(|| FixedOffset::east_opt(0).unwrap());
+        now.with_timezone(&offset).naive_local()
+    }
+
+    /// The local date of the digest that's due, if any: its time passed
+    /// within the last hour, yesterday's when that was just before
+    /// midnight, and it hasn't been sent yet.
+    pub fn due_date(&self, now: DateTime<Utc>) -> Option<NaiveDate> {
+        let local = self.local_now(now);
+        [Some(local.date()), local.date().pred_opt()]
+            .into_iter()
+            .flatten()
+            .find(|date| {
+                let scheduled = date.and_time(self.time);
+                local >= scheduled && local - scheduled < GRACE
+            })
+            .filter(|&date| self.last_sent.is_none_or(|sent| sent < date))
+    }
+
+    fn matches(&self, chat_id: i64, city: &str) -> bool {
+        self.chat_id == chat_id && self.city.eq_ignore_ascii_case(city)
+    }
+}
+
+/// All digest subscriptions across chats.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct WeatherSubscriptions {
+    subscriptions: Vec<WeatherSubscription>,
+}
+
+impl WeatherSubscriptions {
+    /// Adds a subscription, replacing the chat's existing one for the same
+    /// city. Returns whether one was replaced.
+    pub fn upsert(&mut self, subscription: WeatherSubscription) -> bool {
+        match self
+            .subscriptions
+            .iter_mut()
+            .find(|s| s.matches(subscription.chat_id, &subscription.city))
+        {
+            Some(existing) => {
+                *existing = subscription;
+                true
+            }
+            None => {
+                self.subscriptions.push(subscription);
+                false
+            }
+        }
+    }
+
+    /// Removes the chat's subscription for `city`, or all of them if no city
+    /// is given, skipping those `allowed` refuses. Returns how many were
+    /// removed and how many were skipped.
+    pub fn remove(
+        &mut self,
+        chat_id: i64,
+        city: Option<&str>,
+        allowed: impl Fn(&WeatherSubscription) -> bool,
+    ) -> (usize, usize) {
+        let (mut removed, mut refused) = (0, 0);
+        self.subscriptions.retain(|s| {
+            let selected = match city {
+                Some(city) => s.matches(chat_id, city),
+                None => s.chat_id == chat_id,
+            };
+            if !selected {
+                true
+            } else if allowed(s) {
+                removed += 1;
+                false
+            } else {
+                refused += 1;
+                true
+            }
+        });
+        (removed, refused)
+    }
+
+    /// Records a delivered digest and the city's latest UTC offset.
+    pub fn mark_sent(&mut self, chat_id: i64, city: &str, date: NaiveDate, utc_offset: i32) {
+        if let Some(subscription) = self
+            .subscriptions
+            .iter_mut()
+            .find(|s| s.matches(chat_id, city))
+        {
+            subscription.last_sent = Some(date);
+            subscription.utc_offset = utc_offset;
+        }
+    }
+
+    pub fn in_chat(&self, chat_id: i64) -> Vec<WeatherSubscription> {
+        self.subscriptions
+            .iter()
+            .filter(|s| s.chat_id == chat_id)
+            .cloned()
+            .collect()
+    }
+
+    pub fn all(&self) -> &[WeatherSubscription] {
+        &self.subscriptions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn subscription(chat_id: i64, city: &str) -> WeatherSubscription {
+        WeatherSubscription {
+            chat_id,
+            user_id: Some(1),
+            city: city.to_string(),
+            time: NaiveTime::from_hms_opt(7, 30, 0).unwrap(),
+            utc_offset: -5 * 60,
+            last_sent: None,
+        }
+    }
+
+    #[test]
+    fn test_upsert_and_remove() {
+        let mut subs = WeatherSubscriptions::default();
+        assert!(!subs.upsert(subscription(10, "Lima")));
+        assert!(subs.upsert(subscription(10, "lima")));
+        subs.upsert(subscription(10, "Cusco"));
+        subs.upsert(subscription(20, "Lima"));
+        assert_eq!(subs.in_chat(10).len(), 2);
+
+        subs.upsert(WeatherSubscription {
+            user_id: Some(2),
+            ..subscription(10, "Arequipa")
+        });
+
+        assert_eq!(subs.remove(10, Some("LIMA"), |_| true), (1, 0));
+        let mine = |s: &WeatherSubscription| s.user_id == Some(1);
+        assert_eq!(subs.remove(10, None, mine), (1, 1));
+        assert_eq!(subs.in_chat(10)[0].city, "Arequipa");
+        assert_eq!(subs.all().len(), 2);
+    }
+
+    #[test]
+    fn test_due_in_local_time() {
+        // 12:40 UTC is 07:40 in Lima (UTC-5).
+        let now = DateTime::parse_from_rfc3339("2025-01-31T12:40:00Z")
+            .unwrap()
+            .to_utc();
+        let today = NaiveDate::from_ymd_opt(2025, 1, 31).unwrap();
+        let mut sub = subscription(10, "Lima");
+        assert_eq!(sub.due_date(now), Some(today));
+        assert_eq!(sub.due_date(now - Duration::minutes(15)), None);
+        assert_eq!(sub.due_date(now + Duration::hours(1)), None);
+
+        sub.last_sent = Some(today);
+        assert_eq!(sub.due_date(now), None);
+    }
+
+    #[test]
+    fn test_due_across_midnight() {
+        let day = |d| NaiveDate::from_ymd_opt(2025, 1, d).unwrap();
+        // Local times in Lima (UTC-5).
+        let at = |d, h, m| (day(d).and_hms_opt(h, m, 0).unwrap() + Duration::hours(5)).and_utc();
+        let mut sub = WeatherSubscription {
+            time: NaiveTime::from_hms_opt(23, 30, 0).unwrap(),
+            last_sent: Some(day(4)),
+            ..subscription(10, "Lima")
+        };
+        assert_eq!(sub.due_date(at(5, 23, 45)), Some(day(5)));
+        assert_eq!(sub.due_date(at(6, 0, 20)), Some(day(5)));
+        assert_eq!(sub.due_date(at(6, 0, 40)), None);
+        sub.last_sent = Some(day(5));
+        assert_eq!(sub.due_date(at(6, 0, 20)), None);
+    }
+}